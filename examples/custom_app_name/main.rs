@@ -16,7 +16,6 @@
 mod constants;
 
 use bevy::prelude::*;
-use bevy::window::Monitor;
 use bevy::window::PrimaryWindow;
 use bevy_window_manager::CurrentMonitor;
 use bevy_window_manager::WindowManagerPlugin;
@@ -67,17 +66,13 @@ fn setup(mut commands: Commands) {
 
 fn update_info_text(
     window_query: Single<(&Window, &CurrentMonitor), With<PrimaryWindow>>,
-    bevy_monitors: Query<&Monitor>,
     mut text: Single<&mut Text, With<InfoText>>,
 ) {
     let (window, current_monitor) = *window_query;
     let effective_window_mode = current_monitor.effective_window_mode;
 
-    // Find refresh rate from Bevy's `Monitor` by matching position.
-    let refresh_rate = bevy_monitors
-        .iter()
-        .find(|monitor| monitor.physical_position == current_monitor.physical_position)
-        .and_then(|monitor| monitor.refresh_rate_millihertz)
+    let refresh_rate = current_monitor
+        .refresh_rate_millihertz
         .map(|refresh_rate| refresh_rate / MILLIHERTZ_PER_HERTZ);
 
     let refresh_display =
@@ -1,5 +1,4 @@
 use bevy::prelude::*;
-use bevy::window::Monitor;
 use bevy::window::PrimaryWindow;
 use bevy::window::WindowPosition;
 use bevy_kana::ToU32;
@@ -36,7 +35,6 @@ pub(crate) fn update_primary_display(
     primary_display: Single<Entity, With<PrimaryDisplay>>,
     window_query: Single<(Entity, &Window, &CurrentMonitor), With<PrimaryWindow>>,
     monitors: Res<Monitors>,
-    bevy_monitors: Query<(Entity, &Monitor)>,
     mut selected_video_modes: ResMut<SelectedVideoModes>,
     managed_window_persistence: Res<ManagedWindowPersistence>,
     managed_query: Query<(&Window, &ManagedWindow, Option<&CurrentMonitor>)>,
@@ -50,10 +48,9 @@ pub(crate) fn update_primary_display(
     let cached_restored_state = restored_states.by_entity.get(&window_entity);
     let cached_mismatch_state = mismatch_states.by_entity.get(&window_entity);
 
-    let (video_modes, refresh_rate) =
-        input::get_video_modes_for_monitor(&bevy_monitors, current_monitor);
-    let refresh_display = input::format_refresh_rate(window, refresh_rate);
-    let active_mode_idx = input::find_active_video_mode_index(window, &video_modes);
+    let video_modes = monitors.video_modes(current_monitor.index);
+    let refresh_display = input::format_refresh_rate(window, current_monitor);
+    let active_mode_idx = input::find_active_video_mode_index(window, video_modes);
     input::sync_selected_to_active(
         window,
         current_monitor,
@@ -62,7 +59,7 @@ pub(crate) fn update_primary_display(
     );
     let selected_idx = selected_video_modes.get(current_monitor.index);
     let video_modes_display =
-        input::build_video_modes_display(&video_modes, selected_idx, active_mode_idx);
+        input::build_video_modes_display(video_modes, selected_idx, active_mode_idx);
 
     let text_font = TextFont {
         font_size: FontSize::Px(FONT_SIZE),
@@ -121,8 +118,8 @@ pub(crate) fn update_primary_display(
             let mut managed_lines = Vec::new();
             for (managed_window, managed, current_monitor) in &managed_query {
                 let monitor_info = current_monitor.map_or_else(
-                    || *monitors.first(),
-                    |current_monitor| current_monitor.monitor_info,
+                    || monitors.first(),
+                    |current_monitor| &current_monitor.monitor_info,
                 );
                 let position = match managed_window.position {
                     WindowPosition::At(managed_position) => {
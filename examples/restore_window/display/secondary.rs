@@ -1,5 +1,4 @@
 use bevy::prelude::*;
-use bevy::window::Monitor;
 use bevy_window_manager::CurrentMonitor;
 use bevy_window_manager::ManagedWindow;
 use bevy_window_manager::Monitors;
@@ -31,7 +30,6 @@ pub(crate) fn update_secondary_displays(
     windows: Query<(&Window, Option<&CurrentMonitor>)>,
     managed_query: Query<&ManagedWindow>,
     monitors: Res<Monitors>,
-    bevy_monitors: Query<(Entity, &Monitor)>,
     mut selected_video_modes: ResMut<SelectedVideoModes>,
     restored_states: Res<RestoredStates>,
     mismatch_states: Res<MismatchStates>,
@@ -52,10 +50,9 @@ pub(crate) fn update_secondary_displays(
         let cached_restored_state = restored_states.by_entity.get(&display.0);
         let cached_mismatch_state = mismatch_states.by_entity.get(&display.0);
 
-        let (video_modes, refresh_rate) =
-            input::get_video_modes_for_monitor(&bevy_monitors, &current_monitor);
-        let refresh_display = input::format_refresh_rate(window, refresh_rate);
-        let active_mode_idx = input::find_active_video_mode_index(window, &video_modes);
+        let video_modes = monitors.video_modes(current_monitor.index);
+        let refresh_display = input::format_refresh_rate(window, &current_monitor);
+        let active_mode_idx = input::find_active_video_mode_index(window, video_modes);
         input::sync_selected_to_active(
             window,
             &current_monitor,
@@ -64,7 +61,7 @@ pub(crate) fn update_secondary_displays(
         );
         let selected_idx = selected_video_modes.get(current_monitor.index);
         let video_modes_display =
-            input::build_video_modes_display(&video_modes, selected_idx, active_mode_idx);
+            input::build_video_modes_display(video_modes, selected_idx, active_mode_idx);
 
         let text_font = TextFont {
             font_size: FontSize::Px(FONT_SIZE),
@@ -3,8 +3,6 @@ use std::env::current_exe;
 use std::path::PathBuf;
 
 use bevy::prelude::*;
-use bevy::window::Monitor;
-use bevy::window::VideoMode;
 use bevy::window::VideoModeSelection;
 use bevy::window::WindowMode;
 use bevy_window_manager::CurrentMonitor;
@@ -12,6 +10,7 @@ use bevy_window_manager::ManagedWindow;
 use bevy_window_manager::Monitors;
 #[cfg(target_os = "linux")]
 use bevy_window_manager::Platform;
+use bevy_window_manager::SavedVideoMode;
 use dirs::config_dir;
 
 use super::constants::ACTIVE_VIDEO_MODE_SUFFIX;
@@ -131,8 +130,8 @@ pub(crate) fn resolve_current_monitor(
     window: &Window,
     monitors: &Monitors,
 ) -> CurrentMonitor {
-    current_monitor.copied().unwrap_or_else(|| CurrentMonitor {
-        monitor_info:          *monitors.first(),
+    current_monitor.cloned().unwrap_or_else(|| CurrentMonitor {
+        monitor_info:          monitors.first().clone(),
         effective_window_mode: window.mode,
     })
 }
@@ -141,7 +140,6 @@ pub(crate) fn handle_window_mode_input(
     keys: Res<ButtonInput<KeyCode>>,
     mut windows: Query<(Entity, &mut Window, Option<&CurrentMonitor>)>,
     monitors: Res<Monitors>,
-    bevy_monitors: Query<(Entity, &Monitor)>,
     mut selected_video_modes: ResMut<SelectedVideoModes>,
     restored_states: Res<RestoredStates>,
     mut commands: Commands,
@@ -161,13 +159,7 @@ pub(crate) fn handle_window_mode_input(
         window.mode = current_monitor.effective_window_mode;
     }
 
-    let video_modes: Vec<VideoMode> = bevy_monitors
-        .iter()
-        .find(|(_, bevy_monitor)| {
-            bevy_monitor.physical_position == current_monitor.physical_position
-        })
-        .map(|(_, bevy_monitor)| bevy_monitor.video_modes.clone())
-        .unwrap_or_default();
+    let video_modes = monitors.video_modes(current_monitor.index);
 
     let current_idx = selected_video_modes.get(current_monitor.index);
     if keys.just_pressed(KeyCode::ArrowUp) && current_idx > 0 {
@@ -188,39 +180,21 @@ pub(crate) fn handle_window_mode_input(
     }
 }
 
-pub(crate) fn get_video_modes_for_monitor<'a>(
-    bevy_monitors: &'a Query<(Entity, &Monitor)>,
-    current_monitor: &CurrentMonitor,
-) -> (Vec<&'a VideoMode>, Option<u32>) {
-    bevy_monitors
-        .iter()
-        .find(|(_, bevy_monitor)| {
-            bevy_monitor.physical_position == current_monitor.physical_position
-        })
-        .map(|(_, bevy_monitor)| {
-            (
-                bevy_monitor.video_modes.iter().collect(),
-                bevy_monitor
-                    .refresh_rate_millihertz
-                    .map(|rate| rate / MILLIHERTZ_PER_HERTZ),
-            )
-        })
-        .unwrap_or_default()
-}
-
-pub(crate) fn format_refresh_rate(window: &Window, monitor_refresh: Option<u32>) -> String {
+pub(crate) fn format_refresh_rate(window: &Window, current_monitor: &CurrentMonitor) -> String {
     let active_refresh = match &window.mode {
         WindowMode::Fullscreen(_, VideoModeSelection::Specific(mode)) => {
             Some(mode.refresh_rate_millihertz / MILLIHERTZ_PER_HERTZ)
         },
-        _ => monitor_refresh,
+        _ => current_monitor
+            .refresh_rate_millihertz
+            .map(|rate| rate / MILLIHERTZ_PER_HERTZ),
     };
     active_refresh.map_or_else(|| NOT_AVAILABLE_TEXT.into(), |hz| format!("{hz}Hz"))
 }
 
 pub(crate) fn find_active_video_mode_index(
     window: &Window,
-    video_modes: &[&VideoMode],
+    video_modes: &[SavedVideoMode],
 ) -> Option<usize> {
     match &window.mode {
         WindowMode::Fullscreen(_, VideoModeSelection::Specific(active)) => {
@@ -282,7 +256,7 @@ pub(crate) fn format_monitor_row(
 }
 
 pub(crate) fn build_video_modes_display(
-    video_modes: &[&VideoMode],
+    video_modes: &[SavedVideoMode],
     selected_idx: usize,
     active_mode_idx: Option<usize>,
 ) -> String {
@@ -1,15 +1,13 @@
 use std::fs::remove_file;
 
 use bevy::prelude::*;
-use bevy::window::Monitor;
 use bevy::window::MonitorSelection;
-use bevy::window::VideoMode;
-use bevy::window::VideoModeSelection;
 use bevy::window::WindowMode;
 use bevy_window_manager::CurrentMonitor;
 use bevy_window_manager::ManagedWindow;
 use bevy_window_manager::ManagedWindowPersistence;
 use bevy_window_manager::Monitors;
+use bevy_window_manager::WindowExt;
 
 use super::events::ClearStateAndQuit;
 use super::events::QuitApp;
@@ -47,7 +45,6 @@ pub(crate) fn on_set_exclusive_fullscreen(
     _trigger: On<SetExclusiveFullscreen>,
     mut windows: Query<(&mut Window, Option<&CurrentMonitor>)>,
     monitors: Res<Monitors>,
-    bevy_monitors: Query<(Entity, &Monitor)>,
     selected_video_modes: Res<SelectedVideoModes>,
 ) {
     let Some((mut window, maybe_current_monitor)) =
@@ -57,27 +54,12 @@ pub(crate) fn on_set_exclusive_fullscreen(
     };
     let current_monitor = input::resolve_current_monitor(maybe_current_monitor, &window, &monitors);
 
-    let video_modes: Vec<VideoMode> = bevy_monitors
-        .iter()
-        .find(|(_, bevy_monitor)| {
-            bevy_monitor.physical_position == current_monitor.monitor_info.physical_position
-        })
-        .map(|(_, bevy_monitor)| bevy_monitor.video_modes.clone())
-        .unwrap_or_default();
-
+    let index = current_monitor.monitor_info.index;
+    let video_modes = monitors.video_modes(index);
     let selected_idx = selected_video_modes
-        .get(current_monitor.monitor_info.index)
+        .get(index)
         .min(video_modes.len().saturating_sub(1));
-    let video_mode_selection = video_modes
-        .get(selected_idx)
-        .map_or(VideoModeSelection::Current, |mode| {
-            VideoModeSelection::Specific(*mode)
-        });
-
-    window.mode = WindowMode::Fullscreen(
-        MonitorSelection::Index(current_monitor.monitor_info.index),
-        video_mode_selection,
-    );
+    window.set_exclusive_fullscreen(&monitors, index, video_modes.get(selected_idx));
 }
 
 pub(crate) fn on_toggle_persistence(
@@ -1,5 +1,7 @@
 //! Cross-module constants.
 
+use std::time::Duration;
+
 // managed window naming
 /// First numeric suffix appended to deduplicate a managed window name (e.g. `name-2`).
 pub(crate) const FIRST_DUPLICATE_SUFFIX: u32 = 2;
@@ -14,19 +16,36 @@ pub(crate) const MONITOR_SOURCE_WINIT: &str = "winit";
 // monitor selection
 pub(crate) const PRIMARY_MONITOR_INDEX: usize = 0;
 
+// off-screen recovery
+/// Duration (in seconds) a window must stay off screen at the same position before
+/// `OffScreenRecovery::Enabled` moves it back, so a drag in progress is left alone.
+pub(crate) const OFF_SCREEN_GRACE_SECS: f32 = 1.0;
+
+// opacity
+/// Opacity of a window that is not translucent.
+pub(crate) const FULLY_OPAQUE: f32 = 1.0;
+/// Lowest opacity restore applies, so a bad saved value never hides the window entirely.
+pub(crate) const MIN_RESTORED_OPACITY: f32 = 0.1;
+#[cfg(target_os = "linux")]
+pub(crate) const WINDOW_OPACITY_ATOM_NAME: &[u8] = b"_NET_WM_WINDOW_OPACITY";
+/// Alpha of a fully opaque layered window (`SetLayeredWindowAttributes`).
+#[cfg(target_os = "windows")]
+pub(crate) const LAYERED_WINDOW_MAX_ALPHA: u8 = u8::MAX;
+
 // persistence
 pub(crate) const CURRENT_STATE_VERSION: u8 = 2;
+/// Profile used until another is selected. Stored in the top-level `entries` of the state
+/// file, so files without profiles load as this profile.
+pub(crate) const DEFAULT_PROFILE: &str = "default";
 pub(crate) const PRIMARY_WINDOW_KEY: &str = "primary";
+/// Default time after startup during which window changes are tracked but not written.
+pub(crate) const DEFAULT_STARTUP_SAVE_DELAY: Duration = Duration::from_millis(250);
 pub(crate) const STATE_FILE: &str = "windows.ron";
 
 // platform
 #[cfg(target_os = "linux")]
 pub(crate) const WAYLAND_DISPLAY_ENV_VAR: &str = "WAYLAND_DISPLAY";
 
-// restore strategy
-pub(crate) const RESTORE_STRATEGY_APPLY_UNCHANGED: &str = "ApplyUnchanged";
-pub(crate) const RESTORE_STRATEGY_LOWER_TO_HIGHER: &str = "LowerToHigher";
-
 // scale factor
 /// Fallback scale factor when the monitor cannot be determined.
 pub(crate) const DEFAULT_SCALE_FACTOR: f64 = 1.0;
@@ -41,6 +60,20 @@ pub(crate) const SCALE_FACTOR_EPSILON: f64 = 0.01;
 pub(crate) const SETTLE_STABILITY_SECS: f32 = 0.2;
 /// Maximum total duration (in seconds) to wait for values to stabilize.
 pub(crate) const SETTLE_TIMEOUT_SECS: f32 = 2.0;
+/// Frames a cross-DPI restore waits for `WindowScaleFactorChanged` after its initial move
+/// before applying the size anyway.
+pub(crate) const SCALE_CHANGE_TIMEOUT_FRAMES: u32 = 30;
+
+// snapping
+/// Distance in logical pixels within which `SnapPolicy::Edges` moves a window edge onto
+/// the matching monitor edge.
+pub(crate) const SNAP_EDGE_THRESHOLD: f64 = 8.0;
+
+// stage manager
+#[cfg(target_os = "macos")]
+pub(crate) const STAGE_MANAGER_DEFAULTS_DOMAIN: &str = "com.apple.WindowManager";
+#[cfg(target_os = "macos")]
+pub(crate) const STAGE_MANAGER_ENABLED_KEY: &str = "GlobalEnabled";
 
 // state format
 /// Header comment prepended to the RON file to document the coordinate contract.
@@ -61,12 +94,18 @@ pub(crate) const SUBCLASS_ID: usize = 1;
 #[cfg(all(target_os = "windows", feature = "workaround-winit-4341"))]
 pub(crate) const SUBCLASS_REFERENCE_DATA: usize = 0;
 
+// x11 work area (`_NET_WORKAREA`: x, y, width, height per desktop)
+#[cfg(target_os = "linux")]
+pub(crate) const CURRENT_DESKTOP_ATOM_NAME: &[u8] = b"_NET_CURRENT_DESKTOP";
+#[cfg(target_os = "linux")]
+pub(crate) const WORK_AREA_ATOM_NAME: &[u8] = b"_NET_WORKAREA";
+/// Number of values per desktop in `_NET_WORKAREA` (x, y, width, height).
+#[cfg(target_os = "linux")]
+pub(crate) const WORK_AREA_VALUE_COUNT: u32 = 4;
+
 // x11 frame extents (`_NET_FRAME_EXTENTS`: left, right, top, bottom)
 /// Number of values in `_NET_FRAME_EXTENTS` (left, right, top, bottom).
 #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
 pub(crate) const FRAME_EXTENT_COUNT: u32 = 4;
 #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
 pub(crate) const FRAME_EXTENTS_ATOM_NAME: &[u8] = b"_NET_FRAME_EXTENTS";
-/// Index of the "top" extent in `_NET_FRAME_EXTENTS`.
-#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
-pub(crate) const FRAME_EXTENT_TOP_INDEX: usize = 2;
@@ -0,0 +1,106 @@
+//! Global mouse cursor queries.
+//!
+//! winit only reports the cursor while it is over one of the app's windows, and a window
+//! being restored may not be visible yet, so the global cursor position is queried from
+//! each platform directly. Wayland clients cannot see global coordinates, so there the
+//! cursor position is always unknown.
+
+use bevy::prelude::*;
+#[cfg(target_os = "macos")]
+use bevy_kana::ToI32;
+#[cfg(target_os = "macos")]
+use objc2::MainThreadMarker;
+#[cfg(target_os = "macos")]
+use objc2_app_kit::NSEvent;
+#[cfg(target_os = "macos")]
+use objc2_app_kit::NSScreen;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::POINT;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+#[cfg(target_os = "linux")]
+use x11rb::connection::Connection;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::ConnectionExt;
+#[cfg(target_os = "linux")]
+use x11rb::xcb_ffi::XCBConnection;
+
+use crate::monitors::MonitorInfo;
+use crate::monitors::Monitors;
+use crate::platform::Platform;
+use crate::restore_window_config::RestorePreference;
+
+/// The monitor under the cursor when `restore_preference` asks for it and the cursor
+/// position is known.
+pub(crate) fn preferred_cursor_monitor(
+    restore_preference: RestorePreference,
+    monitors: &Monitors,
+    platform: Platform,
+) -> Option<&MonitorInfo> {
+    if restore_preference != RestorePreference::CursorMonitor {
+        return None;
+    }
+    let Some(physical_cursor_position) = cursor_physical_position(platform) else {
+        debug!("[preferred_cursor_monitor] Cursor position unavailable, using saved monitor");
+        return None;
+    };
+    let monitor_info = monitors.at(physical_cursor_position.x, physical_cursor_position.y);
+    debug!(
+        "[preferred_cursor_monitor] cursor={physical_cursor_position:?} -> monitor={:?}",
+        monitor_info.map(|monitor| monitor.index)
+    );
+    monitor_info
+}
+
+/// Global cursor position in physical pixels, from the X11 root window.
+#[cfg(target_os = "linux")]
+fn cursor_physical_position(platform: Platform) -> Option<IVec2> {
+    if !platform.is_x11() {
+        return None;
+    }
+    let (conn, screen_num) = XCBConnection::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let pointer = conn.query_pointer(root).ok()?.reply().ok()?;
+    Some(IVec2::new(
+        i32::from(pointer.root_x),
+        i32::from(pointer.root_y),
+    ))
+}
+
+/// Global cursor position in physical pixels, from `NSEvent.mouseLocation`.
+///
+/// AppKit reports points with a bottom-left origin on the first screen; the point is
+/// converted with the scale of the screen containing it, matching winit's monitor
+/// positions.
+#[cfg(target_os = "macos")]
+fn cursor_physical_position(_platform: Platform) -> Option<IVec2> {
+    // `NSScreen` is main-thread only.
+    let main_thread_marker = MainThreadMarker::new()?;
+    let screens = NSScreen::screens(main_thread_marker);
+    let main_frame_height = screens.iter().next()?.frame().size.height;
+    let location = NSEvent::mouseLocation();
+
+    screens.iter().find_map(|screen| {
+        let frame = screen.frame();
+        let contains = location.x >= frame.origin.x
+            && location.x < frame.origin.x + frame.size.width
+            && location.y >= frame.origin.y
+            && location.y < frame.origin.y + frame.size.height;
+        contains.then(|| {
+            let scale = screen.backingScaleFactor();
+            IVec2::new(
+                (location.x * scale).round().to_i32(),
+                ((main_frame_height - location.y) * scale).round().to_i32(),
+            )
+        })
+    })
+}
+
+/// Global cursor position in physical pixels, from `GetCursorPos`.
+#[cfg(target_os = "windows")]
+fn cursor_physical_position(_platform: Platform) -> Option<IVec2> {
+    let mut point = POINT::default();
+    // SAFETY: `point` is a valid, writable `POINT`.
+    unsafe { GetCursorPos(&raw mut point) }.ok()?;
+    Some(IVec2::new(point.x, point.y))
+}
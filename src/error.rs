@@ -0,0 +1,56 @@
+//! Error type for state file persistence.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+use std::path::PathBuf;
+
+/// Why reading or writing the state file failed.
+///
+/// Carried by [`WindowStateLoadFailed`](crate::WindowStateLoadFailed) and
+/// [`WindowStateSaveFailed`](crate::WindowStateSaveFailed) so apps can tell a corrupt
+/// state file apart from a missing one (which is not an error) or an unwritable directory.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WindowManagerError {
+    /// Reading, writing, or creating the directory for the state file failed.
+    Io {
+        /// Path that could not be accessed.
+        path:   PathBuf,
+        /// Underlying I/O error.
+        source: io::Error,
+    },
+    /// The state file exists but its contents could not be decoded.
+    Deserialize {
+        /// Path of the state file that failed to decode.
+        path: PathBuf,
+    },
+    /// Window state could not be serialized.
+    Serialize {
+        /// Serializer error message.
+        message: String,
+    },
+}
+
+impl Display for WindowManagerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "I/O error on {}: {source}", path.display()),
+            Self::Deserialize { path } => {
+                write!(f, "could not decode state file {}", path.display())
+            },
+            Self::Serialize { message } => write!(f, "could not serialize state: {message}"),
+        }
+    }
+}
+
+impl Error for WindowManagerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Deserialize { .. } | Self::Serialize { .. } => None,
+        }
+    }
+}
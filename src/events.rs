@@ -1,9 +1,42 @@
 //! Public API events for window restoration.
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use bevy::prelude::*;
 use bevy::window::WindowMode;
 
 use super::WindowKey;
+use super::WindowManagerError;
+
+/// Event fired when a window restore begins, before any position or size is applied.
+///
+/// This is an [`EntityEvent`] triggered on the window entity when the plugin starts
+/// restoring it from saved state. Every restore that starts ends with exactly one
+/// [`WindowRestored`] or [`WindowRestoreMismatch`], so the pair brackets the multi-frame
+/// restore, e.g. to defer layout work until the window has settled.
+///
+/// Fields carry the restore target, with the same meaning as on [`WindowRestored`].
+#[derive(EntityEvent, Debug, Clone, Reflect)]
+pub struct WindowRestoreStarted {
+    /// The window entity this event targets.
+    pub entity:            Entity,
+    /// Identifier for this window (primary or managed name).
+    pub window_key:        WindowKey,
+    /// Target position in physical pixels (None on Wayland).
+    pub physical_position: Option<IVec2>,
+    /// Target position in logical pixels (pre-scale, from the saved state).
+    /// None on Wayland or when the saved state had no position.
+    pub logical_position:  Option<IVec2>,
+    /// Target physical size (content area).
+    pub physical_size:     UVec2,
+    /// Target logical size (content area).
+    pub logical_size:      UVec2,
+    /// Target window mode.
+    pub window_mode:       WindowMode,
+    /// Monitor index the window is being restored to.
+    pub monitor_index:     usize,
+}
 
 /// Event fired when a window restore completes and the window becomes visible.
 ///
@@ -49,6 +82,42 @@ pub struct WindowRestored {
     pub window_mode:       WindowMode,
     /// Monitor index the window was restored to.
     pub monitor_index:     usize,
+    /// Custom icon path saved from [`WindowIconPath`](crate::WindowIconPath), for the app
+    /// to load again. The plugin does not apply icons itself.
+    pub icon_path:         Option<PathBuf>,
+    /// App-defined settings saved from [`WindowExtra`](crate::WindowExtra), for the app to
+    /// apply. Empty when none were saved.
+    pub extra:             BTreeMap<String, String>,
+}
+
+/// Event fired once a restored window's geometry has stayed the same for a whole frame
+/// after [`WindowRestored`] or [`WindowRestoreMismatch`].
+///
+/// `WindowRestored` marks the restore target being reached, but the OS can still deliver a
+/// late resize or move in the frames that follow. Layout that depends on the window's
+/// final size, e.g. a docking layout the app saves itself, can wait for this event
+/// instead so it never lays out against an intermediate size. Fires exactly once per
+/// restore, after the `WindowRestored` or `WindowRestoreMismatch` that ended it.
+///
+/// ```ignore
+/// app.add_observer(|settled: On<GeometrySettled>| {
+///     restore_docking_layout(settled.logical_size);
+/// });
+/// ```
+#[derive(EntityEvent, Debug, Clone, Reflect)]
+pub struct GeometrySettled {
+    /// The window entity this event targets.
+    pub entity:            Entity,
+    /// Identifier for this window (primary or managed name).
+    pub window_key:        WindowKey,
+    /// Final position in physical pixels, read the same way saves read it: from winit on
+    /// macOS and X11, else from `Window.position`. None on Wayland or while the position
+    /// is not known.
+    pub physical_position: Option<IVec2>,
+    /// Final physical size (content area).
+    pub physical_size:     UVec2,
+    /// Final logical size (content area).
+    pub logical_size:      UVec2,
 }
 
 /// Event fired when the actual window state doesn't match what was requested.
@@ -126,3 +195,60 @@ pub struct WindowRestoreMismatch {
     /// Lags behind monitor changes; updates only on winit `ScaleFactorChanged`.
     pub actual_scale:               f64,
 }
+
+/// Event fired when monitors are connected, disconnected, moved, resized, or rescaled
+/// while the app runs.
+///
+/// `added` and `removed` are both empty when only the geometry or scale of connected
+/// monitors changed.
+///
+/// `Monitors` is already updated when this fires, so observers can read the new list
+/// with `Res<Monitors>`:
+/// ```ignore
+/// app.add_observer(|trigger: On<MonitorConfigurationChanged>, monitors: Res<Monitors>| {
+///     let event = trigger.event();
+///     // Relayout for `monitors`, e.g. after `event.removed` took away a display
+/// });
+/// ```
+#[derive(Event, Debug, Clone, Reflect)]
+pub struct MonitorConfigurationChanged {
+    /// Indices in the new [`Monitors`](crate::Monitors) list of monitors that were connected.
+    pub added:   Vec<usize>,
+    /// Indices in the previous monitor list of monitors that were disconnected. They no
+    /// longer exist in [`Monitors`](crate::Monitors).
+    pub removed: Vec<usize>,
+}
+
+/// Event fired when the state file exists but cannot be read or decoded.
+///
+/// A missing state file is the normal first-launch case and does not fire this event.
+/// The plugin logs the failure and continues as if no state had been saved; observe this
+/// event to tell the user their saved layout could not be restored:
+/// ```ignore
+/// app.add_observer(|trigger: On<WindowStateLoadFailed>| {
+///     let event = trigger.event();
+///     // Show a toast with `event.error`
+/// });
+/// ```
+#[derive(Event, Debug)]
+pub struct WindowStateLoadFailed {
+    /// Path of the state file.
+    pub path:  PathBuf,
+    /// Why loading failed.
+    pub error: WindowManagerError,
+}
+
+/// Event fired when writing the state file fails.
+///
+/// The plugin logs the failure and keeps running; the next change retries the save. When
+/// the location is read-only or permission is denied, this fires once and that location is
+/// not written again for the rest of the session, while restoring from the existing file
+/// still works. With mirror paths, each failing location fires separately and the others
+/// keep being written.
+#[derive(Event, Debug)]
+pub struct WindowStateSaveFailed {
+    /// Path of the state file.
+    pub path:  PathBuf,
+    /// Why saving failed.
+    pub error: WindowManagerError,
+}
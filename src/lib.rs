@@ -28,53 +28,216 @@
 //! The plugin automatically hides the window during startup and shows it after positioning
 //! is complete, preventing any visual flash at the default position.
 //!
+//! On first run, when no state is saved for a window, the plugin leaves it alone: the
+//! window opens at the `resolution` (and position) it was created with, and nothing is
+//! restored. Only a [`DefaultPlacement`] other than [`DefaultPlacement::OsDefault`], set
+//! with [`WindowManagerPluginBuilder::default_placement`], moves a fresh primary window,
+//! and only [`DefaultPlacement::ScaledDefault`] changes its startup size.
+//!
+//! An app that spawns its primary window itself after startup, e.g. with
+//! `WindowPlugin { primary_window: None, .. }`, gets the same restore once the window with
+//! [`PrimaryWindow`](bevy::window::PrimaryWindow) appears.
+//!
 //! See the `custom_app_name` example for how to override the `app_name` used in the path
 //! (default is to choose the executable name).
 //!
 //! See the `custom_path` example for how to override the full path to the state file.
+//!
+//! ## Multiple Windows
+//!
+//! The primary window is always saved and restored under the `"primary"` key. Any other
+//! window opts in by adding a [`ManagedWindow`] component with a unique name, which becomes
+//! its key in the state file. Windows without either marker are never persisted, so apps
+//! that only have a primary window keep the single-window behavior with no extra setup.
+//!
+//! ```ignore
+//! commands.spawn((
+//!     Window { title: "Tool Palette".into(), ..default() },
+//!     ManagedWindow { name: "tool_palette".into() },
+//! ));
+//! ```
+//!
+//! [`ManagedWindowPersistence`] controls whether closed windows keep their saved entry.
+//! [`RestoreRelativeTo`] restores a managed window onto whichever monitor another window is
+//! on, e.g. to keep a tool window next to the main window.
+//!
+//! ## Saving and Restoring on Demand
+//!
+//! State is saved automatically whenever a window moves, resizes, or changes mode, and
+//! restored once at startup. [`save_current_window_state`] and [`restore_window_state`]
+//! trigger either step explicitly, e.g. from a "Reset window layout" menu item.
+//!
+//! Both are exclusive systems, so they can also be scheduled directly, e.g. to save the
+//! layout when leaving a state and restore it when entering the state again:
+//!
+//! ```ignore
+//! app.add_systems(OnExit(AppState::InGame), bevy_window_manager::save_current_window_state)
+//!     .add_systems(OnEnter(AppState::InGame), bevy_window_manager::restore_window_state);
+//! ```
+//!
+//! Apps that show a loading screen first can hold the startup restore with
+//! [`RestoreTrigger::Manual`] and apply it with [`trigger_restore`] once their content is
+//! ready.
+//!
+//! [`clear_saved_state`] deletes the state file for a clean slate, and
+//! [`reset_window_layout`] also centers every window on the primary monitor, e.g. to
+//! recover a window stuck off screen. [`undo_window_geometry`] steps the most recently
+//! changed window back to its previous geometry, e.g. after an accidental resize.
+//! [`read_saved_state`] reads the saved layouts without restoring them, e.g. for a layouts
+//! menu.
+//!
+//! The [`Platform`] resource is the windowing backend the plugin detected, so app code can
+//! branch on the same X11 vs Wayland decision the plugin makes instead of checking
+//! `WAYLAND_DISPLAY` itself. [`Platform::detect`] gives the same answer before the plugin
+//! is added.
+//!
+//! Systems that move windows themselves can run after the plugin's restore and before its
+//! save by ordering against [`WindowManagerSet`], or wait for every window to finish
+//! restoring with the [`restore_in_progress`] run condition. Layout that depends on a
+//! window's final size can observe [`GeometrySettled`], which fires once the restored
+//! window's geometry has stopped changing.
+//!
+//! [`SavePolicy::OnExit`] instead keeps changes in memory and writes the state file once
+//! when the app exits, for long-running apps that want to avoid frequent disk writes.
+//!
+//! ## Layout Profiles
+//!
+//! The state file can hold several named layouts, e.g. one for coding and one for
+//! presenting. Windows are saved under the active profile, `"default"` unless
+//! [`WindowManagerPluginBuilder::profile`] picks another, and [`switch_profile`] makes a
+//! different profile active and restores its windows.
+//!
+//! ## Logging
+//!
+//! The plugin logs through `tracing` with each module's path as the target, so every
+//! message it emits has a target starting with `bevy_window_manager`. Restore and monitor
+//! diagnostics are at `debug`, a few notable events such as dry-run restores at `info`,
+//! and failures at `warn`. Filter them independently of the app's own logs, e.g. to keep
+//! only warnings:
+//!
+//! ```ignore
+//! app.add_plugins(DefaultPlugins.set(LogPlugin {
+//!     filter: "wgpu=error,bevy_window_manager=warn".into(),
+//!     ..default()
+//! }));
+//! ```
+//!
+//! or `RUST_LOG=bevy_window_manager=off` to silence it entirely.
 
 mod constants;
+mod cursor;
+mod error;
 mod events;
+mod macos_spaces;
+#[cfg(target_os = "macos")]
+mod macos_stage_manager;
 #[cfg(target_os = "macos")]
 mod macos_tabbing_fix;
 mod managed;
+mod manual;
 mod monitor;
 mod monitors;
+mod opacity;
 mod persistence;
 mod platform;
 mod restore;
 mod restore_window_config;
+mod scale_factor_changes;
+mod system_set;
+#[cfg(test)]
+mod test_support;
+mod virtual_desktop;
 mod visibility;
+mod window_ext;
 #[cfg(all(target_os = "windows", feature = "workaround-winit-4341"))]
 mod windows_dpi_fix;
+mod windows_invisible_border;
+mod winit_access;
+mod work_area;
 #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
 mod x11_position_fix;
 
-use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+pub use error::WindowManagerError;
+pub use events::GeometrySettled;
+pub use events::MonitorConfigurationChanged;
 pub use events::WindowRestoreMismatch;
+pub use events::WindowRestoreStarted;
 pub use events::WindowRestored;
+pub use events::WindowStateLoadFailed;
+pub use events::WindowStateSaveFailed;
+pub use managed::DoNotRestore;
 pub use managed::ManagedWindow;
 pub use managed::ManagedWindowPersistence;
 use managed::ManagedWindowRegistry;
+pub use managed::RestoreRelativeTo;
+pub use managed::RestoreTarget;
+pub use managed::WindowExtra;
+pub use managed::WindowIconPath;
+use managed::on_do_not_restore_removed;
 use managed::on_managed_window_added;
 use managed::on_managed_window_load;
 use managed::on_managed_window_removed;
 use managed::on_persistence_changed;
+use managed::on_restore_target_added;
+pub use manual::clear_saved_state;
+pub use manual::read_saved_state;
+pub use manual::reset_window_layout;
+pub use manual::restore_window_state;
+pub use manual::save_current_window_state;
+pub use manual::switch_profile;
+pub use manual::trigger_restore;
+pub use manual::undo_window_geometry;
 pub use monitors::CurrentMonitor;
 pub use monitors::MonitorInfo;
 use monitors::MonitorPlugin;
 pub use monitors::Monitors;
+pub use persistence::MemoryStateStore;
+pub use persistence::SavedVideoMode;
+use persistence::SavedWindowMode;
+pub use persistence::StateFormat;
+use persistence::WindowGeometryHistory;
 pub use persistence::WindowKey;
+pub use persistence::WindowState;
+use persistence::WindowStateCache;
 pub use platform::Platform;
+use restore::RestoreGate;
+pub use restore::RestorePhase;
 use restore::RestorePlugin;
 #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
 use restore::has_restoring_windows;
+pub use restore::no_restore_in_progress;
 use restore::no_restoring_windows;
+pub use restore::restore_in_progress;
+#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+use restore::restore_triggered;
+pub use restore_window_config::DefaultPlacement;
+pub use restore_window_config::DisconnectedMonitorPolicy;
+pub use restore_window_config::FocusOnRestore;
+pub use restore_window_config::GeometryMemory;
+pub use restore_window_config::GeometryRestore;
+pub use restore_window_config::GreenButtonDetection;
+pub use restore_window_config::MinimizedRestore;
+pub use restore_window_config::ModeSequencing;
+pub use restore_window_config::MonitorFallback;
+pub use restore_window_config::OffScreenRecovery;
+use restore_window_config::RestoreExecution;
+use restore_window_config::RestoreOptions;
+pub use restore_window_config::RestorePreference;
+pub use restore_window_config::RestoreTrigger;
 use restore_window_config::RestoreWindowConfig;
+pub use restore_window_config::SavePolicy;
+pub use restore_window_config::SizePolicy;
+pub use restore_window_config::SnapPolicy;
+pub use restore_window_config::TitleRestore;
+pub use system_set::WindowManagerSet;
+pub use window_ext::MonitorAnchor;
+pub use window_ext::WindowExt;
 
 /// The main plugin. See module docs for usage.
 ///
@@ -87,6 +250,20 @@ use restore_window_config::RestoreWindowConfig;
 pub struct WindowManagerPlugin;
 
 impl WindowManagerPlugin {
+    /// Start configuring a plugin with every option at its default.
+    ///
+    /// ```ignore
+    /// app.add_plugins(
+    ///     WindowManagerPlugin::builder()
+    ///         .path("windows.json")
+    ///         .save_policy(SavePolicy::OnExit)
+    ///         .monitor_fallback(MonitorFallback::FallbackToClosest)
+    ///         .build(),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn builder() -> WindowManagerPluginBuilder { WindowManagerPluginBuilder::default() }
+
     /// Create a plugin with a custom app name.
     ///
     /// Uses `config_dir()/<app_name>/windows.ron`.
@@ -95,23 +272,17 @@ impl WindowManagerPlugin {
     ///
     /// Panics if the config directory cannot be determined.
     #[must_use]
-    #[expect(clippy::expect_used, reason = "fail fast if path cannot be determined")]
     pub fn with_app_name(app_name: impl Into<String>) -> impl Plugin {
-        WindowManagerPluginCustomPath {
-            path:                       persistence::get_state_path_for_app(&app_name.into())
-                .expect("Could not determine state file path"),
-            managed_window_persistence: ManagedWindowPersistence::default(),
-        }
+        Self::builder().app_name(app_name).build()
     }
 
     /// Create a plugin with a custom state file path.
+    ///
+    /// A `.json` extension stores state as JSON and `.cbor` as compact binary CBOR; any
+    /// other extension uses RON.
+    /// See [`StateFormat`].
     #[must_use]
-    pub fn with_path(path: impl Into<PathBuf>) -> impl Plugin {
-        WindowManagerPluginCustomPath {
-            path:                       path.into(),
-            managed_window_persistence: ManagedWindowPersistence::default(),
-        }
-    }
+    pub fn with_path(path: impl Into<PathBuf>) -> impl Plugin { Self::builder().path(path).build() }
 
     /// Create a plugin with a specific persistence behavior.
     ///
@@ -119,42 +290,345 @@ impl WindowManagerPlugin {
     ///
     /// Panics if the config directory cannot be determined.
     #[must_use]
-    #[expect(clippy::expect_used, reason = "fail fast if path cannot be determined")]
     pub fn with_persistence(managed_window_persistence: ManagedWindowPersistence) -> impl Plugin {
-        WindowManagerPluginCustomPath {
-            path: persistence::get_default_state_path()
-                .expect("Could not determine state file path"),
-            managed_window_persistence,
-        }
+        Self::builder()
+            .persistence(managed_window_persistence)
+            .build()
     }
 }
 
 impl Plugin for WindowManagerPlugin {
+    fn build(&self, app: &mut App) { app.add_plugins(Self::builder().build()); }
+}
+
+/// Builder for a [`WindowManagerPlugin`] with custom options.
+///
+/// Created with [`WindowManagerPlugin::builder`]. Options left unset keep their defaults.
+#[derive(Default)]
+pub struct WindowManagerPluginBuilder {
+    path:                        Option<PathBuf>,
+    app_name:                    Option<String>,
+    managed_window_persistence:  ManagedWindowPersistence,
+    restore_trigger:             RestoreTrigger,
+    disconnected_monitor_policy: DisconnectedMonitorPolicy,
+    off_screen_recovery:         OffScreenRecovery,
+    restore_options:             RestoreOptions,
+}
+
+impl WindowManagerPluginBuilder {
+    /// Use a custom state file path. Takes precedence over [`Self::app_name`].
+    ///
+    /// A `.json` extension stores state as JSON and `.cbor` as compact binary CBOR; any
+    /// other extension uses RON.
+    /// See [`StateFormat`].
+    #[must_use]
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Use `config_dir()/<app_name>/windows.ron` instead of the executable name.
+    #[must_use]
+    pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Also write the state to `path`, e.g. a project-local backup. Call once per mirror.
+    ///
+    /// Every save writes the state file and then each mirror, in the same format. Loading
+    /// reads the state file first and tries the mirrors in order when it is missing or
+    /// cannot be read. A location that fails to write is reported through
+    /// [`WindowStateSaveFailed`] without affecting the others.
+    #[must_use]
+    pub fn mirror_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.restore_options.mirror_paths.push(path.into());
+        self
+    }
+
+    /// Set what happens to saved state when a managed window is closed.
+    #[must_use]
+    pub const fn persistence(
+        mut self,
+        managed_window_persistence: ManagedWindowPersistence,
+    ) -> Self {
+        self.managed_window_persistence = managed_window_persistence;
+        self
+    }
+
+    /// Set what happens when a window's saved monitor is no longer connected.
+    #[must_use]
+    pub const fn monitor_fallback(mut self, monitor_fallback: MonitorFallback) -> Self {
+        self.restore_options.monitor_fallback = monitor_fallback;
+        self
+    }
+
+    /// Set where the primary window opens when there is no saved state for it, e.g.
+    /// [`DefaultPlacement::CenterPrimary`] for a predictable first-run layout. The window
+    /// keeps the size it was created with, unless [`DefaultPlacement::ScaledDefault`] gives
+    /// one.
+    #[must_use]
+    pub const fn default_placement(mut self, default_placement: DefaultPlacement) -> Self {
+        self.restore_options.default_placement = default_placement;
+        self
+    }
+
+    /// Set how windows saved while minimized are restored.
+    #[must_use]
+    pub const fn minimized_restore(mut self, minimized_restore: MinimizedRestore) -> Self {
+        self.restore_options.minimized_restore = minimized_restore;
+        self
+    }
+
+    /// Set when window changes are written to the state file.
+    #[must_use]
+    pub const fn save_policy(mut self, save_policy: SavePolicy) -> Self {
+        self.restore_options.save_policy = save_policy;
+        self
+    }
+
+    /// Hold back state file writes for this long after startup, so the moves and resizes
+    /// a window goes through while it first appears don't overwrite a good saved layout.
+    /// Changes made in that time are written once it ends. Defaults to 250ms;
+    /// `Duration::ZERO` writes from the first frame.
+    #[must_use]
+    pub const fn startup_save_delay(mut self, startup_save_delay: Duration) -> Self {
+        self.restore_options.startup_save_delay = startup_save_delay;
+        self
+    }
+
+    /// Restore windows at no less than this logical size, e.g. when the state file holds a
+    /// size too small to use. A window grown this way is kept on its monitor.
+    #[must_use]
+    pub const fn min_size(mut self, min_size: UVec2) -> Self {
+        self.restore_options.min_size = Some(min_size);
+        self
+    }
+
+    /// Restore windows at no more than this logical size. Wins over
+    /// [`Self::min_size`] if the two conflict.
+    #[must_use]
+    pub const fn max_size(mut self, max_size: UVec2) -> Self {
+        self.restore_options.max_size = Some(max_size);
+        self
+    }
+
+    /// Treat two monitor scale factors as equal when they differ by less than this, so no
+    /// cross-DPI compensation runs between them. Raise it if winit reports a scale like
+    /// `1.0000001` for a `1.0` display; lower it to tell close fractional scales such as
+    /// `1.2` and `1.25` apart. Defaults to `0.01`.
+    #[must_use]
+    pub const fn scale_factor_epsilon(mut self, scale_factor_epsilon: f64) -> Self {
+        self.restore_options.scale_factor_epsilon = scale_factor_epsilon;
+        self
+    }
+
+    /// Set which monitor saved windows are restored onto.
+    #[must_use]
+    pub const fn restore_preference(mut self, restore_preference: RestorePreference) -> Self {
+        self.restore_options.restore_preference = restore_preference;
+        self
+    }
+
+    /// Set whether window geometry is remembered per monitor.
+    #[must_use]
+    pub const fn geometry_memory(mut self, geometry_memory: GeometryMemory) -> Self {
+        self.restore_options.geometry_memory = geometry_memory;
+        self
+    }
+
+    /// Set which parts of the saved geometry are restored, e.g.
+    /// [`GeometryRestore::SizeOnly`] to leave placement to a tiling window manager.
+    #[must_use]
+    pub const fn geometry_restore(mut self, geometry_restore: GeometryRestore) -> Self {
+        self.restore_options.geometry_restore = geometry_restore;
+        self
+    }
+
+    /// Set whether a window restored onto a monitor with a different scale keeps its
+    /// saved logical size (the default) or its saved physical size.
+    #[must_use]
+    pub const fn size_policy(mut self, size_policy: SizePolicy) -> Self {
+        self.restore_options.size_policy = size_policy;
+        self
+    }
+
+    /// Set whether restored windows snap to nearby monitor edges or to a grid. Off by
+    /// default.
+    #[must_use]
+    pub const fn snap_policy(mut self, snap_policy: SnapPolicy) -> Self {
+        self.restore_options.snap_policy = snap_policy;
+        self
+    }
+
+    /// Set whether fullscreen and maximized restores change the window mode in a later
+    /// frame than they move the window, for window managers that mishandle both at once.
+    #[must_use]
+    pub const fn mode_sequencing(mut self, mode_sequencing: ModeSequencing) -> Self {
+        self.restore_options.mode_sequencing = mode_sequencing;
+        self
+    }
+
+    /// Run `on_save` on every window's state before it is written, e.g. to snap positions
+    /// to a grid.
+    ///
+    /// ```ignore
+    /// WindowManagerPlugin::builder()
+    ///     .on_save(|window_state: &mut WindowState| {
+    ///         if let Some(position) = window_state.logical_position() {
+    ///             window_state.set_logical_position((position / 8) * 8);
+    ///         }
+    ///     })
+    ///     .build()
+    /// ```
+    #[must_use]
+    pub fn on_save(mut self, on_save: impl Fn(&mut WindowState) + Send + Sync + 'static) -> Self {
+        self.restore_options.on_save = Some(Arc::new(on_save));
+        self
+    }
+
+    /// Run `on_load` on a window's saved state before it is restored. The monitor it picks
+    /// is still checked against the connected monitors, falling back per
+    /// [`MonitorFallback`] when it is missing.
+    #[must_use]
+    pub fn on_load(mut self, on_load: impl Fn(&mut WindowState) + Send + Sync + 'static) -> Self {
+        self.restore_options.on_load = Some(Arc::new(on_load));
+        self
+    }
+
+    /// Set what happens to a window when its monitor is disconnected while the app runs.
+    #[must_use]
+    pub const fn disconnected_monitor_policy(
+        mut self,
+        disconnected_monitor_policy: DisconnectedMonitorPolicy,
+    ) -> Self {
+        self.disconnected_monitor_policy = disconnected_monitor_policy;
+        self
+    }
+
+    /// Set whether a window left entirely off screen while the app runs is moved back onto
+    /// the nearest monitor.
+    #[must_use]
+    pub const fn off_screen_recovery(mut self, off_screen_recovery: OffScreenRecovery) -> Self {
+        self.off_screen_recovery = off_screen_recovery;
+        self
+    }
+
+    /// Set whether window titles are saved and restored.
+    #[must_use]
+    pub const fn title_restore(mut self, title_restore: TitleRestore) -> Self {
+        self.restore_options.title_restore = title_restore;
+        self
+    }
+
+    /// Set whether a windowed window that exactly fills its monitor is treated as macOS
+    /// green-button fullscreen. Disable it for apps that size windows to fill the screen
+    /// and want them saved as `Windowed`.
+    #[must_use]
+    pub const fn green_button_detection(
+        mut self,
+        green_button_detection: GreenButtonDetection,
+    ) -> Self {
+        self.restore_options.green_button_detection = green_button_detection;
+        self
+    }
+
+    /// Set whether restored windows are focused once their restore completes. See
+    /// [`FocusOnRestore`] for the platforms where focus requests are best-effort.
+    #[must_use]
+    pub const fn focus_on_restore(mut self, focus_on_restore: FocusOnRestore) -> Self {
+        self.restore_options.focus_on_restore = focus_on_restore;
+        self
+    }
+
+    /// Set when loaded window state is applied. Under [`RestoreTrigger::Manual`], nothing
+    /// is restored until the app calls [`trigger_restore`].
+    #[must_use]
+    pub const fn restore_trigger(mut self, restore_trigger: RestoreTrigger) -> Self {
+        self.restore_trigger = restore_trigger;
+        self
+    }
+
+    /// Compute every restore and `info!`-log the target monitor, scale strategy, and
+    /// planned position and size, without moving or resizing any window.
+    ///
+    /// Windows are still shown and checked against the computed target once they settle,
+    /// so [`WindowRestoreMismatch`] reports the planned geometry next to what the OS
+    /// reports. Useful for debugging multi-monitor restores. Saving is unaffected.
+    #[must_use]
+    pub const fn dry_run(mut self) -> Self {
+        self.restore_options.restore_execution = RestoreExecution::DryRun;
+        self
+    }
+
+    /// Keep window states in `memory_store` instead of a state file, so nothing is read
+    /// from or written to disk. Meant for tests; seed the store to restore from it and read
+    /// it to check what was saved. The state file path and mirrors are ignored.
+    #[must_use]
+    pub fn memory_store(mut self, memory_store: MemoryStateStore) -> Self {
+        self.restore_options.memory_store = Some(memory_store);
+        self
+    }
+
+    /// Start with this layout profile active instead of the default profile.
+    ///
+    /// Each profile keeps its own saved window states in the state file. Switch at runtime
+    /// with [`switch_profile`].
+    #[must_use]
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.restore_options.profile = profile.into();
+        self
+    }
+
+    /// Build the plugin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither a path nor a memory store was set and the config directory cannot
+    /// be determined.
+    #[must_use]
     #[expect(clippy::expect_used, reason = "fail fast if path cannot be determined")]
-    fn build(&self, app: &mut App) {
-        app.add_plugins(WindowManagerPluginCustomPath {
-            path:                       persistence::get_default_state_path()
+    pub fn build(self) -> impl Plugin {
+        let path = match (self.path, self.app_name) {
+            (Some(path), _) => path,
+            (None, Some(app_name)) => persistence::get_state_path_for_app(&app_name)
                 .expect("Could not determine state file path"),
-            managed_window_persistence: ManagedWindowPersistence::default(),
-        });
+            (None, None) if self.restore_options.memory_store.is_some() => {
+                PathBuf::from(constants::STATE_FILE)
+            },
+            (None, None) => {
+                persistence::get_default_state_path().expect("Could not determine state file path")
+            },
+        };
+        WindowManagerPluginCustomPath {
+            path,
+            managed_window_persistence: self.managed_window_persistence,
+            restore_trigger: self.restore_trigger,
+            disconnected_monitor_policy: self.disconnected_monitor_policy,
+            off_screen_recovery: self.off_screen_recovery,
+            restore_options: self.restore_options,
+        }
     }
 }
 
 /// Plugin variant with a custom state file path.
 struct WindowManagerPluginCustomPath {
-    path:                       PathBuf,
-    managed_window_persistence: ManagedWindowPersistence,
+    path:                        PathBuf,
+    managed_window_persistence:  ManagedWindowPersistence,
+    restore_trigger:             RestoreTrigger,
+    disconnected_monitor_policy: DisconnectedMonitorPolicy,
+    off_screen_recovery:         OffScreenRecovery,
+    restore_options:             RestoreOptions,
 }
 
-impl Plugin for WindowManagerPluginCustomPath {
-    fn build(&self, app: &mut App) {
-        let path = self.path.clone();
-        let managed_window_persistence = self.managed_window_persistence.clone();
-
-        let platform = Platform::detect();
-        app.insert_resource(platform);
+impl WindowManagerPluginCustomPath {
+    fn restore_window_config(&self) -> RestoreWindowConfig {
+        RestoreWindowConfig::new(self.path.clone(), self.restore_options.clone())
+    }
 
-        // Hide primary window to prevent flash at default position.
+    /// Hide the primary window until its restore completes, to prevent a flash at the
+    /// default position.
+    fn hide_primary_window_on_startup(&self, app: &mut App, platform: Platform) {
         // Two cases to handle:
         // 1. Window already exists (`WindowManagerPlugin` added after `DefaultPlugins`) — hide
         //    immediately
@@ -164,22 +638,43 @@ impl Plugin for WindowManagerPluginCustomPath {
         // EXCEPTION: On Linux X11 with frame extent compensation (workaround-winit-4445),
         // we cannot hide the window because the compensation system needs to query
         // `_NET_FRAME_EXTENTS`, which requires the window to be visible/mapped.
-        let should_hide = platform.should_hide_on_startup();
+        //
+        // Under `RestoreTrigger::Manual` the window stays visible for the app's loading
+        // screen until `trigger_restore`.
+        let should_hide =
+            platform.should_hide_on_startup() && self.restore_trigger == RestoreTrigger::Startup;
 
         if should_hide {
             let mut query = app
                 .world_mut()
                 .query_filtered::<&mut Window, With<PrimaryWindow>>();
             if let Some(mut window) = query.iter_mut(app.world_mut()).next() {
-                debug!("[build] Window already exists, hiding immediately");
+                debug!(
+                    "[hide_primary_window_on_startup] Window already exists, hiding immediately"
+                );
                 window.visible = false;
             } else {
-                debug!("[build] Window doesn't exist yet, registering observer");
+                debug!(
+                    "[hide_primary_window_on_startup] Window doesn't exist yet, registering observer"
+                );
                 app.add_observer(visibility::hide_window_on_creation);
             }
         } else {
-            debug!("[build] Linux X11: skipping window hide for frame extent compensation");
+            debug!(
+                "[hide_primary_window_on_startup] Not hiding: X11 frame extent compensation or manual restore"
+            );
         }
+    }
+}
+
+impl Plugin for WindowManagerPluginCustomPath {
+    fn build(&self, app: &mut App) {
+        let managed_window_persistence = self.managed_window_persistence.clone();
+
+        let platform = Platform::detect();
+        app.insert_resource(platform);
+
+        self.hide_primary_window_on_startup(app, platform);
 
         #[cfg(target_os = "macos")]
         {
@@ -188,6 +683,7 @@ impl Plugin for WindowManagerPluginCustomPath {
                 Update,
                 macos_tabbing_fix::disable_tabbing_on_managed.before(restore::restore_windows),
             );
+            app.add_systems(Last, macos_stage_manager::forget_stage_manager_setting);
         }
 
         #[cfg(all(target_os = "windows", feature = "workaround-winit-4341"))]
@@ -198,34 +694,49 @@ impl Plugin for WindowManagerPluginCustomPath {
 
         app.add_plugins(MonitorPlugin)
             .add_plugins(RestorePlugin)
-            .insert_resource(RestoreWindowConfig {
-                path,
-                loaded_states: HashMap::new(),
-            })
+            .insert_resource(self.restore_window_config())
+            .insert_resource(RestoreGate::new(self.restore_trigger))
             .insert_resource(managed_window_persistence)
             .init_resource::<ManagedWindowRegistry>()
+            .init_resource::<WindowStateCache>()
+            .init_resource::<WindowGeometryHistory>()
+            // Saved state is reflectable so inspectors can show and edit it.
+            .register_type::<WindowState>()
+            .register_type::<SavedWindowMode>()
+            .register_type::<SavedVideoMode>()
             .add_observer(on_managed_window_added)
             .add_observer(on_managed_window_removed)
-            .add_observer(on_managed_window_load);
+            .add_observer(on_managed_window_load)
+            .add_observer(on_do_not_restore_removed)
+            .add_observer(on_restore_target_added);
 
-        // X11 frame extent compensation (W6 workaround, winit #4445).
-        #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
-        app.add_systems(
+        app.configure_sets(
             Update,
-            (
-                x11_position_fix::compensate_target_position,
-                // Re-apply the compensated position once the window is mapped: bevy 0.19
-                // can ignore the first `set_outer_position` request while the X11 window is
-                // unmapped, while a mapped window's `Window.position` readback matches the
-                // requested compensated position plus `X11FrameTop`.
-                x11_position_fix::reapply_compensated_position
-                    .after(restore::restore_windows)
-                    .before(restore::check_restore_settling),
-            )
-                .run_if(has_restoring_windows)
-                .run_if(|p: Res<Platform>| p.is_x11()),
+            WindowManagerSet::Restore.before(WindowManagerSet::Save),
         );
 
+        // X11 frame extent compensation (W6 workaround, winit #4445).
+        #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+        app.init_resource::<x11_position_fix::X11FrameExtentsCache>()
+            .init_resource::<x11_position_fix::X11Connection>()
+            .add_systems(
+                Update,
+                (
+                    x11_position_fix::compensate_target_position,
+                    // Re-apply the compensated position once the window is mapped: bevy 0.19
+                    // can ignore the first `set_outer_position` request while the X11 window is
+                    // unmapped, while a mapped window's `Window.position` readback matches the
+                    // requested compensated position plus the cached frame top.
+                    x11_position_fix::reapply_compensated_position
+                        .after(restore::restore_windows)
+                        .before(restore::check_restore_settling),
+                )
+                    .in_set(WindowManagerSet::Restore)
+                    .run_if(has_restoring_windows)
+                    .run_if(restore_triggered)
+                    .run_if(|p: Res<Platform>| p.is_x11()),
+            );
+
         // Unified monitor detection + save window state
         app.add_systems(
             Update,
@@ -238,7 +749,37 @@ impl Plugin for WindowManagerPluginCustomPath {
                     .run_if(resource_changed::<ManagedWindowPersistence>)
                     .run_if(no_restoring_windows)
                     .after(monitor::update_current_monitor),
-            ),
+                persistence::poll_state_file_writes,
+            )
+                .in_set(WindowManagerSet::Save),
+        )
+        .add_systems(
+            Last,
+            (
+                persistence::flush_pending_window_state,
+                persistence::flush_state_file_writes,
+            )
+                .chain()
+                .in_set(WindowManagerSet::Save)
+                .run_if(on_message::<AppExit>),
         );
+
+        if self.disconnected_monitor_policy == DisconnectedMonitorPolicy::MoveToPrimary {
+            app.add_systems(
+                Update,
+                monitor::move_windows_off_disconnected_monitors
+                    .run_if(resource_changed::<Monitors>)
+                    .run_if(no_restoring_windows)
+                    .before(monitor::update_current_monitor),
+            );
+        }
+        if self.off_screen_recovery == OffScreenRecovery::Enabled {
+            app.add_systems(
+                Update,
+                monitor::recover_off_screen_windows
+                    .run_if(no_restoring_windows)
+                    .before(monitor::update_current_monitor),
+            );
+        }
     }
 }
@@ -0,0 +1,66 @@
+//! macOS Spaces behavior (`macos-spaces` feature).
+//!
+//! `AppKit` has no public API to read or choose the specific Space a window is on; that
+//! assignment lives in private `CGS` calls and in the Dock's per-app "Assign To" setting.
+//! What `NSWindow.collectionBehavior` does expose is how a window takes part in Spaces:
+//! shown on every Space, or moved to the active Space when shown. Those bits are saved
+//! and set back on restore. A window tied to a single Space (the default) saves nothing.
+//!
+//! Without the feature, or on other platforms, no behavior is saved and nothing is set.
+//! All functions must run on the main thread; callers hold a `NonSendMarker`.
+
+use bevy::prelude::*;
+#[cfg(all(target_os = "macos", feature = "macos-spaces"))]
+use objc2_app_kit::NSWindowCollectionBehavior;
+
+#[cfg(all(target_os = "macos", feature = "macos-spaces"))]
+use crate::macos_tabbing_fix::get_ns_window;
+use crate::persistence::SavedSpaceBehavior;
+
+/// Spaces behavior of the window, when it is not tied to a single Space.
+#[cfg(all(target_os = "macos", feature = "macos-spaces"))]
+pub(crate) fn window_space_behavior(entity: Entity) -> Option<SavedSpaceBehavior> {
+    let collection_behavior = get_ns_window(entity)?.collectionBehavior();
+    if collection_behavior.contains(NSWindowCollectionBehavior::CanJoinAllSpaces) {
+        Some(SavedSpaceBehavior::AllSpaces)
+    } else if collection_behavior.contains(NSWindowCollectionBehavior::MoveToActiveSpace) {
+        Some(SavedSpaceBehavior::MoveToActiveSpace)
+    } else {
+        None
+    }
+}
+
+/// Spaces behavior of the window, when it is not tied to a single Space.
+#[cfg(not(all(target_os = "macos", feature = "macos-spaces")))]
+pub(crate) const fn window_space_behavior(_entity: Entity) -> Option<SavedSpaceBehavior> { None }
+
+/// Set the window's Spaces behavior, keeping its other collection behavior bits. Returns
+/// whether it was set; `false` when the window has no `NSWindow` yet.
+#[cfg(all(target_os = "macos", feature = "macos-spaces"))]
+pub(crate) fn set_space_behavior(entity: Entity, space_behavior: SavedSpaceBehavior) -> bool {
+    let Some(ns_window) = get_ns_window(entity) else {
+        return false;
+    };
+    let mut collection_behavior = ns_window.collectionBehavior();
+    // `CanJoinAllSpaces` and `MoveToActiveSpace` are mutually exclusive; AppKit raises
+    // when both are set.
+    collection_behavior.remove(
+        NSWindowCollectionBehavior::CanJoinAllSpaces
+            | NSWindowCollectionBehavior::MoveToActiveSpace,
+    );
+    collection_behavior.insert(match space_behavior {
+        SavedSpaceBehavior::AllSpaces => NSWindowCollectionBehavior::CanJoinAllSpaces,
+        SavedSpaceBehavior::MoveToActiveSpace => NSWindowCollectionBehavior::MoveToActiveSpace,
+    });
+    ns_window.setCollectionBehavior(collection_behavior);
+    true
+}
+
+/// Set the window's Spaces behavior. Returns whether it was set.
+#[cfg(not(all(target_os = "macos", feature = "macos-spaces")))]
+pub(crate) const fn set_space_behavior(
+    _entity: Entity,
+    _space_behavior: SavedSpaceBehavior,
+) -> bool {
+    false
+}
@@ -0,0 +1,67 @@
+//! Detect macOS Stage Manager, which overrides restored window positions.
+//!
+//! Stage Manager places windows itself and moves a restored window straight back, so
+//! restoring the saved position only produces a visible jump. While it is on, restore
+//! keeps the saved size and mode but leaves the window where macOS put it, as under
+//! [`GeometryRestore::SizeOnly`](crate::GeometryRestore::SizeOnly).
+//!
+//! Stage Manager has no public API. Its switch is the `GlobalEnabled` key of the
+//! `com.apple.WindowManager` defaults domain, read through `NSUserDefaults`. The user can
+//! toggle it from Control Center at any time, so it is read again for each restore pass:
+//! once per frame in which a window is restored, shared by every window restored in it.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use bevy::prelude::*;
+use objc2::AllocAnyThread;
+use objc2_foundation::NSString;
+use objc2_foundation::NSUserDefaults;
+
+use crate::constants::STAGE_MANAGER_DEFAULTS_DOMAIN;
+use crate::constants::STAGE_MANAGER_ENABLED_KEY;
+use crate::restore_window_config::RestoreWindowConfig;
+
+/// Stage Manager setting read during the current frame, `None` until a restore asks
+/// for it.
+///
+/// Held by `RestoreWindowConfig`, so each app reads its own. Cloning shares the same value.
+#[derive(Clone, Default)]
+pub(crate) struct StageManagerSetting(Arc<Mutex<Option<bool>>>);
+
+impl StageManagerSetting {
+    fn lock(&self) -> MutexGuard<'_, Option<bool>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether Stage Manager is on. `false` when the setting cannot be read, which is also
+    /// the case on macOS versions without Stage Manager.
+    pub(crate) fn enabled(&self) -> bool {
+        *self.lock().get_or_insert_with(read_stage_manager_enabled)
+    }
+
+    /// Drop the value, so the next `enabled` call reads the setting again.
+    fn forget(&self) { *self.lock() = None; }
+}
+
+/// Forget the setting read this frame, so the next restore pass reads it again.
+pub(crate) fn forget_stage_manager_setting(restore_window_config: Res<RestoreWindowConfig>) {
+    restore_window_config.stage_manager_setting.forget();
+}
+
+fn read_stage_manager_enabled() -> bool {
+    let suite_name = NSString::from_str(STAGE_MANAGER_DEFAULTS_DOMAIN);
+    let Some(user_defaults) =
+        NSUserDefaults::initWithSuiteName(NSUserDefaults::alloc(), Some(&suite_name))
+    else {
+        debug!(
+            "[stage_manager_enabled] Could not open the {STAGE_MANAGER_DEFAULTS_DOMAIN} defaults"
+        );
+        return false;
+    };
+    let enabled = user_defaults.boolForKey(&NSString::from_str(STAGE_MANAGER_ENABLED_KEY));
+    debug!("[stage_manager_enabled] Stage Manager enabled: {enabled}");
+    enabled
+}
@@ -21,7 +21,7 @@ use raw_window_handle::RawWindowHandle;
 use super::ManagedWindow;
 
 /// Get the `NSWindow` for a Bevy window entity.
-fn get_ns_window(entity: Entity) -> Option<Retained<NSWindow>> {
+pub(crate) fn get_ns_window(entity: Entity) -> Option<Retained<NSWindow>> {
     WINIT_WINDOWS.with(|winit_windows| {
         let winit_windows = winit_windows.borrow();
         let winit_window = winit_windows.get_window(entity)?;
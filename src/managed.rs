@@ -1,7 +1,9 @@
 //! Managed window types, registry, and lifecycle observers.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -9,21 +11,25 @@ use bevy_kana::ToI32;
 use bevy_kana::ToU32;
 
 use super::WindowKey;
-use super::constants::DEFAULT_SCALE_FACTOR;
 use super::constants::FIRST_DUPLICATE_SUFFIX;
+use super::constants::FULLY_OPAQUE;
 use super::constants::MANAGED_WINDOW_NAME_SEPARATOR;
-use super::constants::PRIMARY_MONITOR_INDEX;
 use super::constants::PRIMARY_WINDOW_KEY;
 use super::monitors::CurrentMonitor;
+use super::monitors::MonitorInfo;
 use super::monitors::Monitors;
 use super::persistence;
+use super::persistence::SavedWindowAttributes;
 use super::persistence::SavedWindowMode;
 use super::persistence::WindowState;
+use super::persistence::WindowStateCache;
 use super::platform::Platform;
 use super::restore;
+use super::restore::RestorePhase;
 use super::restore::WinitInfo;
 use super::restore::X11FrameCompensated;
 use super::restore_window_config::RestoreWindowConfig;
+use super::restore_window_config::SavePolicy;
 
 /// Marks a window entity as managed by the window manager plugin.
 ///
@@ -49,6 +55,171 @@ pub struct ManagedWindow {
     pub name: String,
 }
 
+/// Suppresses saving for a primary or managed window while present.
+///
+/// Changes to the window's position, size, or mode are not saved until the component is
+/// removed, at which point the window's current geometry is saved if it changed. Insert it
+/// around a scripted layout animation, or on a transient window that should not be
+/// persisted. Saves made meanwhile for other windows keep the window's last saved state.
+///
+/// # Example
+///
+/// ```ignore
+/// commands.entity(primary_window).insert(DoNotRestore);
+/// // ... animate the window ...
+/// commands.entity(primary_window).remove::<DoNotRestore>();
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct DoNotRestore;
+
+/// Observer: when `DoNotRestore` is removed, mark the window changed so `save_window_state`
+/// picks up anything that moved while saving was suppressed.
+pub(crate) fn on_do_not_restore_removed(
+    remove: On<Remove, DoNotRestore>,
+    mut windows: Query<&mut Window>,
+) {
+    if let Ok(mut window) = windows.get_mut(remove.entity) {
+        window.set_changed();
+        debug!(
+            "[on_do_not_restore_removed] Saving resumed for {:?}",
+            remove.entity
+        );
+    }
+}
+
+/// Marks the window the app spawns itself as the one restored in place of a
+/// `WindowPlugin` primary window.
+///
+/// Restore is driven by the window with `PrimaryWindow`. An app that sets
+/// `WindowPlugin { primary_window: None }` and spawns its main window later tags it with
+/// `RestoreTarget` instead; the plugin adds `PrimaryWindow` to it, and the window is
+/// restored under the primary key once winit has created it. Ignored, with a warning,
+/// while another window already has `PrimaryWindow`.
+///
+/// # Example
+///
+/// ```ignore
+/// commands.spawn((Window { visible: false, ..default() }, RestoreTarget));
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct RestoreTarget;
+
+/// Observer: make a window tagged `RestoreTarget` the primary window, so the deferred
+/// primary window restore picks it up.
+pub(crate) fn on_restore_target_added(
+    add: On<Add, RestoreTarget>,
+    mut commands: Commands,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<(), With<Window>>,
+) {
+    let entity = add.entity;
+    if windows.get(entity).is_err() {
+        warn!("[on_restore_target_added] {entity:?} has `RestoreTarget` but is not a window");
+        return;
+    }
+    match primary_window.iter().next() {
+        Some(primary_entity) if primary_entity == entity => {},
+        Some(primary_entity) => warn!(
+            "[on_restore_target_added] {entity:?} has `RestoreTarget`, but {primary_entity:?} \
+             is already the primary window and is restored instead"
+        ),
+        None => {
+            debug!("[on_restore_target_added] Made {entity:?} the primary window");
+            commands.entity(entity).insert(PrimaryWindow);
+        },
+    }
+}
+
+/// Restores a managed window onto the monitor another window is on, instead of the
+/// monitor it was saved on.
+///
+/// The window keeps its saved size and its saved offset from its monitor's top-left
+/// corner, now measured from the reference window's monitor. Add it alongside
+/// [`ManagedWindow`] so the startup restore sees it; [`restore_window_state`] honors it
+/// too. When the reference window has no known monitor, e.g. it was despawned, the saved
+/// monitor is used.
+///
+/// [`restore_window_state`]: crate::restore_window_state
+///
+/// # Example
+///
+/// ```ignore
+/// commands.spawn((
+///     Window { title: "Inspector".into(), ..default() },
+///     ManagedWindow { name: "inspector".into() },
+///     RestoreRelativeTo(primary_window),
+/// ));
+/// ```
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct RestoreRelativeTo(pub Entity);
+
+/// Monitor the window `entity` restores onto under [`RestoreRelativeTo`]: the current
+/// monitor of its reference window. `None` without the component or when the reference
+/// window's monitor is unknown.
+pub(crate) fn reference_monitor<'a>(
+    entity: Entity,
+    relative_to: &Query<&RestoreRelativeTo>,
+    current_monitors: &'a Query<&CurrentMonitor>,
+) -> Option<&'a MonitorInfo> {
+    let RestoreRelativeTo(reference) = *relative_to.get(entity).ok()?;
+    let Ok(current_monitor) = current_monitors.get(reference) else {
+        warn!(
+            "[reference_monitor] {entity:?} restores relative to {reference:?}, which has no known monitor; using its saved monitor"
+        );
+        return None;
+    };
+    Some(&current_monitor.monitor_info)
+}
+
+/// Path of a custom icon the app set on the window, saved with its state.
+///
+/// The plugin never loads or applies icons; it only remembers the path and reports it in
+/// [`WindowRestored::icon_path`](crate::WindowRestored::icon_path) so the app can load
+/// the icon again. Insert the component again when applying the restored icon, or the
+/// next save drops the path.
+///
+/// # Example
+///
+/// ```ignore
+/// commands.entity(primary_window).insert(WindowIconPath("icons/dark.png".into()));
+///
+/// app.add_observer(|restored: On<WindowRestored>, mut commands: Commands| {
+///     if let Some(icon_path) = &restored.icon_path {
+///         // ... load and apply the icon ...
+///         commands.entity(restored.entity).insert(WindowIconPath(icon_path.clone()));
+///     }
+/// });
+/// ```
+#[derive(Component, Clone, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct WindowIconPath(pub PathBuf);
+
+/// App-defined settings saved with the window's state, such as a present mode or render
+/// scale the user picked.
+///
+/// The plugin stores the map as-is and reports it in
+/// [`WindowRestored::extra`](crate::WindowRestored::extra); applying the settings is up to
+/// the app. As with [`WindowIconPath`], insert the component again after a restore, or the
+/// next save drops the settings.
+///
+/// # Example
+///
+/// ```ignore
+/// let extra = BTreeMap::from([("present_mode".to_string(), "AutoVsync".to_string())]);
+/// commands.entity(primary_window).insert(WindowExtra(extra));
+///
+/// app.add_observer(|restored: On<WindowRestored>, mut commands: Commands| {
+///     // ... apply the settings in `restored.extra` ...
+///     commands.entity(restored.entity).insert(WindowExtra(restored.extra.clone()));
+/// });
+/// ```
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct WindowExtra(pub BTreeMap<String, String>);
+
 /// Controls what happens to saved state when a managed window is despawned.
 ///
 /// Set as a resource on the app to control persistence behavior for all windows.
@@ -77,11 +248,12 @@ pub(crate) struct ManagedWindowRegistry {
 /// needed.
 pub(crate) fn on_managed_window_added(
     add: On<Add, ManagedWindow>,
+    mut commands: Commands,
     mut managed: Query<&mut ManagedWindow>,
     mut managed_window_registry: ResMut<ManagedWindowRegistry>,
     restore_window_config: Res<RestoreWindowConfig>,
     monitors: Res<Monitors>,
-    windows: Query<&Window>,
+    windows: Query<(&Window, Option<&WindowIconPath>, Option<&WindowExtra>), Without<DoNotRestore>>,
     primary_query: Query<(), With<PrimaryWindow>>,
 ) {
     let entity = add.entity;
@@ -129,20 +301,21 @@ pub(crate) fn on_managed_window_added(
         "[on_managed_window_added] Registered managed window \"{unique_name}\" on entity {entity:?}"
     );
 
-    // If no saved state exists for this window, save its current position/size immediately
-    let existing = persistence::load_all_states(&restore_window_config.path);
+    // If no saved state exists for this window, save its current position/size immediately,
+    // unless `DoNotRestore` suppresses saving it
+    let existing = persistence::load_all_states_or_notify(&restore_window_config, &mut commands);
     let already_saved = existing
         .as_ref()
         .is_some_and(|states| states.contains_key(&WindowKey::Managed(unique_name.clone())));
 
-    if !already_saved && let Ok(window) = windows.get(entity) {
+    if !already_saved && let Ok((window, icon_path, extra)) = windows.get(entity) {
         let monitor_info = match window.position {
-            WindowPosition::At(physical_position) => *monitors.monitor_for_window(
+            WindowPosition::At(physical_position) => monitors.monitor_for_window(
                 physical_position,
                 window.physical_width(),
                 window.physical_height(),
             ),
-            _ => *monitors.first(),
+            _ => monitors.first(),
         };
         let logical_position = match window.position {
             WindowPosition::At(physical_position) => {
@@ -163,12 +336,34 @@ pub(crate) fn on_managed_window_added(
             scale: monitor_info.scale,
             monitor: monitor_info.index,
             saved_window_mode: SavedWindowMode::Windowed,
+            monitor_name: monitor_info.name.clone(),
+            logical_monitor_offset: match window.position {
+                WindowPosition::At(physical_position) => {
+                    Some(monitor_info.logical_offset(physical_position))
+                },
+                _ => None,
+            },
+            monitor_geometries: Vec::new(),
+            minimized: false,
+            restore_bounds: None,
+            window_attributes: SavedWindowAttributes::from_window(window),
+            opacity: FULLY_OPAQUE,
+            title: restore_window_config.saved_title(window),
+            icon_path: icon_path.map(|WindowIconPath(icon_path)| icon_path.clone()),
+            extra: extra
+                .map(|WindowExtra(extra)| extra.clone())
+                .unwrap_or_default(),
+            virtual_desktop: None,
+            space_behavior: None,
+            focused: window.focused,
+            logical_invisible_border: None,
+            follows_current_monitor: false,
             app_name: String::new(),
         };
 
         let mut states = existing.unwrap_or_default();
         states.insert(WindowKey::Managed(unique_name.clone()), window_state);
-        persistence::save_all_states(&restore_window_config.path, &states);
+        persistence::save_all_states_or_notify(&restore_window_config, &states);
         debug!("[on_managed_window_added] Saved initial state for \"{unique_name}\"");
     }
 }
@@ -176,6 +371,7 @@ pub(crate) fn on_managed_window_added(
 /// Observer: unregister a `ManagedWindow` name when removed, and update state file if `ActiveOnly`.
 pub(crate) fn on_managed_window_removed(
     remove: On<Remove, ManagedWindow>,
+    mut commands: Commands,
     mut managed_window_registry: ResMut<ManagedWindowRegistry>,
     restore_window_config: Res<RestoreWindowConfig>,
     managed_window_persistence: Res<ManagedWindowPersistence>,
@@ -186,23 +382,38 @@ pub(crate) fn on_managed_window_removed(
             &Window,
             Option<&CurrentMonitor>,
             Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
         ),
         Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
     >,
     primary_query: Query<(), With<PrimaryWindow>>,
+    mut window_state_cache: ResMut<WindowStateCache>,
 ) {
     let entity = remove.entity;
     if let Some(name) = managed_window_registry.entities.remove(&entity) {
         // If `ActiveOnly`, rebuild state from all remaining active windows.
         // The removed entity's `ManagedWindow` is being removed, so the query
         // naturally excludes it — but guard against it just in case.
-        if *managed_window_persistence == ManagedWindowPersistence::ActiveOnly {
+        // Under `SavePolicy::OnExit` only drop it from the cache that is written at exit.
+        if *managed_window_persistence == ManagedWindowPersistence::ActiveOnly
+            && restore_window_config.save_policy == SavePolicy::OnExit
+        {
+            window_state_cache.retain_open(&open_window_keys(
+                &all_windows,
+                &primary_query,
+                Some(entity),
+            ));
+        } else if *managed_window_persistence == ManagedWindowPersistence::ActiveOnly {
             persistence::save_active_window_state(
                 &restore_window_config,
                 &monitors,
                 &all_windows,
                 &primary_query,
                 Some(entity),
+                &mut window_state_cache,
+                &mut commands,
             );
             debug!(
                 "[on_managed_window_removed] Rebuilt state file without \"{name}\" (ActiveOnly)"
@@ -220,6 +431,7 @@ pub(crate) fn on_managed_window_removed(
 /// file from the currently-active windows so that any previously-remembered-but-closed
 /// window entries are pruned.
 pub(crate) fn on_persistence_changed(
+    mut commands: Commands,
     managed_window_persistence: Res<ManagedWindowPersistence>,
     restore_window_config: Res<RestoreWindowConfig>,
     monitors: Res<Monitors>,
@@ -229,21 +441,63 @@ pub(crate) fn on_persistence_changed(
             &Window,
             Option<&CurrentMonitor>,
             Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
         ),
         Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
     >,
     primary_query: Query<(), With<PrimaryWindow>>,
+    mut window_state_cache: ResMut<WindowStateCache>,
 ) {
-    if *managed_window_persistence == ManagedWindowPersistence::ActiveOnly {
-        persistence::save_active_window_state(
-            &restore_window_config,
-            &monitors,
-            &all_windows,
-            &primary_query,
-            None,
-        );
-        debug!("[on_persistence_changed] Rebuilt state file for ActiveOnly mode");
+    if *managed_window_persistence != ManagedWindowPersistence::ActiveOnly {
+        return;
+    }
+    if restore_window_config.save_policy == SavePolicy::OnExit {
+        window_state_cache.retain_open(&open_window_keys(&all_windows, &primary_query, None));
+        debug!("[on_persistence_changed] Pruned closed windows from the exit save (ActiveOnly)");
+        return;
     }
+    persistence::save_active_window_state(
+        &restore_window_config,
+        &monitors,
+        &all_windows,
+        &primary_query,
+        None,
+        &mut window_state_cache,
+        &mut commands,
+    );
+    debug!("[on_persistence_changed] Rebuilt state file for ActiveOnly mode");
+}
+
+/// State file keys of every open primary and managed window, skipping `exclude_entity`.
+fn open_window_keys(
+    all_windows: &Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: &Query<(), With<PrimaryWindow>>,
+    exclude_entity: Option<Entity>,
+) -> HashSet<WindowKey> {
+    all_windows
+        .iter()
+        .filter(|(entity, ..)| exclude_entity != Some(*entity))
+        .filter_map(|(entity, _, _, managed, ..)| {
+            if primary_query.get(entity).is_ok() {
+                Some(WindowKey::Primary)
+            } else {
+                managed.map(|managed_window| WindowKey::Managed(managed_window.name.clone()))
+            }
+        })
+        .collect()
 }
 
 /// Observer: hide a managed window on creation and load its saved state.
@@ -256,6 +510,8 @@ pub(crate) fn on_managed_window_load(
     restore_window_config: Res<RestoreWindowConfig>,
     mut windows: Query<&mut Window>,
     primary_monitor: Query<&CurrentMonitor, With<PrimaryWindow>>,
+    relative_to: Query<&RestoreRelativeTo>,
+    current_monitors: Query<&CurrentMonitor>,
     platform: Res<Platform>,
 ) {
     let entity = add.entity;
@@ -313,72 +569,100 @@ pub(crate) fn on_managed_window_load(
     }
 
     // The window will be created on the focused window's monitor (the primary window's
-    // monitor), so use that scale as starting_scale for scale factor compensation.
-    let primary_scale = primary_monitor
-        .iter()
-        .next()
-        .map_or(DEFAULT_SCALE_FACTOR, |current_monitor| {
-            current_monitor.scale
-        });
+    // monitor), so use that as the starting monitor for scale factor compensation.
+    let starting_monitor = primary_monitor.iter().next().map_or_else(
+        || monitors.first(),
+        |current_monitor| &current_monitor.monitor_info,
+    );
+    let Ok(window) = windows.get(entity) else {
+        return;
+    };
 
-    restore_managed_window(
+    restore_saved_window(
         entity,
+        window_key,
         &saved_state,
         &monitors,
         &winit_info,
         &mut commands,
-        primary_scale,
+        starting_monitor,
+        window,
+        &restore_window_config,
         *platform,
+        reference_monitor(entity, &relative_to, &current_monitors),
     );
 }
 
-/// Compute the target position for a managed window from saved state.
+/// Compute the target position for a window from saved state.
+///
+/// `starting_monitor` is the monitor the window currently sits on: the primary window's
+/// monitor for freshly created managed windows, or the window's own `CurrentMonitor` when
+/// re-restoring an existing window on demand. `reference_monitor` replaces the saved
+/// monitor for a window with [`RestoreRelativeTo`].
 ///
 /// Inserts a `TargetPosition` component but does NOT modify `Window.position` or
 /// `Window.resolution`. The actual restore is deferred to `restore_windows`, which
 /// gates on the winit window existing (via `WINIT_WINDOWS`). This ensures
 /// `create_windows` → `set_scale_factor_and_apply_to_physical_size()` runs first,
 /// preventing the physical size from being doubled on high-DPI displays.
-fn restore_managed_window(
+pub(crate) fn restore_saved_window(
     entity: Entity,
+    window_key: WindowKey,
     saved_window_state: &WindowState,
     monitors: &Monitors,
     winit_info: &WinitInfo,
     commands: &mut Commands,
-    primary_scale: f64,
+    starting_monitor: &MonitorInfo,
+    window: &Window,
+    restore_window_config: &RestoreWindowConfig,
     platform: Platform,
+    reference_monitor: Option<&MonitorInfo>,
 ) {
-    let resolved_monitor = restore::resolve_target_monitor_and_position(
-        saved_window_state.monitor,
-        saved_window_state.logical_position,
+    let mut saved_window_state = saved_window_state.clone();
+    restore_window_config.run_on_load(&mut saved_window_state);
+    let geometry_restore = restore_window_config.effective_geometry_restore();
+    let Some((resolved_monitor, saved_window_state)) = restore::resolve_restore_target(
+        &saved_window_state,
         monitors,
-    );
-    if matches!(
-        resolved_monitor.monitor_resolution_source,
-        restore::MonitorResolutionSource::FallbackToPrimary
-    ) {
-        warn!(
-            "[restore_managed_window] Target monitor {} not found, falling back to monitor {PRIMARY_MONITOR_INDEX}",
-            saved_window_state.monitor,
-        );
-    }
-
+        restore_window_config,
+        geometry_restore,
+        platform,
+        starting_monitor,
+        window,
+        reference_monitor,
+    ) else {
+        commands.entity(entity).queue(|mut entity: EntityWorldMut| {
+            if let Some(mut window) = entity.get_mut::<Window>() {
+                window.visible = true;
+            }
+        });
+        return;
+    };
     let physical_decoration = winit_info.physical_decoration();
 
     // The window is created on the focused window's monitor (the primary window's monitor)
     // without explicit positioning. Its starting scale matches the primary monitor, not the
     // target monitor.
-    let target_position = restore::compute_target_position(
-        saved_window_state,
+    let mut target_position = restore::compute_target_position(
+        &saved_window_state,
         resolved_monitor.monitor_info,
         resolved_monitor.logical_position,
         physical_decoration,
-        primary_scale,
+        starting_monitor.scale,
+        restore_window_config.min_size,
+        restore_window_config.max_size,
+        restore_window_config.scale_factor_epsilon,
         platform,
     );
+    target_position.apply_snap_policy(
+        restore_window_config.snap_policy,
+        &saved_window_state,
+        resolved_monitor.monitor_info,
+        physical_decoration,
+    );
 
     debug!(
-        "[restore_managed_window] saved_position={:?} clamped_position={:?} target_scale={} logical={}x{} physical={}x{} monitor={} monitor_position=({},{}) monitor_size=({},{})",
+        "[restore_saved_window] saved_position={:?} clamped_position={:?} target_scale={} logical={}x{} physical={}x{} monitor={} monitor_position=({},{}) monitor_size=({},{})",
         saved_window_state.logical_position,
         target_position.physical_position,
         target_position.target_scale,
@@ -393,8 +677,20 @@ fn restore_managed_window(
         resolved_monitor.monitor_info.physical_size.y,
     );
 
+    target_position.minimize_after_restore =
+        restore_window_config.minimize_after_restore(&saved_window_state);
+    target_position.focus_after_restore =
+        restore_window_config.focus_after_restore(&saved_window_state);
+    target_position.geometry_restore = geometry_restore;
+    target_position.apply_mode_sequencing(restore_window_config.mode_sequencing);
+
     let is_fullscreen = saved_window_state.saved_window_mode.is_fullscreen();
-    commands.entity(entity).insert(target_position);
+    let restore_started = target_position.restore_started(entity, window_key);
+    let restore_phase = RestorePhase::of(&target_position);
+    commands
+        .entity(entity)
+        .insert((target_position, restore_phase));
+    commands.trigger(restore_started);
 
     // Insert `X11FrameCompensated` for platforms that don't need compensation.
     // For fullscreen modes, skip frame compensation — frame extents are irrelevant
@@ -403,3 +699,39 @@ fn restore_managed_window(
         commands.entity(entity).insert(X11FrameCompensated);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with_restore_target_observer() -> App {
+        let mut app = App::new();
+        app.add_observer(on_restore_target_added);
+        app
+    }
+
+    #[test]
+    fn restore_target_becomes_the_primary_window() {
+        let mut app = app_with_restore_target_observer();
+        let entity = app
+            .world_mut()
+            .spawn((Window::default(), RestoreTarget))
+            .id();
+        app.world_mut().flush();
+
+        assert!(app.world().entity(entity).contains::<PrimaryWindow>());
+    }
+
+    #[test]
+    fn restore_target_is_ignored_while_another_window_is_primary() {
+        let mut app = app_with_restore_target_observer();
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let entity = app
+            .world_mut()
+            .spawn((Window::default(), RestoreTarget))
+            .id();
+        app.world_mut().flush();
+
+        assert!(!app.world().entity(entity).contains::<PrimaryWindow>());
+    }
+}
@@ -0,0 +1,477 @@
+//! On-demand save, restore, and profile switching entry points.
+//!
+//! Each one is, or returns, a `FnOnce(&mut World)`, so it can be called from exclusive
+//! systems or queued as a command: `commands.queue(save_current_window_state)`.
+//! [`save_current_window_state`] and [`restore_window_state`] are exclusive systems
+//! themselves and can be added to any schedule, including `OnEnter`/`OnExit`.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::window::WindowMode;
+
+use super::DoNotRestore;
+use super::ManagedWindow;
+use super::ManagedWindowPersistence;
+use super::RestoreRelativeTo;
+use super::WindowExt;
+use super::WindowExtra;
+use super::WindowIconPath;
+use super::WindowKey;
+use super::WindowManagerError;
+use super::managed;
+use super::monitors::CurrentMonitor;
+use super::monitors::Monitors;
+use super::persistence;
+use super::persistence::WindowGeometryHistory;
+use super::persistence::WindowState;
+use super::persistence::WindowStateCache;
+use super::platform::Platform;
+use super::restore;
+use super::restore::RestoreGate;
+use super::restore::TargetPosition;
+use super::restore::WinitInfo;
+use super::restore_window_config::RestoreWindowConfig;
+
+/// Save the state of every open window to the state file immediately.
+///
+/// Saves right away instead of waiting for the automatic save, which only fires after a
+/// window's position, size, mode, or monitor changes. Like every save, the file itself is
+/// written in the background. Respects
+/// [`ManagedWindowPersistence`]: `RememberAll` keeps entries for closed windows,
+/// `ActiveOnly` writes only the windows that are open now.
+///
+/// Does nothing while a restore is in progress, since windows have not reached their
+/// restored geometry yet.
+///
+/// ```ignore
+/// fn on_save_layout(mut commands: Commands) {
+///     commands.queue(bevy_window_manager::save_current_window_state);
+/// }
+///
+/// app.add_systems(OnExit(AppState::InGame), bevy_window_manager::save_current_window_state);
+/// ```
+pub fn save_current_window_state(world: &mut World) {
+    if let Err(e) = world.run_system_cached(save_current_window_state_system) {
+        warn!("[save_current_window_state] Failed to run save: {e}");
+    }
+}
+
+/// Re-read the state file and restore every open window to its saved state.
+///
+/// Uses the same restore pipeline as startup, so [`WindowRestored`](crate::WindowRestored)
+/// or [`WindowRestoreMismatch`](crate::WindowRestoreMismatch) fires for each restored
+/// window. Windows without a saved entry are left untouched.
+///
+/// Does nothing while a restore is already in progress.
+///
+/// ```ignore
+/// app.add_systems(OnEnter(AppState::InGame), bevy_window_manager::restore_window_state);
+/// ```
+pub fn restore_window_state(world: &mut World) {
+    if let Err(e) = world.run_system_cached(restore_window_state_system) {
+        warn!("[restore_window_state] Failed to run restore: {e}");
+    }
+}
+
+/// Apply the restores held under [`RestoreTrigger::Manual`](crate::RestoreTrigger::Manual).
+///
+/// Every window whose saved state was loaded while the restores were held is moved to its
+/// saved geometry, through the same pipeline as a startup restore. Windows restored after
+/// this call, such as managed windows spawned later, are restored right away. Does nothing
+/// under `RestoreTrigger::Startup` or when already triggered.
+///
+/// ```ignore
+/// app.add_systems(OnEnter(AppState::Ready), bevy_window_manager::trigger_restore);
+/// ```
+pub fn trigger_restore(world: &mut World) {
+    let Some(mut restore_gate) = world.get_resource_mut::<RestoreGate>() else {
+        warn!("[trigger_restore] RestoreGate not available, skipping");
+        return;
+    };
+    if !restore_gate.trigger() {
+        debug!("[trigger_restore] Restores already triggered");
+        return;
+    }
+    debug!("[trigger_restore] Applying held restores");
+    let mut primary_window = world.query_filtered::<(), With<PrimaryWindow>>();
+    if primary_window.iter(world).next().is_some()
+        && let Err(e) = world.run_system_cached(restore::move_to_target_monitor)
+    {
+        warn!("[trigger_restore] Failed to move the primary window: {e}");
+    }
+}
+
+/// Make `profile` the active layout profile and restore every open window from it.
+///
+/// Window changes are saved under the active profile, so from then on they are written
+/// to `profile`. Under [`SavePolicy::OnExit`](crate::SavePolicy::OnExit), changes still
+/// pending for the outgoing profile are written to it first. Windows without an entry in
+/// `profile` stay where they are and are saved under it on their next change.
+///
+/// Each restored window gets a fresh restore, exactly like startup. Does nothing while a
+/// restore is in progress.
+///
+/// ```ignore
+/// fn on_presentation_mode(mut commands: Commands) {
+///     commands.queue(bevy_window_manager::switch_profile("presentation"));
+/// }
+/// ```
+pub fn switch_profile(profile: impl Into<String>) -> impl FnOnce(&mut World) + Send + 'static {
+    let profile = profile.into();
+    move |world: &mut World| {
+        let mut restoring = world.query_filtered::<(), With<TargetPosition>>();
+        if restoring.iter(world).next().is_some() {
+            warn!("[switch_profile] Restore in progress, not switching to \"{profile}\"");
+            return;
+        }
+        if let Err(e) = world.run_system_cached(persistence::flush_pending_window_state) {
+            warn!("[switch_profile] Failed to save pending changes: {e}");
+        }
+
+        let Some(mut restore_window_config) = world.get_resource_mut::<RestoreWindowConfig>()
+        else {
+            warn!("[switch_profile] RestoreWindowConfig not available, skipping");
+            return;
+        };
+        debug!(
+            "[switch_profile] Switching profile \"{}\" -> \"{profile}\"",
+            restore_window_config.profile
+        );
+        restore_window_config.profile = profile;
+        // Read the state file again, picking up changes made outside this app.
+        restore_window_config.profile_cache.forget();
+        // A profile with no saved state must not leave the previous profile's snapshot
+        // behind for managed windows spawned later.
+        restore_window_config.loaded_states.clear();
+
+        restore_window_state(world);
+    }
+}
+
+/// Restore the window that changed most recently to the geometry it had before that change.
+///
+/// The plugin keeps the last few distinct window geometries in memory, across all
+/// windows, so repeated calls step further back. The restore uses the same pipeline as
+/// [`restore_window_state`], and the restored geometry is saved like any other change
+/// without being added to the history. Does nothing when there is nothing to undo, the
+/// window has since closed, or a restore is in progress.
+///
+/// ```ignore
+/// fn on_undo_layout(mut commands: Commands) {
+///     commands.queue(bevy_window_manager::undo_window_geometry);
+/// }
+/// ```
+pub fn undo_window_geometry(world: &mut World) {
+    if let Err(e) = world.run_system_cached(undo_window_geometry_system) {
+        warn!("[undo_window_geometry] Failed to run undo: {e}");
+    }
+}
+
+/// Read every saved layout without restoring anything, e.g. to list profiles in a menu.
+///
+/// Returns the window states of each profile in the configured state file, keyed by
+/// profile name. The state file is read again, so changes made outside this app are
+/// seen. Changes still queued for writing are included, and the backup is read when the
+/// file is missing or corrupt, exactly like a restore. Unlike a restore, a
+/// failure is only returned, never sent as
+/// [`WindowStateLoadFailed`](crate::WindowStateLoadFailed).
+///
+/// ```ignore
+/// fn layouts_menu(world: &mut World) {
+///     if let Ok(Some(profiles)) = bevy_window_manager::read_saved_state(world) {
+///         let names: Vec<&String> = profiles.keys().collect();
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`WindowManagerError::Io`] when the state file exists but cannot be read, and
+/// [`WindowManagerError::Deserialize`] when neither it nor its backup can be decoded.
+pub fn read_saved_state(
+    world: &World,
+) -> Result<Option<HashMap<String, HashMap<WindowKey, WindowState>>>, WindowManagerError> {
+    let Some(restore_window_config) = world.get_resource::<RestoreWindowConfig>() else {
+        warn!("[read_saved_state] RestoreWindowConfig not available, skipping");
+        return Ok(None);
+    };
+    restore_window_config.profile_cache.forget();
+    persistence::load_current_profiles(restore_window_config)
+}
+
+/// Delete the state file, its backup, any temporary file left by an interrupted write,
+/// and any mirrors so the next launch starts from default window placement.
+///
+/// A state file that does not exist is not an error. Pending background writes are
+/// dropped first so they cannot recreate the file, and under
+/// [`SavePolicy::OnExit`](crate::SavePolicy::OnExit) nothing is written at exit unless a
+/// window changes afterwards. Open windows stay where they are; a window that moves or
+/// resizes later is saved again as usual. See [`reset_window_layout`] to also move them.
+///
+/// ```ignore
+/// fn on_forget_layout(mut commands: Commands) {
+///     commands.queue(bevy_window_manager::clear_saved_state);
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`WindowManagerError::Io`] when the state file, a mirror, or a backup exists but
+/// cannot be deleted.
+pub fn clear_saved_state(world: &mut World) -> Result<(), WindowManagerError> {
+    let Some(mut restore_window_config) = world.get_resource_mut::<RestoreWindowConfig>() else {
+        warn!("[clear_saved_state] RestoreWindowConfig not available, skipping");
+        return Ok(());
+    };
+    restore_window_config.state_file_writer.discard();
+    restore_window_config.profile_cache.replace(HashMap::new());
+    restore_window_config.loaded_states.clear();
+    let path = restore_window_config.path.clone();
+    let mirror_paths = restore_window_config.mirror_paths.clone();
+    let memory_store = restore_window_config.memory_store.clone();
+    if let Some(mut window_state_cache) = world.get_resource_mut::<WindowStateCache>() {
+        window_state_cache.discard_pending_write();
+    }
+    if let Some(memory_store) = memory_store {
+        memory_store.clear();
+        debug!("[clear_saved_state] Cleared the memory store");
+        return Ok(());
+    }
+    persistence::delete_state_file(&path)?;
+    for mirror_path in &mirror_paths {
+        persistence::delete_state_file(mirror_path)?;
+    }
+    debug!(
+        "[clear_saved_state] Cleared saved state at {}",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Delete the saved state and center every persisted window on the primary monitor.
+///
+/// For recovering from a bad state file, e.g. a window stuck off screen. Fullscreen and
+/// maximized windows return to windowed first. Failures to delete the state file are
+/// logged. The windows' new placement is saved like any other change.
+///
+/// Does nothing while a restore is in progress.
+pub fn reset_window_layout(world: &mut World) {
+    let mut restoring = world.query_filtered::<(), With<TargetPosition>>();
+    if restoring.iter(world).next().is_some() {
+        warn!("[reset_window_layout] Restore in progress, skipping reset");
+        return;
+    }
+    if let Err(e) = clear_saved_state(world) {
+        warn!("[reset_window_layout] Failed to clear saved state: {e}");
+    }
+    if let Err(e) = world.run_system_cached(reset_window_layout_system) {
+        warn!("[reset_window_layout] Failed to run reset: {e}");
+    }
+}
+
+fn reset_window_layout_system(
+    monitors: Res<Monitors>,
+    mut windows: Query<&mut Window, Or<(With<PrimaryWindow>, With<ManagedWindow>)>>,
+) {
+    if monitors.is_empty() {
+        warn!("[reset_window_layout] No monitors available, skipping");
+        return;
+    }
+    let primary_index = monitors.primary().index;
+    for mut window in &mut windows {
+        window.mode = WindowMode::Windowed;
+        window.set_maximized(false);
+        window.center_on_monitor(&monitors, primary_index);
+    }
+    debug!("[reset_window_layout] Centered windows on monitor {primary_index}");
+}
+
+fn save_current_window_state_system(
+    mut commands: Commands,
+    restore_window_config: Res<RestoreWindowConfig>,
+    managed_window_persistence: Res<ManagedWindowPersistence>,
+    monitors: Res<Monitors>,
+    all_windows: Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: Query<(), With<PrimaryWindow>>,
+    restoring: Query<(), With<TargetPosition>>,
+    mut window_state_cache: ResMut<WindowStateCache>,
+) {
+    if !restoring.is_empty() {
+        warn!("[save_current_window_state] Restore in progress, skipping save");
+        return;
+    }
+
+    persistence::save_open_window_states(
+        &restore_window_config,
+        &managed_window_persistence,
+        &monitors,
+        &all_windows,
+        &primary_query,
+        &mut window_state_cache,
+        &mut commands,
+    );
+    debug!(
+        "[save_current_window_state] Saved window state to {:?}",
+        restore_window_config.path
+    );
+}
+
+fn restore_window_state_system(
+    mut commands: Commands,
+    mut restore_window_config: ResMut<RestoreWindowConfig>,
+    monitors: Res<Monitors>,
+    winit_info: Option<Res<WinitInfo>>,
+    platform: Res<Platform>,
+    windows: Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Has<PrimaryWindow>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    relative_to: Query<&RestoreRelativeTo>,
+    current_monitors: Query<&CurrentMonitor>,
+    restoring: Query<(), With<TargetPosition>>,
+) {
+    if !restoring.is_empty() {
+        warn!("[restore_window_state] Restore already in progress, skipping");
+        return;
+    }
+    let Some(winit_info) = winit_info else {
+        warn!("[restore_window_state] WinitInfo not available, skipping");
+        return;
+    };
+    if monitors.is_empty() {
+        warn!("[restore_window_state] No monitors available, skipping");
+        return;
+    }
+    // Read the state file again, picking up changes made outside this app.
+    restore_window_config.profile_cache.forget();
+    let Some(states) =
+        persistence::load_all_states_or_notify(&restore_window_config, &mut commands)
+    else {
+        debug!("[restore_window_state] No saved state to restore");
+        return;
+    };
+
+    for (entity, window, current_monitor, managed, is_primary) in &windows {
+        let window_key = if is_primary {
+            WindowKey::Primary
+        } else if let Some(managed_window) = managed {
+            WindowKey::Managed(managed_window.name.clone())
+        } else {
+            continue;
+        };
+        let Some(window_state) = states.get(&window_key) else {
+            continue;
+        };
+
+        let starting_monitor = current_monitor.map_or_else(
+            || monitors.first(),
+            |current_monitor| &current_monitor.monitor_info,
+        );
+        debug!("[restore_window_state] Restoring \"{window_key}\" on entity {entity:?}");
+        managed::restore_saved_window(
+            entity,
+            window_key.clone(),
+            window_state,
+            &monitors,
+            &winit_info,
+            &mut commands,
+            starting_monitor,
+            window,
+            &restore_window_config,
+            *platform,
+            managed::reference_monitor(entity, &relative_to, &current_monitors),
+        );
+    }
+
+    restore_window_config.loaded_states = states;
+}
+
+fn undo_window_geometry_system(
+    mut commands: Commands,
+    restore_window_config: Res<RestoreWindowConfig>,
+    monitors: Res<Monitors>,
+    winit_info: Option<Res<WinitInfo>>,
+    platform: Res<Platform>,
+    mut geometry_history: ResMut<WindowGeometryHistory>,
+    windows: Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Has<PrimaryWindow>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    restoring: Query<(), With<TargetPosition>>,
+) {
+    if !restoring.is_empty() {
+        warn!("[undo_window_geometry] Restore in progress, skipping undo");
+        return;
+    }
+    let Some(winit_info) = winit_info else {
+        warn!("[undo_window_geometry] WinitInfo not available, skipping");
+        return;
+    };
+    if monitors.is_empty() {
+        warn!("[undo_window_geometry] No monitors available, skipping");
+        return;
+    }
+    let Some((window_key, window_state)) = geometry_history.pop_for_undo() else {
+        debug!("[undo_window_geometry] Nothing to undo");
+        return;
+    };
+
+    let window = windows
+        .iter()
+        .find(|(_, _, _, managed, is_primary)| match &window_key {
+            WindowKey::Primary => *is_primary,
+            WindowKey::Managed(name) => {
+                !*is_primary && managed.is_some_and(|managed_window| managed_window.name == *name)
+            },
+        });
+    let Some((entity, window, current_monitor, ..)) = window else {
+        debug!("[undo_window_geometry] \"{window_key}\" is no longer open");
+        geometry_history.cancel_undo(&window_key);
+        return;
+    };
+
+    let starting_monitor = current_monitor.map_or_else(
+        || monitors.first(),
+        |current_monitor| &current_monitor.monitor_info,
+    );
+    debug!("[undo_window_geometry] Restoring \"{window_key}\" on entity {entity:?}");
+    managed::restore_saved_window(
+        entity,
+        window_key,
+        &window_state,
+        &monitors,
+        &winit_info,
+        &mut commands,
+        starting_monitor,
+        window,
+        &restore_window_config,
+        *platform,
+        None,
+    );
+}
@@ -8,7 +8,6 @@ use bevy::prelude::*;
 use bevy::window::MonitorSelection;
 use bevy::window::PrimaryWindow;
 use bevy::window::WindowMode;
-use bevy::winit::WINIT_WINDOWS;
 use bevy_kana::ToI32;
 
 use super::ManagedWindow;
@@ -19,9 +18,22 @@ use crate::constants::MONITOR_SOURCE_EXISTING;
 use crate::constants::MONITOR_SOURCE_FALLBACK;
 use crate::constants::MONITOR_SOURCE_POSITION;
 use crate::constants::MONITOR_SOURCE_WINIT;
+use crate::constants::OFF_SCREEN_GRACE_SECS;
+use crate::constants::SCALE_FACTOR_EPSILON;
+use crate::persistence::get_window_position;
+use crate::persistence::is_minimized;
+use crate::restore_window_config::GreenButtonDetection;
+use crate::restore_window_config::RestoreWindowConfig;
+use crate::window_ext::WindowExt;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
 
 /// Unified monitor detection system. Maintains `CurrentMonitor` on all managed windows.
 ///
+/// Runs every frame rather than on `Changed<Window>`: an OS-initiated move, or a window
+/// snapped by the window manager, can change the monitor without Bevy updating `Window`.
+/// The component is only reinserted when the result differs.
+///
 /// Detection priority:
 /// 1. winit's `current_monitor()` — most reliable, works even before `window.position` is set
 /// 2. Position-based center-point detection — uses `window.position` when available
@@ -36,6 +48,7 @@ pub(crate) fn update_current_monitor(
         Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
     >,
     monitors: Res<Monitors>,
+    restore_window_config: Res<RestoreWindowConfig>,
     _: NonSendMarker,
 ) {
     if monitors.is_empty() {
@@ -43,7 +56,7 @@ pub(crate) fn update_current_monitor(
     }
 
     for (entity, window, existing) in &windows {
-        let winit_result = winit_detect_monitor(entity, &monitors);
+        let winit_result = winit_detect_monitor(&LiveWinitWindows, entity, &monitors);
         let position_result = if winit_result.is_none() {
             position_detect_monitor(window, &monitors)
         } else {
@@ -53,59 +66,229 @@ pub(crate) fn update_current_monitor(
         let (monitor_info, source) = match (winit_result, position_result, existing) {
             (Some(monitor_info), _, _) => (monitor_info, MONITOR_SOURCE_WINIT),
             (_, Some(monitor_info), _) => (monitor_info, MONITOR_SOURCE_POSITION),
-            (_, _, Some(current_monitor)) => {
-                (current_monitor.monitor_info, MONITOR_SOURCE_EXISTING)
-            },
-            _ => (*monitors.first(), MONITOR_SOURCE_FALLBACK),
+            (_, _, Some(current_monitor)) => (
+                current_monitor.monitor_info.clone(),
+                MONITOR_SOURCE_EXISTING,
+            ),
+            _ => (monitors.first().clone(), MONITOR_SOURCE_FALLBACK),
         };
 
         // Compute effective window mode.
-        let effective_window_mode = compute_effective_window_mode(window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            window,
+            &monitor_info,
+            &monitors,
+            restore_window_config.green_button_detection,
+        );
 
         let new_current = CurrentMonitor {
             monitor_info,
             effective_window_mode,
         };
 
-        // Only insert if changed to avoid unnecessary change detection triggers
+        // Only insert if changed to avoid unnecessary change detection triggers. A rescaled
+        // monitor keeps its index, so its scale and size are compared too.
         let changed = existing.is_none_or(|current_monitor| {
             current_monitor.monitor_info.index != new_current.monitor_info.index
+                || (current_monitor.monitor_info.scale - new_current.monitor_info.scale).abs()
+                    > SCALE_FACTOR_EPSILON
+                || current_monitor.monitor_info.physical_size
+                    != new_current.monitor_info.physical_size
                 || current_monitor.effective_window_mode != new_current.effective_window_mode
         });
 
         if changed {
             debug!(
                 "[update_current_monitor] source={} index={} scale={} effective_window_mode={:?}",
-                source, monitor_info.index, monitor_info.scale, effective_window_mode
+                source,
+                new_current.monitor_info.index,
+                new_current.monitor_info.scale,
+                effective_window_mode
             );
             commands.entity(entity).insert(new_current);
         }
     }
 }
 
+/// Move windows whose monitor was disconnected onto the primary monitor.
+///
+/// Runs when `Monitors` changes, before `update_current_monitor` reassigns
+/// `CurrentMonitor`, so it still names the monitor each window was on. Windowed windows
+/// are centered with [`WindowExt::center_on_monitor`], which applies the same cross-DPI
+/// compensation as restore; fullscreen windows keep their mode on the primary monitor.
+pub(crate) fn move_windows_off_disconnected_monitors(
+    mut windows: Query<
+        (&mut Window, &CurrentMonitor),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    monitors: Res<Monitors>,
+) {
+    if monitors.is_empty() {
+        return;
+    }
+    let primary_index = monitors.primary().index;
+
+    for (mut window, current_monitor) in &mut windows {
+        if is_connected(&current_monitor.monitor_info, &monitors) {
+            continue;
+        }
+        info!(
+            "[move_windows_off_disconnected_monitors] Monitor {} ({:?}) disconnected, moving window to primary monitor {primary_index}",
+            current_monitor.index, current_monitor.name,
+        );
+        match window.mode {
+            WindowMode::Windowed => {
+                window.center_on_monitor(&monitors, primary_index);
+            },
+            WindowMode::BorderlessFullscreen(_) => {
+                window.mode =
+                    WindowMode::BorderlessFullscreen(MonitorSelection::Index(primary_index));
+            },
+            WindowMode::Fullscreen(_, video_mode_selection) => {
+                window.mode = WindowMode::Fullscreen(
+                    MonitorSelection::Index(primary_index),
+                    video_mode_selection,
+                );
+            },
+        }
+    }
+}
+
+/// A windowed window seen overlapping no monitor, and where and when it was first seen
+/// there.
+#[derive(Component)]
+pub(crate) struct OffScreenSince {
+    physical_position: IVec2,
+    since_secs:        f32,
+}
+
+impl OffScreenSince {
+    const fn new(physical_position: IVec2, since_secs: f32) -> Self {
+        Self {
+            physical_position,
+            since_secs,
+        }
+    }
+
+    /// Whether the window has stayed at `physical_position` for `OFF_SCREEN_GRACE_SECS`.
+    /// A new position restarts the wait.
+    fn grace_elapsed(&mut self, physical_position: IVec2, now_secs: f32) -> bool {
+        if self.physical_position != physical_position {
+            *self = Self::new(physical_position, now_secs);
+            return false;
+        }
+        now_secs - self.since_secs >= OFF_SCREEN_GRACE_SECS
+    }
+}
+
+/// Move windowed windows that overlap no monitor onto the monitor closest to them
+/// (`OffScreenRecovery::Enabled`).
+///
+/// Runs every frame, but a window is only moved once it has stayed off screen at the same
+/// position for `OFF_SCREEN_GRACE_SECS`, so one being dragged past the edge of the desktop
+/// is not pulled back mid-gesture. Minimized windows, which Windows parks far off screen,
+/// and windows without a known position (Wayland) are left alone.
+pub(crate) fn recover_off_screen_windows(
+    mut commands: Commands,
+    mut windows: Query<
+        (Entity, &mut Window, Option<&mut OffScreenSince>),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    monitors: Res<Monitors>,
+    time: Res<Time<Real>>,
+    _: NonSendMarker,
+) {
+    if monitors.is_empty() {
+        return;
+    }
+    let now_secs = time.elapsed_secs();
+
+    for (entity, mut window, off_screen_since) in &mut windows {
+        let Some(physical_rect) = off_screen_rect(&LiveWinitWindows, entity, &window, &monitors)
+            .filter(|_| !is_minimized(&LiveWinitWindows, entity))
+        else {
+            if off_screen_since.is_some() {
+                commands.entity(entity).remove::<OffScreenSince>();
+            }
+            continue;
+        };
+        let Some(mut off_screen_since) = off_screen_since else {
+            commands
+                .entity(entity)
+                .insert(OffScreenSince::new(physical_rect.min, now_secs));
+            continue;
+        };
+        if !off_screen_since.grace_elapsed(physical_rect.min, now_secs) {
+            continue;
+        }
+
+        let physical_center = physical_rect.center();
+        let target_index = monitors
+            .closest_to(physical_center.x, physical_center.y)
+            .index;
+        info!(
+            "[recover_off_screen_windows] Window at {} overlaps no monitor, moving it to monitor {target_index}",
+            physical_rect.min
+        );
+        window.center_on_monitor(&monitors, target_index);
+        commands.entity(entity).remove::<OffScreenSince>();
+    }
+}
+
+/// Physical rect of a windowed window that overlaps no monitor. `None` when it overlaps
+/// one, is not windowed, or has no known position.
+fn off_screen_rect(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    window: &Window,
+    monitors: &Monitors,
+) -> Option<IRect> {
+    if window.mode != WindowMode::Windowed {
+        return None;
+    }
+    let physical_position = get_window_position(winit_windows, entity, window)?;
+    let physical_rect = IRect::from_corners(
+        physical_position,
+        physical_position + window.resolution.physical_size().as_ivec2(),
+    );
+    (!monitors.any_overlap(physical_rect)).then_some(physical_rect)
+}
+
+/// Whether `monitor_info` is still connected.
+///
+/// Bevy keeps a monitor's `Monitor` entity for as long as it stays connected, so it is
+/// matched by entity, which tells apart two monitors sharing a name. A monitor removed
+/// and added again has a new entity and is matched by its name, position, and size.
+fn is_connected(monitor_info: &MonitorInfo, monitors: &Monitors) -> bool {
+    monitors
+        .iter()
+        .any(|monitor| monitor.entity == monitor_info.entity || monitor.is_unchanged(monitor_info))
+}
+
 /// Detect monitor via winit's `current_monitor()`.
-fn winit_detect_monitor(entity: Entity, monitors: &Monitors) -> Option<MonitorInfo> {
-    WINIT_WINDOWS.with(|winit_windows| {
-        let winit_windows = winit_windows.borrow();
-        winit_windows.get_window(entity).and_then(|winit_window| {
-            winit_window.current_monitor().and_then(|current_monitor| {
-                let physical_position = current_monitor.position();
-                monitors
-                    .at(physical_position.x, physical_position.y)
-                    .copied()
-            })
-        })
-    })
+fn winit_detect_monitor(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    monitors: &Monitors,
+) -> Option<MonitorInfo> {
+    let physical_position = winit_windows.current_monitor_position(entity)?;
+    monitors
+        .at(physical_position.x, physical_position.y)
+        .cloned()
 }
 
 /// Detect monitor from `window.position` using center-point logic.
 fn position_detect_monitor(window: &Window, monitors: &Monitors) -> Option<MonitorInfo> {
     if let WindowPosition::At(physical_position) = window.position {
-        Some(*monitors.monitor_for_window(
-            physical_position,
-            window.physical_width(),
-            window.physical_height(),
-        ))
+        Some(
+            monitors
+                .monitor_for_window(
+                    physical_position,
+                    window.physical_width(),
+                    window.physical_height(),
+                )
+                .clone(),
+        )
     } else {
         None
     }
@@ -114,17 +297,23 @@ fn position_detect_monitor(window: &Window, monitors: &Monitors) -> Option<Monit
 /// Compute the effective window mode, including macOS green button detection.
 ///
 /// On macOS, clicking the green "maximize" button fills the screen but `window.mode`
-/// remains `Windowed`. This detects that case and returns `BorderlessFullscreen`.
+/// remains `Windowed`. This detects that case and returns `BorderlessFullscreen`, unless
+/// `green_button_detection` is `Disabled`.
 fn compute_effective_window_mode(
     window: &Window,
     monitor_info: &MonitorInfo,
     monitors: &Monitors,
+    green_button_detection: GreenButtonDetection,
 ) -> WindowMode {
     // Trust exclusive fullscreen - OS manages this mode
     if matches!(window.mode, WindowMode::Fullscreen(_, _)) {
         return window.mode;
     }
 
+    if green_button_detection == GreenButtonDetection::Disabled {
+        return window.mode;
+    }
+
     // Can't determine effective mode without monitors
     if monitors.is_empty() {
         return window.mode;
@@ -156,13 +345,14 @@ mod tests {
     use bevy::window::WindowPosition;
 
     use super::*;
+    use crate::test_support;
+    use crate::test_support::FakeWinitWindow;
 
     fn monitor_0() -> MonitorInfo {
         MonitorInfo {
-            index:             0,
-            scale:             2.0,
-            physical_position: IVec2::ZERO,
-            physical_size:     UVec2::new(3456, 2234),
+            scale: 2.0,
+            physical_size: UVec2::new(3456, 2234),
+            ..test_support::monitor(0, 0)
         }
     }
 
@@ -184,39 +374,120 @@ mod tests {
         window
     }
 
+    /// `off_screen_rect` for a window at `physical_position`, reported there by both
+    /// `Window` and winit, so it reads the same on every platform.
+    fn off_screen_rect_at(
+        physical_position: IVec2,
+        window_mode: WindowMode,
+        monitors: &Monitors,
+    ) -> Option<IRect> {
+        let mut window = window_at(physical_position, 800, 600);
+        window.mode = window_mode;
+        let winit_window = FakeWinitWindow {
+            physical_outer_position: Some(physical_position),
+            ..default()
+        };
+        off_screen_rect(&winit_window, Entity::PLACEHOLDER, &window, monitors)
+    }
+
+    #[test]
+    fn off_screen_rect_only_for_windows_overlapping_no_monitor() {
+        let monitors = monitors_with(monitor_0());
+
+        assert_eq!(
+            off_screen_rect_at(IVec2::new(-790, 100), WindowMode::Windowed, &monitors),
+            None
+        );
+        assert_eq!(
+            off_screen_rect_at(IVec2::new(-800, 100), WindowMode::Windowed, &monitors),
+            Some(IRect::new(-800, 100, 0, 700))
+        );
+        assert_eq!(
+            off_screen_rect_at(
+                IVec2::new(-800, 100),
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+                &monitors
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn off_screen_grace_restarts_when_the_window_moves() {
+        let mut off_screen_since = OffScreenSince::new(IVec2::new(-800, 100), 0.0);
+
+        assert!(!off_screen_since.grace_elapsed(IVec2::new(-800, 100), 0.5));
+        assert!(!off_screen_since.grace_elapsed(IVec2::new(-900, 100), OFF_SCREEN_GRACE_SECS));
+        assert!(!off_screen_since.grace_elapsed(IVec2::new(-900, 100), OFF_SCREEN_GRACE_SECS));
+        assert!(off_screen_since.grace_elapsed(IVec2::new(-900, 100), 2.0 * OFF_SCREEN_GRACE_SECS));
+
+        // Moving after the grace elapsed restarts it too.
+        assert!(
+            !off_screen_since.grace_elapsed(IVec2::new(-800, 100), 2.0 * OFF_SCREEN_GRACE_SECS)
+        );
+        assert!(off_screen_since.grace_elapsed(IVec2::new(-800, 100), 3.0 * OFF_SCREEN_GRACE_SECS));
+    }
+
     #[test]
     fn effective_window_mode_fullscreen_when_window_fills_monitor() {
         let monitor_info = monitor_0();
-        let monitors = monitors_with(monitor_info);
+        let monitors = monitors_with(monitor_info.clone());
         let window = window_at(
             monitor_info.physical_position,
             monitor_info.physical_size.x,
             monitor_info.physical_size.y,
         );
 
-        let effective_window_mode =
-            compute_effective_window_mode(&window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Enabled,
+        );
         assert_eq!(
             effective_window_mode,
             WindowMode::BorderlessFullscreen(MonitorSelection::Index(0))
         );
     }
 
+    #[test]
+    fn effective_window_mode_windowed_when_green_button_detection_disabled() {
+        let monitor_info = monitor_0();
+        let monitors = monitors_with(monitor_info.clone());
+        let window = window_at(
+            monitor_info.physical_position,
+            monitor_info.physical_size.x,
+            monitor_info.physical_size.y,
+        );
+
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Disabled,
+        );
+        assert_eq!(effective_window_mode, WindowMode::Windowed);
+    }
+
     #[test]
     fn effective_window_mode_windowed_when_window_smaller_than_monitor() {
         let monitor_info = monitor_0();
-        let monitors = monitors_with(monitor_info);
+        let monitors = monitors_with(monitor_info.clone());
         let window = window_at(IVec2::new(100, 100), 1600, 1200);
 
-        let effective_window_mode =
-            compute_effective_window_mode(&window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Enabled,
+        );
         assert_eq!(effective_window_mode, WindowMode::Windowed);
     }
 
     #[test]
     fn effective_window_mode_windowed_when_not_left_aligned() {
         let monitor_info = monitor_0();
-        let monitors = monitors_with(monitor_info);
+        let monitors = monitors_with(monitor_info.clone());
         // Full width + reaches bottom, but offset from left edge
         let window = window_at(
             IVec2::new(1, 0),
@@ -224,21 +495,29 @@ mod tests {
             monitor_info.physical_size.y,
         );
 
-        let effective_window_mode =
-            compute_effective_window_mode(&window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Enabled,
+        );
         assert_eq!(effective_window_mode, WindowMode::Windowed);
     }
 
     #[test]
     fn effective_window_mode_trusts_exclusive_fullscreen() {
         let monitor_info = monitor_0();
-        let monitors = monitors_with(monitor_info);
+        let monitors = monitors_with(monitor_info.clone());
         let mut window = window_at(IVec2::ZERO, 800, 600);
         window.mode =
             WindowMode::Fullscreen(MonitorSelection::Index(0), VideoModeSelection::Current);
 
-        let effective_window_mode =
-            compute_effective_window_mode(&window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Enabled,
+        );
         assert!(matches!(
             effective_window_mode,
             WindowMode::Fullscreen(_, _)
@@ -248,15 +527,19 @@ mod tests {
     #[test]
     fn effective_window_mode_returns_mode_when_no_position() {
         let monitor_info = monitor_0();
-        let monitors = monitors_with(monitor_info);
+        let monitors = monitors_with(monitor_info.clone());
         let mut window = Window::default();
         window
             .resolution
             .set_physical_resolution(monitor_info.physical_size.x, monitor_info.physical_size.y);
         // position is Automatic (no position available, like Wayland)
 
-        let effective_window_mode =
-            compute_effective_window_mode(&window, &monitor_info, &monitors);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &monitors,
+            GreenButtonDetection::Enabled,
+        );
         assert_eq!(effective_window_mode, WindowMode::Windowed);
     }
 
@@ -270,7 +553,55 @@ mod tests {
             monitor_info.physical_size.y,
         );
 
-        let effective_window_mode = compute_effective_window_mode(&window, &monitor_info, &empty);
+        let effective_window_mode = compute_effective_window_mode(
+            &window,
+            &monitor_info,
+            &empty,
+            GreenButtonDetection::Enabled,
+        );
         assert_eq!(effective_window_mode, WindowMode::Windowed);
     }
+
+    #[test]
+    fn disconnected_monitor_is_detected_by_entity_then_name_and_geometry() {
+        let mut world = World::new();
+        let [entity, respawned] = [(); 2].map(|()| world.spawn_empty().id());
+        let connected = MonitorInfo {
+            entity,
+            ..monitor_0()
+        };
+        let monitors = monitors_with(connected.clone());
+        assert!(is_connected(&connected, &monitors));
+
+        let mut moved = connected.clone();
+        moved.physical_position = IVec2::new(3456, 0);
+        assert!(is_connected(&moved, &monitors));
+
+        let reconnected = monitors_with(MonitorInfo {
+            entity: respawned,
+            ..connected.clone()
+        });
+        assert!(is_connected(&connected, &reconnected));
+        assert!(!is_connected(&moved, &reconnected));
+    }
+
+    #[test]
+    fn unplugging_one_of_two_monitors_sharing_a_name_is_detected() {
+        let mut world = World::new();
+        let [left_entity, right_entity] = [(); 2].map(|()| world.spawn_empty().id());
+        let left = MonitorInfo {
+            name: Some("DELL U2720Q".to_string()),
+            entity: left_entity,
+            ..monitor_0()
+        };
+        let right = MonitorInfo {
+            index: 1,
+            physical_position: IVec2::new(3456, 0),
+            entity: right_entity,
+            ..left.clone()
+        };
+        let monitors = monitors_with(left.clone());
+        assert!(is_connected(&left, &monitors));
+        assert!(!is_connected(&right, &monitors));
+    }
 }
@@ -1,10 +1,14 @@
 //! Monitor management for window restoration.
 //!
-//! Provides a `Monitors` resource that maintains a sorted list of monitors,
-//! automatically updated when monitors are added or removed.
+//! Provides a `Monitors` resource that maintains an ordered list of monitors,
+//! automatically updated when monitors are added, removed, moved, resized, or rescaled.
 
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ops::Deref;
 
+use bevy::ecs::system::NonSendMarker;
 use bevy::prelude::*;
 use bevy::window::Monitor;
 use bevy::window::PrimaryWindow;
@@ -12,32 +16,134 @@ use bevy::window::WindowMode;
 use bevy_diagnostic::FrameCount;
 use bevy_kana::ToI32;
 
+use crate::MonitorConfigurationChanged;
+use crate::persistence::SavedVideoMode;
+use crate::platform::Platform;
+use crate::scale_factor_changes::ScaleFactorChanges;
+use crate::scale_factor_changes::collect_scale_factor_changes;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
+use crate::work_area;
+
 /// Plugin that manages the `Monitors` resource.
 pub(crate) struct MonitorPlugin;
 
 impl Plugin for MonitorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreStartup, init_monitors)
+        app.init_resource::<ScaleFactorChanges>()
+            .add_systems(PreStartup, init_monitors)
+            .add_systems(First, collect_scale_factor_changes)
             .add_systems(Update, update_monitors);
     }
 }
 
 /// Information about a single monitor.
-#[derive(Clone, Copy, Debug, Reflect)]
+#[derive(Clone, Debug, Reflect)]
 pub struct MonitorInfo {
-    /// Index in the sorted monitor list.
-    pub index:             usize,
+    /// Index in the monitor list, matching `MonitorSelection::Index`.
+    pub index:                   usize,
     /// Scale factor (typically 1.0 or 2.0 on macOS).
-    pub scale:             f64,
+    pub scale:                   f64,
     /// Top-left corner of the monitor.
-    pub physical_position: IVec2,
+    pub physical_position:       IVec2,
     /// Monitor dimensions in pixels.
-    pub physical_size:     UVec2,
+    pub physical_size:           UVec2,
+    /// Human-readable monitor name reported by the OS, if available.
+    ///
+    /// Stable across sessions, unlike `index`, which depends on enumeration order, but not
+    /// unique: two monitors of the same model can report the same name.
+    pub name:                    Option<String>,
+    /// Usable area excluding the menu bar, Dock, taskbar, or panels, in physical pixels.
+    ///
+    /// `None` when the platform cannot report it (Wayland, or a failed query).
+    pub physical_work_area:      Option<IRect>,
+    /// Refresh rate in millihertz, when the platform reports it.
+    pub refresh_rate_millihertz: Option<u32>,
+    /// Video modes available for exclusive fullscreen, in the order winit lists them.
+    pub video_modes:             Vec<SavedVideoMode>,
+    /// Bevy's `Monitor` entity for this monitor, for joining with its ECS components.
+    pub entity:                  Entity,
 }
 
-/// Sorted monitor list, updated when monitors change.
+impl MonitorInfo {
+    /// Full monitor bounds in physical pixels.
+    #[must_use]
+    pub fn physical_bounds(&self) -> IRect {
+        IRect::from_corners(
+            self.physical_position,
+            self.physical_position + self.physical_size.as_ivec2(),
+        )
+    }
+
+    /// The work area if known, otherwise the full monitor bounds.
+    #[must_use]
+    pub fn physical_usable_bounds(&self) -> IRect {
+        self.physical_work_area
+            .unwrap_or_else(|| self.physical_bounds())
+    }
+
+    /// Area in physical pixels where `physical_rect` overlaps this monitor's full bounds.
+    #[must_use]
+    pub fn physical_overlap_area(&self, physical_rect: IRect) -> u64 {
+        let overlap = self.physical_bounds().intersect(physical_rect);
+        if overlap.is_empty() {
+            0
+        } else {
+            u64::from(overlap.width().unsigned_abs()) * u64::from(overlap.height().unsigned_abs())
+        }
+    }
+
+    /// Top-left corner in this monitor's logical pixels.
+    pub(crate) fn logical_origin(&self) -> (i32, i32) {
+        (
+            (f64::from(self.physical_position.x) / self.scale)
+                .round()
+                .to_i32(),
+            (f64::from(self.physical_position.y) / self.scale)
+                .round()
+                .to_i32(),
+        )
+    }
+
+    /// Whether `other` describes the same physical monitor: matched by name when both
+    /// have one, otherwise by position and size.
+    pub(crate) fn is_same_monitor(&self, other: &Self) -> bool {
+        match (self.name.as_deref(), other.name.as_deref()) {
+            (Some(name), Some(other_name)) => name == other_name,
+            _ => {
+                self.physical_position == other.physical_position
+                    && self.physical_size == other.physical_size
+            },
+        }
+    }
+
+    /// Whether `other` is the same monitor with the same name, position, and size.
+    pub(crate) fn is_unchanged(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.physical_position == other.physical_position
+            && self.physical_size == other.physical_size
+    }
+
+    /// Offset of `physical_position` from this monitor's top-left, in logical pixels.
+    pub(crate) fn logical_offset(&self, physical_position: IVec2) -> (i32, i32) {
+        let physical_offset = physical_position - self.physical_position;
+        (
+            (f64::from(physical_offset.x) / self.scale).round().to_i32(),
+            (f64::from(physical_offset.y) / self.scale).round().to_i32(),
+        )
+    }
+}
+
+/// Monitor list, updated when monitors change.
+///
+/// Monitors are kept in winit's enumeration order, so a monitor's position in the list is
+/// its [`MonitorInfo::index`] and matches `MonitorSelection::Index` for the same monitor.
+/// Build monitor pickers against [`Monitors::iter`] to use the same indices the plugin
+/// saves and restores with.
 ///
-/// `Monitors` are sorted with primary (at 0,0) first, then by position.
+/// The order is not sorted by position and can change when displays are connected or
+/// disconnected. Use [`Monitors::primary`] for the OS primary monitor rather than
+/// assuming it is at index 0.
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct Monitors {
@@ -47,8 +153,10 @@ pub struct Monitors {
 /// Component storing the current monitor and effective window mode.
 ///
 /// This is the single source of truth for which monitor a window is on and its
-/// effective display mode. Updated automatically by the plugin's unified monitor
-/// detection system.
+/// effective display mode. Refreshed every frame in
+/// [`WindowManagerSet::Save`](crate::WindowManagerSet::Save) from the monitor winit
+/// reports for the window, so it follows moves the OS makes without changing `Window`.
+/// Systems that need this frame's value run `.after(WindowManagerSet::Save)`.
 ///
 /// The `effective_window_mode` field reflects what the user actually sees, even when
 /// `window.mode` is stale (e.g., macOS green button fullscreen reports `Windowed`).
@@ -60,7 +168,7 @@ pub struct Monitors {
 ///     println!("Monitor {} at scale {}, mode: {:?}", monitor.index, monitor.scale, monitor.effective_window_mode);
 /// }
 /// ```
-#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[derive(Component, Clone, Debug, Reflect)]
 #[reflect(Component)]
 pub struct CurrentMonitor {
     /// The monitor this window is currently on.
@@ -89,10 +197,97 @@ impl Monitors {
         })
     }
 
-    /// Get monitor by index in sorted list.
+    /// Whether any monitor contains position `(physical_x, physical_y)`.
+    ///
+    /// Coordinates are physical pixels — winit's monitor coordinate space.
+    #[must_use]
+    pub fn contains_point(&self, physical_x: i32, physical_y: i32) -> bool {
+        self.at(physical_x, physical_y).is_some()
+    }
+
+    /// Area in physical pixels where `physical_rect` overlaps the monitor at `index`. Zero
+    /// when no monitor has that index.
+    #[must_use]
+    pub fn overlap_area(&self, physical_rect: IRect, index: usize) -> u64 {
+        self.by_index(index)
+            .map_or(0, |monitor| monitor.physical_overlap_area(physical_rect))
+    }
+
+    /// Whether `physical_rect` overlaps any monitor, e.g. to tell a window that is at
+    /// least partly visible from one that is entirely off screen.
+    #[must_use]
+    pub fn any_overlap(&self, physical_rect: IRect) -> bool {
+        self.list
+            .iter()
+            .any(|monitor| monitor.physical_overlap_area(physical_rect) > 0)
+    }
+
+    /// Get monitor by index in the list.
     #[must_use]
     pub fn by_index(&self, index: usize) -> Option<&MonitorInfo> { self.list.get(index) }
 
+    /// Monitor whose Bevy `Monitor` component is on `entity`.
+    #[must_use]
+    pub fn by_entity(&self, entity: Entity) -> Option<&MonitorInfo> {
+        self.list.iter().find(|monitor| monitor.entity == entity)
+    }
+
+    /// Video modes the monitor at `index` supports for exclusive fullscreen. Empty when no
+    /// monitor has that index or the platform lists no modes.
+    #[must_use]
+    pub fn video_modes(&self, index: usize) -> &[SavedVideoMode] {
+        self.by_index(index)
+            .map_or(&[], |monitor| monitor.video_modes.as_slice())
+    }
+
+    /// Scale factor of the monitor at `index`.
+    #[must_use]
+    pub fn scale_for(&self, index: usize) -> Option<f64> {
+        self.by_index(index).map(|monitor| monitor.scale)
+    }
+
+    /// Refresh rate in millihertz of the monitor at `index`, when the platform reports it.
+    #[must_use]
+    pub fn refresh_rate(&self, index: usize) -> Option<u32> {
+        self.by_index(index)
+            .and_then(|monitor| monitor.refresh_rate_millihertz)
+    }
+
+    /// Get the first monitor with the name the OS reports for it.
+    ///
+    /// Names can collide when two monitors of the same model are connected.
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<&MonitorInfo> {
+        self.list
+            .iter()
+            .find(|monitor| monitor.name.as_deref() == Some(name))
+    }
+
+    /// Hash of every monitor's name, position, size, scale, and refresh rate, in index
+    /// order. Two lists with the same fingerprint describe the same monitor layout.
+    ///
+    /// Only comparable within one run of the app; the value is not stable across builds.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.list.len().hash(&mut hasher);
+        for monitor in &self.list {
+            monitor.name.hash(&mut hasher);
+            monitor.physical_position.hash(&mut hasher);
+            monitor.physical_size.hash(&mut hasher);
+            monitor.scale.to_bits().hash(&mut hasher);
+            monitor.refresh_rate_millihertz.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Number of connected monitors.
+    #[must_use]
+    pub const fn len(&self) -> usize { self.list.len() }
+
+    /// Iterate over the monitors in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &MonitorInfo> { self.list.iter() }
+
     /// Returns true if no monitors are available.
     ///
     /// This can happen when the laptop lid is closed or all displays are disconnected.
@@ -115,6 +310,22 @@ impl Monitors {
             .expect("Monitors::first() requires at least one monitor")
     }
 
+    /// Get the primary monitor: the one at the desktop origin `(0, 0)`, or index 0 if no
+    /// monitor is there.
+    ///
+    /// Windows and macOS always place the primary monitor at the origin; X11 usually does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no monitors exist (should never happen on a real system).
+    #[must_use]
+    pub fn primary(&self) -> &MonitorInfo {
+        self.list
+            .iter()
+            .find(|monitor| monitor.physical_position == IVec2::ZERO)
+            .unwrap_or_else(|| self.first())
+    }
+
     /// Find the monitor a window is on, using window center for detection.
     ///
     /// Uses the center point to correctly handle windows spanning monitor boundaries
@@ -133,6 +344,34 @@ impl Monitors {
         self.closest_to(physical_center_x, physical_center_y)
     }
 
+    /// Find the monitor holding the largest part of `physical_rect`, e.g. a window's
+    /// bounds. A window spanning two monitors belongs to the one showing most of it.
+    ///
+    /// Falls back to the monitor closest to the rect's center when it overlaps none.
+    ///
+    /// Coordinates are physical pixels — winit's monitor coordinate space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no monitors exist (should never happen on a real system).
+    #[must_use]
+    pub fn monitor_with_most_overlap(&self, physical_rect: IRect) -> &MonitorInfo {
+        self.list
+            .iter()
+            .map(|monitor| (monitor, monitor.physical_overlap_area(physical_rect)))
+            // Reversed so the first of several equal overlaps wins.
+            .rev()
+            .max_by_key(|(_, overlap_area)| *overlap_area)
+            .filter(|(_, overlap_area)| *overlap_area > 0)
+            .map_or_else(
+                || {
+                    let physical_center = physical_rect.center();
+                    self.closest_to(physical_center.x, physical_center.y)
+                },
+                |(monitor, _)| monitor,
+            )
+    }
+
     /// Find the monitor at position, or the closest one if outside all bounds.
     ///
     /// Unlike [`at`](Self::at), this always returns a monitor by finding
@@ -178,39 +417,66 @@ impl Monitors {
                     0
                 };
 
-                dx * dx + dy * dy
+                // Widened so far off-screen positions (e.g. Windows' minimized
+                // `-32000`) cannot overflow the squared distance.
+                i64::from(dx).pow(2) + i64::from(dy).pow(2)
             })
             .expect("Monitors::closest_to() requires at least one monitor")
     }
 }
 
+impl<'a> IntoIterator for &'a Monitors {
+    type IntoIter = std::slice::Iter<'a, MonitorInfo>;
+    type Item = &'a MonitorInfo;
+
+    fn into_iter(self) -> Self::IntoIter { self.list.iter() }
+}
+
 /// Build monitor list from query (preserves winit enumeration order).
-fn build_monitors(monitors: &Query<&Monitor>) -> Monitors {
-    let list: Vec<_> = monitors
+fn build_monitors(monitors: &Query<(Entity, &Monitor)>, platform: Platform) -> Monitors {
+    let mut list: Vec<_> = monitors
         .iter()
         .enumerate()
-        .map(|(idx, monitor)| MonitorInfo {
-            index:             idx,
-            scale:             monitor.scale_factor,
+        .map(|(idx, (entity, monitor))| MonitorInfo {
+            index: idx,
+            scale: monitor.scale_factor,
             physical_position: monitor.physical_position,
-            physical_size:     monitor.physical_size(),
+            physical_size: monitor.physical_size(),
+            name: monitor.name.clone(),
+            physical_work_area: None,
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz,
+            video_modes: monitor
+                .video_modes
+                .iter()
+                .map(SavedVideoMode::from)
+                .collect(),
+            entity,
         })
         .collect();
+    work_area::apply_work_areas(&mut list, platform);
 
     Monitors { list }
 }
 
 /// Initialize `Monitors` resource at startup.
-pub(crate) fn init_monitors(mut commands: Commands, monitors: Query<&Monitor>) {
-    let monitors_resource = build_monitors(&monitors);
+///
+/// Runs on the main thread because macOS work area queries require it.
+pub(crate) fn init_monitors(
+    mut commands: Commands,
+    monitors: Query<(Entity, &Monitor)>,
+    platform: Res<Platform>,
+    _: NonSendMarker,
+) {
+    let monitors_resource = build_monitors(&monitors, *platform);
     debug!(
         "[init_monitors] Found {} monitors",
         monitors_resource.list.len()
     );
     for monitor in &monitors_resource.list {
         debug!(
-            "[init_monitors] Monitor {}: position=({}, {}) size={}x{} scale={}",
+            "[init_monitors] Monitor {} {:?}: position=({}, {}) size={}x{} scale={}",
             monitor.index,
+            monitor.name,
             monitor.physical_position.x,
             monitor.physical_position.y,
             monitor.physical_size.x,
@@ -221,34 +487,370 @@ pub(crate) fn init_monitors(mut commands: Commands, monitors: Query<&Monitor>) {
     commands.insert_resource(monitors_resource);
 }
 
-/// Update `Monitors` resource when monitors are added or removed.
+/// Rebuild `monitors` from what winit reports now, keeping each monitor's index and
+/// work area.
+///
+/// Bevy's `Monitor` components are spawned and despawned but never updated, so a scale
+/// or resolution changed in the OS display settings while the app runs is only visible
+/// through winit. Returns `None` when no winit window exists yet.
+fn live_monitors(winit_windows: &impl WinitWindowAccess, monitors: &Monitors) -> Option<Monitors> {
+    let live_list = winit_windows.available_monitors()?;
+    let pairs = pair_monitors(&monitors.list, &live_list);
+    let mut list = monitors.list.clone();
+    for (monitor, live_index) in list.iter_mut().zip(pairs) {
+        let Some(live) = live_index.map(|live_index| &live_list[live_index]) else {
+            continue;
+        };
+        monitor.scale = live.scale;
+        monitor.physical_position = live.physical_position;
+        monitor.physical_size = live.physical_size;
+        monitor.refresh_rate_millihertz = live.refresh_rate_millihertz;
+    }
+    Some(Monitors { list })
+}
+
+/// Update `Monitors` resource when monitors are added, removed, moved, resized, or
+/// rescaled, and trigger [`MonitorConfigurationChanged`] when it is replaced.
+///
+/// A monitor rescaled in the OS display settings sends `WindowScaleFactorChanged` to the
+/// windows on it, which is when winit is asked for the current geometry. The rebuilt list
+/// only replaces `Monitors` when its [`fingerprint`](Monitors::fingerprint) differs, so an
+/// addition and removal that cancel out, or a scale message that changed nothing, leave
+/// it untouched.
 fn update_monitors(
     mut commands: Commands,
-    monitors: Query<&Monitor>,
+    mut previous_monitors: ResMut<Monitors>,
+    monitors: Query<(Entity, &Monitor)>,
     added: Query<Entity, Added<Monitor>>,
     mut removed: RemovedComponents<Monitor>,
+    scale_factor_changes: Res<ScaleFactorChanges>,
     frame_count: Res<FrameCount>,
     current_monitor_query: Query<Option<&CurrentMonitor>, With<PrimaryWindow>>,
+    platform: Res<Platform>,
+    _: NonSendMarker,
 ) {
     let has_changes = !added.is_empty() || removed.read().next().is_some();
 
-    if has_changes {
-        let monitors_resource = build_monitors(&monitors);
-        if let Some(current_monitor) = current_monitor_query.iter().next().flatten() {
-            debug!(
-                "[update_monitors] frame={} Monitors changed, now {} monitors, current_monitor_index={} current_monitor_scale={}",
-                frame_count.0,
-                monitors_resource.list.len(),
-                current_monitor.monitor_info.index,
-                current_monitor.monitor_info.scale,
-            );
-        } else {
-            debug!(
-                "[update_monitors] frame={} Monitors changed, now {} monitors, current_monitor=None",
-                frame_count.0,
-                monitors_resource.list.len(),
-            );
+    let monitors_resource = if has_changes {
+        build_monitors(&monitors, *platform)
+    } else if scale_factor_changes.any()
+        && let Some(mut live) = live_monitors(&LiveWinitWindows, &previous_monitors)
+        && live.fingerprint() != previous_monitors.fingerprint()
+    {
+        work_area::apply_work_areas(&mut live.list, *platform);
+        live
+    } else {
+        return;
+    };
+    if monitors_resource.fingerprint() == previous_monitors.fingerprint() {
+        debug!(
+            "[update_monitors] frame={} Monitors unchanged",
+            frame_count.0
+        );
+        // A monitor removed and added again keeps its geometry but has a new entity.
+        previous_monitors.bypass_change_detection().list = monitors_resource.list;
+        return;
+    }
+
+    if let Some(current_monitor) = current_monitor_query.iter().next().flatten() {
+        debug!(
+            "[update_monitors] frame={} Monitors changed, now {} monitors, current_monitor_index={} current_monitor_scale={}",
+            frame_count.0,
+            monitors_resource.list.len(),
+            current_monitor.monitor_info.index,
+            current_monitor.monitor_info.scale,
+        );
+    } else {
+        debug!(
+            "[update_monitors] frame={} Monitors changed, now {} monitors, current_monitor=None",
+            frame_count.0,
+            monitors_resource.list.len(),
+        );
+    }
+    let added = changed_indices(&monitors_resource, &previous_monitors);
+    let removed = changed_indices(&previous_monitors, &monitors_resource);
+    debug!("[update_monitors] added={added:?} removed={removed:?}");
+    commands.insert_resource(monitors_resource);
+    commands.trigger(MonitorConfigurationChanged { added, removed });
+}
+
+/// Pair each monitor in `monitors` with the same physical monitor in `other`, as an index
+/// into `other`, or `None` when it has no counterpart.
+///
+/// Monitors that kept their name, position, and size are paired first, then the rest by
+/// [`MonitorInfo::is_same_monitor`] in enumeration order. Each monitor in `other` is paired
+/// at most once, so two monitors sharing a name are not both matched to one of them.
+fn pair_monitors(monitors: &[MonitorInfo], other: &[MonitorInfo]) -> Vec<Option<usize>> {
+    let mut pairs = vec![None; monitors.len()];
+    let mut paired = vec![false; other.len()];
+    for unchanged_only in [true, false] {
+        for (monitor, pair) in monitors.iter().zip(&mut pairs) {
+            if pair.is_some() {
+                continue;
+            }
+            *pair = other.iter().enumerate().position(|(other_index, other)| {
+                !paired[other_index]
+                    && if unchanged_only {
+                        monitor.is_unchanged(other)
+                    } else {
+                        monitor.is_same_monitor(other)
+                    }
+            });
+            if let Some(other_index) = *pair {
+                paired[other_index] = true;
+            }
         }
-        commands.insert_resource(monitors_resource);
+    }
+    pairs
+}
+
+/// Indices in `monitors` of the monitors missing from `other`.
+fn changed_indices(monitors: &Monitors, other: &Monitors) -> Vec<usize> {
+    monitors
+        .iter()
+        .zip(pair_monitors(&monitors.list, &other.list))
+        .filter(|(_, pair)| pair.is_none())
+        .map(|(monitor, _)| monitor.index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::MonitorInfo;
+    use super::Monitors;
+    use super::changed_indices;
+    use super::live_monitors;
+    use crate::test_support::FakeWinitWindow;
+    use crate::test_support::monitor;
+
+    #[test]
+    fn primary_is_monitor_at_origin() {
+        let monitors = Monitors {
+            list: vec![monitor(0, -1920), monitor(1, 0)],
+        };
+        assert_eq!(monitors.primary().index, 1);
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(
+            monitors
+                .iter()
+                .map(|monitor| monitor.index)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+    }
+
+    #[test]
+    fn primary_without_monitor_at_origin_is_index_zero() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 100), monitor(1, 2020)],
+        };
+        assert_eq!(monitors.primary().index, 0);
+    }
+
+    #[test]
+    fn scale_and_refresh_rate_look_up_by_index() {
+        let monitors = Monitors {
+            list: vec![
+                monitor(0, 0),
+                MonitorInfo {
+                    scale: 2.0,
+                    refresh_rate_millihertz: Some(120_000),
+                    ..monitor(1, 1920)
+                },
+            ],
+        };
+        assert_eq!(monitors.scale_for(1), Some(2.0));
+        assert_eq!(monitors.refresh_rate(1), Some(120_000));
+        assert_eq!(monitors.refresh_rate(0), None);
+        assert_eq!(monitors.scale_for(2), None);
+    }
+
+    #[test]
+    fn by_entity_finds_the_monitor_with_that_entity() {
+        let mut world = World::new();
+        let [left, right, unknown] = [(); 3].map(|()| world.spawn_empty().id());
+        let monitors = Monitors {
+            list: vec![
+                MonitorInfo {
+                    entity: left,
+                    ..monitor(0, 0)
+                },
+                MonitorInfo {
+                    entity: right,
+                    ..monitor(1, 0)
+                },
+            ],
+        };
+        assert_eq!(
+            monitors.by_entity(right).map(|monitor| monitor.index),
+            Some(1)
+        );
+        assert_eq!(
+            monitors.by_entity(left).map(|monitor| monitor.index),
+            Some(0)
+        );
+        assert!(monitors.by_entity(unknown).is_none());
+    }
+
+    #[test]
+    fn changed_indices_match_monitors_by_identity() {
+        let previous = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        };
+        let current = Monitors {
+            list: vec![monitor(0, 0), monitor(1, -1920)],
+        };
+        assert_eq!(changed_indices(&current, &previous), [1]);
+        assert_eq!(changed_indices(&previous, &current), [1]);
+        assert!(changed_indices(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn changed_indices_tell_apart_monitors_sharing_a_name() {
+        let named = |index, physical_x| MonitorInfo {
+            name: Some("DELL U2720Q".to_string()),
+            ..monitor(index, physical_x)
+        };
+        let previous = Monitors {
+            list: vec![named(0, 0), named(1, 1920)],
+        };
+        let right_unplugged = Monitors {
+            list: vec![named(0, 0)],
+        };
+        assert_eq!(changed_indices(&previous, &right_unplugged), [1]);
+        assert!(changed_indices(&right_unplugged, &previous).is_empty());
+
+        let left_unplugged = Monitors {
+            list: vec![named(0, 1920)],
+        };
+        assert_eq!(changed_indices(&previous, &left_unplugged), [0]);
+        assert!(changed_indices(&left_unplugged, &previous).is_empty());
+    }
+
+    #[test]
+    fn live_monitors_update_each_of_two_monitors_sharing_a_name() {
+        let named = |index, physical_x| MonitorInfo {
+            name: Some("DELL U2720Q".to_string()),
+            ..monitor(index, physical_x)
+        };
+        let monitors = Monitors {
+            list: vec![named(0, 0), named(1, 1920)],
+        };
+        let winit_window = FakeWinitWindow {
+            monitors: vec![
+                named(0, 0),
+                MonitorInfo {
+                    scale: 2.0,
+                    ..named(0, 1920)
+                },
+            ],
+            ..Default::default()
+        };
+
+        let live = live_monitors(&winit_window, &monitors);
+        assert_eq!(live.as_ref().and_then(|live| live.scale_for(0)), Some(1.0));
+        assert_eq!(live.as_ref().and_then(|live| live.scale_for(1)), Some(2.0));
+    }
+
+    #[test]
+    fn fingerprint_changes_with_geometry_and_scale() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        };
+        let same = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        };
+        let rescaled = Monitors {
+            list: vec![
+                monitor(0, 0),
+                MonitorInfo {
+                    scale: 2.0,
+                    ..monitor(1, 1920)
+                },
+            ],
+        };
+        let moved = Monitors {
+            list: vec![monitor(0, 0), monitor(1, -1920)],
+        };
+        let resized = Monitors {
+            list: vec![
+                MonitorInfo {
+                    physical_size: UVec2::new(2560, 1440),
+                    ..monitor(0, 0)
+                },
+                monitor(1, 1920),
+            ],
+        };
+
+        assert_eq!(monitors.fingerprint(), same.fingerprint());
+        assert_ne!(monitors.fingerprint(), rescaled.fingerprint());
+        assert_ne!(monitors.fingerprint(), moved.fingerprint());
+        assert_ne!(monitors.fingerprint(), resized.fingerprint());
+    }
+
+    #[test]
+    fn most_overlap_picks_monitor_showing_most_of_the_window() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        };
+
+        let spanning = IRect::new(1000, 100, 3000, 700);
+        assert_eq!(monitors.monitor_with_most_overlap(spanning).index, 1);
+
+        let evenly_split = IRect::new(1420, 100, 2420, 700);
+        assert_eq!(monitors.monitor_with_most_overlap(evenly_split).index, 0);
+
+        let off_screen = IRect::new(5000, 100, 5800, 700);
+        assert_eq!(monitors.monitor_with_most_overlap(off_screen).index, 1);
+    }
+
+    #[test]
+    fn overlap_area_measures_each_monitor_separately() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        };
+
+        let spanning = IRect::new(1000, 100, 3000, 700);
+        assert_eq!(monitors.overlap_area(spanning, 0), 920 * 600);
+        assert_eq!(monitors.overlap_area(spanning, 1), 1080 * 600);
+        assert_eq!(monitors.overlap_area(spanning, 2), 0);
+        assert!(monitors.any_overlap(spanning));
+
+        let touching_edge = IRect::new(3840, 100, 4640, 700);
+        assert!(!monitors.any_overlap(touching_edge));
+        assert!(monitors.contains_point(3839, 0));
+        assert!(!monitors.contains_point(3840, 0));
+    }
+
+    #[test]
+    fn closest_to_side_by_side_with_negative_coordinates() {
+        let monitors = Monitors {
+            list: vec![monitor(0, -1920), monitor(1, 0), monitor(2, 1920)],
+        };
+
+        assert_eq!(monitors.closest_to(-500, -300).index, 0);
+        assert_eq!(monitors.closest_to(-500, 1500).index, 0);
+        assert_eq!(monitors.closest_to(100, -300).index, 1);
+        assert_eq!(monitors.closest_to(4000, 500).index, 2);
+        assert_eq!(monitors.closest_to(-32000, -32000).index, 0);
+    }
+
+    #[test]
+    fn closest_to_l_shaped_layout_picks_nearest_edge() {
+        let below_primary = MonitorInfo {
+            physical_position: IVec2::new(0, 1080),
+            ..monitor(2, 0)
+        };
+        let monitors = Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920), below_primary],
+        };
+
+        // In the empty corner right of the lower monitor and below the right one.
+        assert_eq!(monitors.closest_to(2500, 1500).index, 1);
+        assert_eq!(monitors.closest_to(2000, 1200).index, 2);
+        assert_eq!(monitors.closest_to(100, 2500).index, 2);
+        assert_eq!(monitors.closest_to(-100, 500).index, 0);
     }
 }
@@ -0,0 +1,229 @@
+//! Window opacity queries.
+//!
+//! winit has no opacity API, so the opacity of the whole window (title bar included) is
+//! read and set through each platform directly:
+//!
+//! - **macOS**: `NSWindow.alphaValue`.
+//! - **Windows**: a layered window (`WS_EX_LAYERED`) with `LWA_ALPHA`.
+//! - **Linux X11**: the `_NET_WM_WINDOW_OPACITY` hint, honored only when a compositing window
+//!   manager is running.
+//! - **Linux Wayland**: unsupported; the core protocol has no window opacity.
+//!
+//! All functions must run on the main thread; callers hold a `NonSendMarker`.
+
+use bevy::prelude::*;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use bevy::winit::WINIT_WINDOWS;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use bevy_kana::ToF32;
+#[cfg(target_os = "windows")]
+use bevy_kana::ToU16;
+#[cfg(target_os = "linux")]
+use bevy_kana::ToU32;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use raw_window_handle::HasWindowHandle;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use raw_window_handle::RawWindowHandle;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::COLORREF;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::HWND;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::GWL_EXSTYLE;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::GetLayeredWindowAttributes;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongPtrW;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::LAYERED_WINDOW_ATTRIBUTES_FLAGS;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::LWA_ALPHA;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::SetLayeredWindowAttributes;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongPtrW;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
+#[cfg(target_os = "linux")]
+use x11rb::connection::Connection;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::AtomEnum;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::ConnectionExt;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::PropMode;
+#[cfg(target_os = "linux")]
+use x11rb::wrapper::ConnectionExt as _;
+#[cfg(target_os = "linux")]
+use x11rb::xcb_ffi::XCBConnection;
+
+use crate::constants::FULLY_OPAQUE;
+#[cfg(target_os = "windows")]
+use crate::constants::LAYERED_WINDOW_MAX_ALPHA;
+use crate::constants::MIN_RESTORED_OPACITY;
+#[cfg(target_os = "linux")]
+use crate::constants::WINDOW_OPACITY_ATOM_NAME;
+#[cfg(target_os = "macos")]
+use crate::macos_tabbing_fix::get_ns_window;
+
+/// Clamp a saved opacity into the range restore applies, so a corrupt or zero value
+/// never leaves the window invisible.
+#[must_use]
+pub(crate) const fn restorable_opacity(opacity: f32) -> f32 {
+    if opacity.is_nan() {
+        return FULLY_OPAQUE;
+    }
+    opacity.clamp(MIN_RESTORED_OPACITY, FULLY_OPAQUE)
+}
+
+/// Opacity of the window, from `0.0` (transparent) to `1.0` (opaque), when the platform
+/// reports it.
+#[cfg(target_os = "macos")]
+pub(crate) fn window_opacity(entity: Entity) -> Option<f32> {
+    get_ns_window(entity).map(|ns_window| ns_window.alphaValue().to_f32())
+}
+
+/// Set the opacity of the window. Returns whether the platform accepted it.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_window_opacity(entity: Entity, opacity: f32) -> bool {
+    let Some(ns_window) = get_ns_window(entity) else {
+        return false;
+    };
+    ns_window.setAlphaValue(f64::from(opacity));
+    true
+}
+
+/// Get the `HWND` from a Bevy window entity.
+#[cfg(target_os = "windows")]
+pub(crate) fn get_hwnd(entity: Entity) -> Option<HWND> {
+    WINIT_WINDOWS.with(|winit_windows| {
+        let winit_windows = winit_windows.borrow();
+        let winit_window = winit_windows.get_window(entity)?;
+        match winit_window.window_handle().ok()?.as_raw() {
+            RawWindowHandle::Win32(handle) => Some(HWND(handle.hwnd.get() as *mut _)),
+            _ => None,
+        }
+    })
+}
+
+/// Opacity of the window, from `0.0` (transparent) to `1.0` (opaque), when the platform
+/// reports it. A window that is not layered is opaque.
+#[cfg(target_os = "windows")]
+pub(crate) fn window_opacity(entity: Entity) -> Option<f32> {
+    let hwnd = get_hwnd(entity)?;
+    // SAFETY: `hwnd` is a live window handle from winit.
+    let extended_style = unsafe { GetWindowLongPtrW(hwnd, GWL_EXSTYLE) };
+    if extended_style & WS_EX_LAYERED.0 as isize == 0 {
+        return Some(FULLY_OPAQUE);
+    }
+
+    let mut alpha = 0_u8;
+    let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS::default();
+    // SAFETY: `hwnd` is a live window handle and both out pointers are valid.
+    unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&raw mut alpha), Some(&raw mut flags)) }
+        .ok()?;
+    if flags.contains(LWA_ALPHA) {
+        Some(f32::from(alpha) / f32::from(LAYERED_WINDOW_MAX_ALPHA))
+    } else {
+        Some(FULLY_OPAQUE)
+    }
+}
+
+/// Set the opacity of the window, making it layered. Returns whether the platform
+/// accepted it.
+#[cfg(target_os = "windows")]
+pub(crate) fn set_window_opacity(entity: Entity, opacity: f32) -> bool {
+    let Some(hwnd) = get_hwnd(entity) else {
+        return false;
+    };
+    let alpha = u8::try_from(
+        (opacity * f32::from(LAYERED_WINDOW_MAX_ALPHA))
+            .round()
+            .to_u16(),
+    )
+    .unwrap_or(LAYERED_WINDOW_MAX_ALPHA);
+    // SAFETY: `hwnd` is a live window handle from winit; adding `WS_EX_LAYERED` keeps
+    // every other extended style bit.
+    unsafe {
+        let extended_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, extended_style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+    }
+    .is_ok()
+}
+
+/// Get the X11 window id from a Bevy window entity. `None` on Wayland.
+#[cfg(target_os = "linux")]
+fn get_x11_window_id(entity: Entity) -> Option<u32> {
+    WINIT_WINDOWS.with(|winit_windows| {
+        let winit_windows = winit_windows.borrow();
+        let winit_window = winit_windows.get_window(entity)?;
+        match winit_window.window_handle().ok()?.as_raw() {
+            RawWindowHandle::Xlib(handle) => Some(handle.window.to_u32()),
+            RawWindowHandle::Xcb(handle) => Some(handle.window.get()),
+            _ => None,
+        }
+    })
+}
+
+/// Opacity of the window, from `0.0` (transparent) to `1.0` (opaque), when the platform
+/// reports it. A window without `_NET_WM_WINDOW_OPACITY` is opaque.
+#[cfg(target_os = "linux")]
+pub(crate) fn window_opacity(entity: Entity) -> Option<f32> {
+    let window_id = get_x11_window_id(entity)?;
+    let (conn, _) = XCBConnection::connect(None).ok()?;
+    let atom = conn
+        .intern_atom(false, WINDOW_OPACITY_ATOM_NAME)
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    let property = conn
+        .get_property(false, window_id, atom, AtomEnum::CARDINAL, 0, 1)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(
+        property
+            .value32()
+            .and_then(|mut values| values.next())
+            .map_or(FULLY_OPAQUE, |value| {
+                (f64::from(value) / f64::from(u32::MAX)).to_f32()
+            }),
+    )
+}
+
+/// Set the window's `_NET_WM_WINDOW_OPACITY` hint, removing it when opaque. Returns
+/// whether the X server accepted it; the hint only takes effect under a compositor.
+#[cfg(target_os = "linux")]
+pub(crate) fn set_window_opacity(entity: Entity, opacity: f32) -> bool {
+    let Some(window_id) = get_x11_window_id(entity) else {
+        return false;
+    };
+    let Ok((conn, _)) = XCBConnection::connect(None) else {
+        return false;
+    };
+    let Some(atom) = conn
+        .intern_atom(false, WINDOW_OPACITY_ATOM_NAME)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .map(|reply| reply.atom)
+    else {
+        return false;
+    };
+
+    let request = if opacity >= FULLY_OPAQUE {
+        conn.delete_property(window_id, atom).map(drop)
+    } else {
+        let value = (f64::from(opacity) * f64::from(u32::MAX)).round().to_u32();
+        conn.change_property32(
+            PropMode::REPLACE,
+            window_id,
+            atom,
+            AtomEnum::CARDINAL,
+            &[value],
+        )
+        .map(drop)
+    };
+    request.is_ok() && conn.flush().is_ok()
+}
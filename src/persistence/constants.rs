@@ -1,6 +1,29 @@
+// per-monitor geometry
+pub(super) const MAX_REMEMBERED_MONITORS: usize = 8;
+
+// geometry history
+/// Distinct geometries kept for `undo_window_geometry`, across all windows.
+pub(super) const GEOMETRY_HISTORY_LEN: usize = 5;
+
+// transient sizes
+/// Smallest logical width or height that is saved. Anything smaller is treated as a
+/// transient size, e.g. from an interrupted restore, and the last good geometry is kept.
+pub(super) const MIN_SAVED_LOGICAL_SIZE: u32 = 50;
+
+// round-trip size stabilization
+/// Largest per-axis difference, in logical pixels, between a restored size and the size
+/// the window reports afterwards that is still saved as the restored size.
+pub(super) const RESTORED_SIZE_TOLERANCE: u32 = 2;
+
 // paths
 pub(super) const EXAMPLES_DIRECTORY_NAME: &str = "examples";
+pub(super) const JSON_EXTENSION: &str = "json";
+pub(super) const CBOR_EXTENSION: &str = "cbor";
 pub(super) const RON_EXTENSION: &str = ".ron";
+pub(super) const BACKUP_EXTENSION: &str = "bak";
+pub(super) const TEMPORARY_EXTENSION: &str = "tmp";
 
 // state format
+/// CBOR self-describe tag (55799) that starts every CBOR state file.
+pub(super) const CBOR_MAGIC: [u8; 3] = [0xd9, 0xd9, 0xf7];
 pub(super) const PERSISTED_STATE_VERSION_V1: u8 = 1;
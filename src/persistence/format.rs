@@ -27,27 +27,47 @@
 //! | Legacy single-window | Bare `WindowState` (no version field, pre-multi-window) |
 //! | v1 | `PersistedState { version: 1, entries }` with `width`/`height` (physical) |
 //! | v2 | `PersistedState { version: 2, entries }` with `logical_width`/`logical_height` + `monitor_scale` |
+//!
+//! ## Profiles
+//!
+//! A v2 file may also carry a `profiles` list of named layouts, each with its own
+//! `entries`. The top-level `entries` hold the default profile, so files written before
+//! profiles existed decode unchanged and `profiles` is omitted when only the default
+//! profile has state.
+//!
+//! ## Backends
+//!
+//! The same [`PersistedState`] layout is written as RON, JSON, or CBOR depending on
+//! [`StateFormat`]. Legacy and v1 files predate JSON and CBOR support, so only RON input
+//! falls back to those decoders. CBOR files start with [`CBOR_MAGIC`], which [`decode`]
+//! checks before the configured format.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::io;
 
 use bevy::prelude::*;
-use ron::Error;
 use ron::from_str;
 use ron::ser::PrettyConfig;
 use ron::ser::to_string_pretty;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::constants::CBOR_MAGIC;
 use super::constants::PERSISTED_STATE_VERSION_V1;
+use super::state_format::StateFormat;
 #[cfg(test)]
 use super::window_state::SavedVideoMode;
+use super::window_state::SavedWindowAttributes;
 use super::window_state::SavedWindowMode;
 use super::window_state::WindowState;
 use crate::constants::CURRENT_STATE_VERSION;
+use crate::constants::DEFAULT_PROFILE;
 use crate::constants::DEFAULT_SCALE_FACTOR;
+use crate::constants::FULLY_OPAQUE;
 use crate::constants::PRIMARY_WINDOW_KEY;
 use crate::constants::RON_HEADER;
 
@@ -76,11 +96,22 @@ pub(super) struct PersistedEntry {
     window_state: WindowState,
 }
 
+/// One named layout profile and its entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct PersistedProfile {
+    name:    String,
+    entries: Vec<PersistedEntry>,
+}
+
 /// Versioned persisted state format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct PersistedState {
-    version: u8,
-    entries: Vec<PersistedEntry>,
+    version:  u8,
+    /// Entries of the default profile.
+    entries:  Vec<PersistedEntry>,
+    /// Every other profile, sorted by name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<PersistedProfile>,
 }
 
 /// Minimal version probe — just extract the version number from any versioned format.
@@ -89,17 +120,57 @@ struct VersionProbe {
     version: u8,
 }
 
-/// Decode persisted state text into typed runtime state.
+/// Failure to serialize state with the selected backend.
+#[derive(Debug)]
+pub(super) enum EncodeError {
+    Ron(ron::Error),
+    Json(serde_json::Error),
+    Cbor(ciborium::ser::Error<io::Error>),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ron(error) => write!(f, "{error}"),
+            Self::Json(error) => write!(f, "{error}"),
+            Self::Cbor(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// Decode persisted state in the given format into typed runtime state, keyed by profile
+/// name. Formats without profiles decode as the default profile.
+///
+/// Contents starting with [`CBOR_MAGIC`] decode as CBOR whatever `state_format` says.
+pub(super) fn decode(
+    contents: &[u8],
+    state_format: StateFormat,
+) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
+    if let Some(cbor_body) = contents.strip_prefix(&CBOR_MAGIC) {
+        return decode_cbor(cbor_body);
+    }
+    let contents = str::from_utf8(contents).ok()?;
+    match state_format {
+        StateFormat::Ron => decode_ron(contents),
+        StateFormat::Json => decode_json(contents),
+        StateFormat::Cbor => {
+            warn!("[decode] CBOR state file is missing its self-describe tag");
+            None
+        },
+    }
+}
+
+/// Decode RON state text.
 ///
 /// Tries versioned formats first (dispatching by the `version` field),
 /// then falls back to legacy unversioned formats. See the module-level
 /// docs for the full list of supported formats.
-pub(super) fn decode(contents: &str) -> Option<HashMap<WindowKey, WindowState>> {
+fn decode_ron(contents: &str) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
     // Probe only `VersionProbe::version` before dispatching to `PersistedStateV1` or
     // `PersistedState`.
     if let Ok(probe) = from_str::<VersionProbe>(contents) {
         match probe.version {
-            PERSISTED_STATE_VERSION_V1 => decode_v1(contents),
+            PERSISTED_STATE_VERSION_V1 => decode_v1(contents).map(default_profile),
             CURRENT_STATE_VERSION => decode_v2(contents),
             unsupported => {
                 warn!(
@@ -113,10 +184,47 @@ pub(super) fn decode(contents: &str) -> Option<HashMap<WindowKey, WindowState>>
         // Legacy unversioned format — bare `WindowState` from before multi-window
         // support. Cannot participate in the version match above because it has no
         // `version` field.
-        decode_legacy_single_window(contents)
+        let decoded = decode_legacy_single_window(contents).map(default_profile);
+        if decoded.is_none()
+            && let Err(error) = from_str::<PersistedState>(contents)
+        {
+            warn!("[decode] State file does not parse at {error}");
+        }
+        decoded
     }
 }
 
+/// Decode JSON state text. JSON was introduced with v2, so only the current version is valid.
+fn decode_json(contents: &str) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
+    let probe = serde_json::from_str::<VersionProbe>(contents).ok()?;
+    if probe.version != CURRENT_STATE_VERSION {
+        warn!(
+            "[decode] Unsupported persisted state version {} in JSON state file \
+             (latest supported: {CURRENT_STATE_VERSION})",
+            probe.version
+        );
+        return None;
+    }
+    let persisted_state = serde_json::from_str::<PersistedState>(contents).ok()?;
+    collect_profiles(persisted_state)
+}
+
+/// Decode CBOR state, after its [`CBOR_MAGIC`] prefix. CBOR was introduced with v2, so
+/// only the current version is valid.
+fn decode_cbor(contents: &[u8]) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
+    let probe = ciborium::from_reader::<VersionProbe, _>(contents).ok()?;
+    if probe.version != CURRENT_STATE_VERSION {
+        warn!(
+            "[decode] Unsupported persisted state version {} in CBOR state file \
+             (latest supported: {CURRENT_STATE_VERSION})",
+            probe.version
+        );
+        return None;
+    }
+    let persisted_state = ciborium::from_reader::<PersistedState, _>(contents).ok()?;
+    collect_profiles(persisted_state)
+}
+
 /// v1 window state layout (used `width`/`height` field names on the wire).
 /// Used only for deserializing v1 and legacy files.
 #[derive(Debug, Clone, Deserialize)]
@@ -138,13 +246,28 @@ impl WindowStateV1 {
     /// Convert to current `WindowState`, treating v1 values as logical (assumes scale 1.0).
     fn into_current(self) -> WindowState {
         WindowState {
-            logical_position:  self.logical_position,
-            logical_width:     self.logical_width,
-            logical_height:    self.logical_height,
-            scale:             DEFAULT_SCALE_FACTOR,
-            monitor:           self.monitor_index,
-            saved_window_mode: self.saved_window_mode,
-            app_name:          self.app_name,
+            logical_position:         self.logical_position,
+            logical_width:            self.logical_width,
+            logical_height:           self.logical_height,
+            scale:                    DEFAULT_SCALE_FACTOR,
+            monitor:                  self.monitor_index,
+            saved_window_mode:        self.saved_window_mode,
+            monitor_name:             None,
+            logical_monitor_offset:   None,
+            monitor_geometries:       Vec::new(),
+            minimized:                false,
+            restore_bounds:           None,
+            window_attributes:        SavedWindowAttributes::default(),
+            opacity:                  FULLY_OPAQUE,
+            title:                    None,
+            icon_path:                None,
+            extra:                    BTreeMap::new(),
+            virtual_desktop:          None,
+            space_behavior:           None,
+            focused:                  false,
+            logical_invisible_border: None,
+            follows_current_monitor:  false,
+            app_name:                 self.app_name,
         }
     }
 }
@@ -205,10 +328,46 @@ fn decode_v1(contents: &str) -> Option<HashMap<WindowKey, WindowState>> {
     Some(states)
 }
 
-fn decode_v2(contents: &str) -> Option<HashMap<WindowKey, WindowState>> {
-    let persisted_state = from_str::<PersistedState>(contents).ok()?;
-    let mut states = HashMap::with_capacity(persisted_state.entries.len());
-    for persisted_entry in persisted_state.entries {
+fn decode_v2(contents: &str) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
+    let persisted_state = from_str::<PersistedState>(contents)
+        .inspect_err(|error| warn!("[decode] State file does not parse at {error}"))
+        .ok()?;
+    collect_profiles(persisted_state)
+}
+
+/// Wrap the states of a format without profiles as the default profile.
+fn default_profile(
+    states: HashMap<WindowKey, WindowState>,
+) -> HashMap<String, HashMap<WindowKey, WindowState>> {
+    HashMap::from([(DEFAULT_PROFILE.to_string(), states)])
+}
+
+/// Build the runtime profile map, rejecting duplicate profile names.
+fn collect_profiles(
+    persisted_state: PersistedState,
+) -> Option<HashMap<String, HashMap<WindowKey, WindowState>>> {
+    let mut profiles = default_profile(collect_entries(persisted_state.entries)?);
+    for persisted_profile in persisted_state.profiles {
+        let states = collect_entries(persisted_profile.entries)?;
+        if profiles
+            .insert(persisted_profile.name.clone(), states)
+            .is_some()
+        {
+            warn!(
+                "[decode] Invalid persisted state: duplicate profile \"{}\"",
+                persisted_profile.name
+            );
+            return None;
+        }
+    }
+
+    Some(profiles)
+}
+
+/// Build the runtime state map from persisted entries, rejecting duplicate keys.
+fn collect_entries(entries: Vec<PersistedEntry>) -> Option<HashMap<WindowKey, WindowState>> {
+    let mut states = HashMap::with_capacity(entries.len());
+    for persisted_entry in entries {
         if states
             .insert(
                 persisted_entry.window_key.clone(),
@@ -227,63 +386,124 @@ fn decode_v2(contents: &str) -> Option<HashMap<WindowKey, WindowState>> {
     Some(states)
 }
 
-/// Encode typed runtime state into persisted v1 text.
-pub(super) fn encode(states: &HashMap<WindowKey, WindowState>) -> Result<String, Error> {
-    let mut entries: Vec<PersistedEntry> = states
+/// Encode typed runtime state, keyed by profile name, into persisted bytes in the given
+/// format.
+///
+/// Only the latest version is ever written. RON output is prefixed with [`RON_HEADER`];
+/// JSON has no comment syntax, so it is written bare. CBOR output is prefixed with
+/// [`CBOR_MAGIC`].
+pub(super) fn encode(
+    profiles: &HashMap<String, HashMap<WindowKey, WindowState>>,
+    state_format: StateFormat,
+) -> Result<Vec<u8>, EncodeError> {
+    let entries = profiles
+        .get(DEFAULT_PROFILE)
+        .map(persisted_entries)
+        .unwrap_or_default();
+    let mut persisted_profiles: Vec<PersistedProfile> = profiles
         .iter()
-        .map(|(key, window_state)| PersistedEntry {
-            window_key:   key.clone(),
-            window_state: window_state.clone(),
+        .filter(|(name, _)| name.as_str() != DEFAULT_PROFILE)
+        .map(|(name, states)| PersistedProfile {
+            name:    name.clone(),
+            entries: persisted_entries(states),
         })
         .collect();
-    entries.sort_by(|a, b| a.window_key.cmp(&b.window_key));
+    persisted_profiles.sort_by(|a, b| a.name.cmp(&b.name));
 
     let persisted_state = PersistedState {
         version: CURRENT_STATE_VERSION,
         entries,
+        profiles: persisted_profiles,
     };
-    let ron_body = to_string_pretty(&persisted_state, PrettyConfig::default())?;
-    Ok(format!("{RON_HEADER}{ron_body}"))
+    match state_format {
+        StateFormat::Ron => {
+            let ron_body = to_string_pretty(&persisted_state, PrettyConfig::default())
+                .map_err(EncodeError::Ron)?;
+            Ok(format!("{RON_HEADER}{ron_body}").into_bytes())
+        },
+        StateFormat::Json => serde_json::to_vec_pretty(&persisted_state).map_err(EncodeError::Json),
+        StateFormat::Cbor => {
+            let mut contents = CBOR_MAGIC.to_vec();
+            ciborium::into_writer(&persisted_state, &mut contents).map_err(EncodeError::Cbor)?;
+            Ok(contents)
+        },
+    }
+}
+
+/// Convert one profile's states into entries sorted by key.
+fn persisted_entries(states: &HashMap<WindowKey, WindowState>) -> Vec<PersistedEntry> {
+    let mut entries: Vec<PersistedEntry> = states
+        .iter()
+        .map(|(key, window_state)| PersistedEntry {
+            window_key:   key.clone(),
+            window_state: window_state.clone(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.window_key.cmp(&b.window_key));
+    entries
 }
 
 #[cfg(test)]
 #[allow(clippy::panic, reason = "tests should panic on unexpected values")]
 mod tests {
     use std::collections::HashMap;
+    use std::path::Path;
+    use std::path::PathBuf;
 
     use bevy::prelude::*;
+    use bevy::window::VideoMode;
     use ron::from_str;
     use ron::ser::PrettyConfig;
     use ron::ser::to_string_pretty;
 
     use super::CURRENT_STATE_VERSION;
+    use super::DEFAULT_PROFILE;
     use super::DEFAULT_SCALE_FACTOR;
     use super::PERSISTED_STATE_VERSION_V1;
     use super::PersistedEntry;
+    use super::PersistedProfile;
     use super::PersistedState;
     use super::SavedVideoMode;
     use super::SavedWindowMode;
+    use super::StateFormat;
     use super::WindowKey;
     use super::WindowState;
     use crate::persistence::format;
 
+    /// Decode and return only the default profile.
+    fn decode_default(
+        contents: impl AsRef<[u8]>,
+        state_format: StateFormat,
+    ) -> Option<HashMap<WindowKey, WindowState>> {
+        format::decode(contents.as_ref(), state_format)
+            .and_then(|mut profiles| profiles.remove(DEFAULT_PROFILE))
+    }
+
+    /// Encode `states` as the default profile.
+    fn encode_default(
+        states: &HashMap<WindowKey, WindowState>,
+        state_format: StateFormat,
+    ) -> Result<Vec<u8>, format::EncodeError> {
+        format::encode(
+            &HashMap::from([(DEFAULT_PROFILE.to_string(), states.clone())]),
+            state_format,
+        )
+    }
+
     fn sample_state() -> WindowState {
         WindowState {
-            logical_position:  Some((10, 20)),
-            logical_width:     800,
-            logical_height:    600,
-            scale:             DEFAULT_SCALE_FACTOR,
-            monitor:           1,
-            saved_window_mode: SavedWindowMode::Windowed,
-            app_name:          "test-app".to_string(),
+            logical_position: Some((10, 20)),
+            monitor: 1,
+            app_name: "test-app".to_string(),
+            ..WindowState::new(UVec2::new(800, 600))
         }
     }
 
     #[test]
     fn decode_v2_distinguishes_primary_and_managed_primary() {
         let persisted_state = PersistedState {
-            version: CURRENT_STATE_VERSION,
-            entries: vec![
+            version:  CURRENT_STATE_VERSION,
+            entries:  vec![
                 PersistedEntry {
                     window_key:   WindowKey::Primary,
                     window_state: sample_state(),
@@ -296,13 +516,14 @@ mod tests {
                     },
                 },
             ],
+            profiles: Vec::new(),
         };
         let contents = match to_string_pretty(&persisted_state, PrettyConfig::default()) {
             Ok(contents) => contents,
             Err(error) => panic!("failed to serialize test state: {error}"),
         };
 
-        let decoded = format::decode(&contents);
+        let decoded = decode_default(&contents, StateFormat::Ron);
         assert!(decoded.is_some(), "expected v2 decode to succeed");
         let decoded = decoded.unwrap_or_default();
         assert!(decoded.contains_key(&WindowKey::Primary));
@@ -323,7 +544,7 @@ mod tests {
     app_name: \"test-app\",
 )";
 
-        let decoded = format::decode(legacy_ron);
+        let decoded = decode_default(legacy_ron, StateFormat::Ron);
         assert!(
             decoded.is_some(),
             "expected legacy single-window decode to succeed"
@@ -336,6 +557,7 @@ mod tests {
         assert_eq!(window_state.logical_width, 800);
         assert_eq!(window_state.logical_height, 600);
         assert!((window_state.scale - DEFAULT_SCALE_FACTOR).abs() < f64::EPSILON);
+        assert_eq!(window_state.physical_size(), UVec2::new(800, 600));
     }
 
     #[test]
@@ -360,7 +582,7 @@ mod tests {
 )",
         );
 
-        let decoded = format::decode(&v1_ron);
+        let decoded = decode_default(&v1_ron, StateFormat::Ron);
         assert!(decoded.is_some(), "expected v1 decode to succeed");
         let decoded = decoded.unwrap_or_default();
         let window_state = &decoded[&WindowKey::Primary];
@@ -372,8 +594,8 @@ mod tests {
     #[test]
     fn decode_v2_rejects_duplicate_keys() {
         let persisted_state = PersistedState {
-            version: CURRENT_STATE_VERSION,
-            entries: vec![
+            version:  CURRENT_STATE_VERSION,
+            entries:  vec![
                 PersistedEntry {
                     window_key:   WindowKey::Primary,
                     window_state: sample_state(),
@@ -383,6 +605,7 @@ mod tests {
                     window_state: sample_state(),
                 },
             ],
+            profiles: Vec::new(),
         };
         let contents = match to_string_pretty(&persisted_state, PrettyConfig::default()) {
             Ok(contents) => contents,
@@ -390,11 +613,23 @@ mod tests {
         };
 
         assert!(
-            format::decode(&contents).is_none(),
+            decode_default(&contents, StateFormat::Ron).is_none(),
             "duplicate keys should fail decode"
         );
     }
 
+    #[test]
+    fn decode_rejects_future_version() {
+        let future_version = CURRENT_STATE_VERSION + 1;
+        let contents =
+            format!("(version: {future_version}, entries: [], field_from_the_future: Some(true))");
+
+        assert!(
+            decode_default(&contents, StateFormat::Ron).is_none(),
+            "unknown future versions should not decode"
+        );
+    }
+
     /// Golden-file tests using exact RON strings from the pre-multi-window era
     /// (commit 516f5930, used through v0.18.2). These are byte-for-byte copies of
     /// files that the published crate wrote via `ron::ser::to_string_pretty` with
@@ -446,7 +681,7 @@ mod tests {
 
         #[test]
         fn decode_golden_legacy_windowed() {
-            let decoded = format::decode(WINDOWED);
+            let decoded = decode_default(WINDOWED, StateFormat::Ron);
             assert!(decoded.is_some(), "golden legacy windowed file must decode");
             let decoded = decoded.unwrap_or_default();
             assert_eq!(decoded.len(), 1);
@@ -462,7 +697,7 @@ mod tests {
 
         #[test]
         fn decode_golden_legacy_borderless_fullscreen() {
-            let decoded = format::decode(BORDERLESS_FULLSCREEN);
+            let decoded = decode_default(BORDERLESS_FULLSCREEN, StateFormat::Ron);
             assert!(
                 decoded.is_some(),
                 "golden legacy borderless fullscreen file must decode"
@@ -480,7 +715,7 @@ mod tests {
 
         #[test]
         fn decode_golden_legacy_exclusive_fullscreen() {
-            let decoded = format::decode(EXCLUSIVE_FULLSCREEN);
+            let decoded = decode_default(EXCLUSIVE_FULLSCREEN, StateFormat::Ron);
             assert!(
                 decoded.is_some(),
                 "golden legacy exclusive fullscreen file must decode"
@@ -510,15 +745,16 @@ mod tests {
             (WindowKey::Managed("inspector".to_string()), sample_state()),
         ]);
 
-        let encoded = match format::encode(&states) {
+        let encoded = match encode_default(&states, StateFormat::Ron) {
             Ok(encoded) => encoded,
             Err(error) => panic!("failed to encode state: {error}"),
         };
-        let decoded = from_str::<PersistedState>(&encoded);
+        let decoded = from_str::<PersistedState>(&String::from_utf8_lossy(&encoded));
         assert!(decoded.is_ok(), "encoded text should parse as v2");
         let decoded = decoded.unwrap_or(PersistedState {
-            version: 0,
-            entries: Vec::new(),
+            version:  0,
+            entries:  Vec::new(),
+            profiles: Vec::new(),
         });
         assert_eq!(decoded.version, CURRENT_STATE_VERSION);
         assert_eq!(decoded.entries.len(), 2);
@@ -531,22 +767,19 @@ mod tests {
             (
                 WindowKey::Managed("inspector".to_string()),
                 WindowState {
-                    logical_position:  Some((100, 200)),
-                    logical_width:     1024,
-                    logical_height:    768,
-                    scale:             2.0,
-                    monitor:           0,
-                    saved_window_mode: SavedWindowMode::Windowed,
-                    app_name:          "test-app".to_string(),
+                    logical_position: Some((100, 200)),
+                    scale: 2.0,
+                    app_name: "test-app".to_string(),
+                    ..WindowState::new(UVec2::new(1024, 768))
                 },
             ),
         ]);
 
-        let encoded = match format::encode(&states) {
+        let encoded = match encode_default(&states, StateFormat::Ron) {
             Ok(encoded) => encoded,
             Err(error) => panic!("failed to encode state: {error}"),
         };
-        let decoded = format::decode(&encoded);
+        let decoded = decode_default(&encoded, StateFormat::Ron);
         assert!(decoded.is_some(), "roundtrip decode should succeed");
         let decoded = decoded.unwrap_or_default();
         assert_eq!(decoded.len(), 2);
@@ -559,4 +792,353 @@ mod tests {
         assert_eq!(inspector.logical_height, 768);
         assert!((inspector.scale - 2.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn json_and_ron_roundtrip_identically() {
+        let states = HashMap::from([
+            (WindowKey::Primary, sample_state()),
+            (
+                WindowKey::Managed("inspector".to_string()),
+                WindowState {
+                    scale: 2.0,
+                    monitor: 1,
+                    saved_window_mode: SavedWindowMode::Fullscreen {
+                        video_mode: Some(SavedVideoMode {
+                            physical_size:           UVec2::new(3840, 2400),
+                            bit_depth:               32,
+                            refresh_rate_millihertz: 120_000,
+                        }),
+                    },
+                    app_name: "test-app".to_string(),
+                    ..WindowState::new(UVec2::new(1920, 1200))
+                },
+            ),
+            (
+                WindowKey::Managed("editor".to_string()),
+                WindowState {
+                    saved_window_mode: SavedWindowMode::Maximized,
+                    ..sample_state()
+                },
+            ),
+        ]);
+
+        for state_format in [StateFormat::Ron, StateFormat::Json, StateFormat::Cbor] {
+            let encoded = match encode_default(&states, state_format) {
+                Ok(encoded) => encoded,
+                Err(error) => panic!("failed to encode {state_format:?} state: {error}"),
+            };
+            let decoded = decode_default(&encoded, state_format);
+            assert_eq!(
+                decoded.as_ref(),
+                Some(&states),
+                "{state_format:?} roundtrip should preserve every field"
+            );
+        }
+    }
+
+    #[test]
+    fn cbor_matches_ron_and_is_detected_by_its_tag() {
+        let states = HashMap::from([
+            (WindowKey::Primary, sample_state()),
+            (
+                WindowKey::Managed("inspector".to_string()),
+                WindowState {
+                    title: Some("Inspector".to_string()),
+                    ..sample_state()
+                },
+            ),
+        ]);
+        let (Ok(ron), Ok(cbor)) = (
+            encode_default(&states, StateFormat::Ron),
+            encode_default(&states, StateFormat::Cbor),
+        ) else {
+            panic!("failed to encode state");
+        };
+
+        assert!(cbor.len() < ron.len(), "CBOR should be smaller than RON");
+        assert_eq!(
+            decode_default(&cbor, StateFormat::Ron),
+            decode_default(&ron, StateFormat::Ron),
+            "CBOR should decode to the same state as RON, even under a RON path"
+        );
+    }
+
+    #[test]
+    fn json_rejects_unsupported_version() {
+        let contents = r#"{ "version": 1, "entries": [] }"#;
+        assert!(
+            decode_default(contents, StateFormat::Json).is_none(),
+            "JSON files only support the current version"
+        );
+    }
+
+    #[test]
+    fn profiles_roundtrip_alongside_default() {
+        let presentation = WindowState {
+            logical_width: 1920,
+            logical_height: 1080,
+            ..sample_state()
+        };
+        let profiles = HashMap::from([
+            (
+                DEFAULT_PROFILE.to_string(),
+                HashMap::from([(WindowKey::Primary, sample_state())]),
+            ),
+            (
+                "presentation".to_string(),
+                HashMap::from([(WindowKey::Primary, presentation)]),
+            ),
+        ]);
+
+        for state_format in [StateFormat::Ron, StateFormat::Json, StateFormat::Cbor] {
+            let encoded = match format::encode(&profiles, state_format) {
+                Ok(encoded) => encoded,
+                Err(error) => panic!("failed to encode {state_format:?} profiles: {error}"),
+            };
+            assert_eq!(
+                format::decode(&encoded, state_format).as_ref(),
+                Some(&profiles),
+                "{state_format:?} roundtrip should preserve every profile"
+            );
+        }
+    }
+
+    #[test]
+    fn encode_omits_profiles_when_only_default() {
+        let states = HashMap::from([(WindowKey::Primary, sample_state())]);
+
+        let encoded = match encode_default(&states, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        assert!(
+            !String::from_utf8_lossy(&encoded).contains("profiles"),
+            "files with only the default profile should keep the pre-profile layout"
+        );
+    }
+
+    #[test]
+    fn decode_rejects_duplicate_profiles() {
+        let profile = || PersistedProfile {
+            name:    "coding".to_string(),
+            entries: Vec::new(),
+        };
+        let persisted_state = PersistedState {
+            version:  CURRENT_STATE_VERSION,
+            entries:  Vec::new(),
+            profiles: vec![profile(), profile()],
+        };
+        let contents = match to_string_pretty(&persisted_state, PrettyConfig::default()) {
+            Ok(contents) => contents,
+            Err(error) => panic!("failed to serialize duplicate-profile test state: {error}"),
+        };
+
+        assert!(
+            format::decode(contents.as_bytes(), StateFormat::Ron).is_none(),
+            "duplicate profiles should fail decode"
+        );
+    }
+
+    #[test]
+    fn monitor_geometries_roundtrip_most_recent_first() {
+        let mut previous_state = sample_state();
+        previous_state.monitor_name = Some("Built-in".to_string());
+        let mut state = sample_state();
+        state.monitor_name = Some("External".to_string());
+        state.logical_width = 1600;
+        state.remember_monitor_geometry(Some(&previous_state));
+
+        let remembered: Vec<_> = state
+            .monitor_geometries
+            .iter()
+            .map(|monitor_geometry| {
+                (
+                    monitor_geometry.monitor_name.as_str(),
+                    monitor_geometry.logical_width,
+                )
+            })
+            .collect();
+        assert_eq!(remembered, [("External", 1600), ("Built-in", 800)]);
+
+        let states = HashMap::from([(WindowKey::Primary, state.clone())]);
+        let encoded = match encode_default(&states, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        let decoded = decode_default(&encoded, StateFormat::Ron);
+        assert_eq!(
+            decoded.and_then(|mut states| states.remove(&WindowKey::Primary)),
+            Some(state)
+        );
+    }
+
+    #[test]
+    fn title_is_omitted_unless_saved() {
+        let untitled = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let encoded = match encode_default(&untitled, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        assert!(!String::from_utf8_lossy(&encoded).contains("title"));
+
+        let titled_state = WindowState {
+            title: Some("notes.md - Editor".to_string()),
+            ..sample_state()
+        };
+        let titled = HashMap::from([(WindowKey::Primary, titled_state.clone())]);
+        let encoded = match encode_default(&titled, StateFormat::Json) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        let decoded = decode_default(&encoded, StateFormat::Json);
+        assert_eq!(
+            decoded.and_then(|mut states| states.remove(&WindowKey::Primary)),
+            Some(titled_state)
+        );
+    }
+
+    #[test]
+    fn icon_path_round_trips_and_is_omitted_unless_set() {
+        let without_icon = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let encoded = match encode_default(&without_icon, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        assert!(!String::from_utf8_lossy(&encoded).contains("icon_path"));
+
+        let mut with_icon_state = sample_state();
+        with_icon_state.set_icon_path(Some(PathBuf::from("icons/dark.png")));
+        let with_icon = HashMap::from([(WindowKey::Primary, with_icon_state.clone())]);
+        let encoded = match encode_default(&with_icon, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        let decoded = decode_default(&encoded, StateFormat::Ron)
+            .and_then(|mut states| states.remove(&WindowKey::Primary));
+        assert_eq!(
+            decoded.as_ref().and_then(WindowState::icon_path),
+            Some(Path::new("icons/dark.png"))
+        );
+    }
+
+    #[test]
+    fn extra_round_trips_in_every_format() {
+        let mut with_extra_state = sample_state();
+        with_extra_state
+            .extra_mut()
+            .insert("present_mode".to_string(), "AutoVsync".to_string());
+        let with_extra = HashMap::from([(WindowKey::Primary, with_extra_state.clone())]);
+
+        for state_format in [StateFormat::Ron, StateFormat::Json, StateFormat::Cbor] {
+            let encoded = match encode_default(&with_extra, state_format) {
+                Ok(encoded) => encoded,
+                Err(error) => panic!("failed to encode state: {error}"),
+            };
+            let decoded = decode_default(&encoded, state_format)
+                .and_then(|mut states| states.remove(&WindowKey::Primary));
+            assert_eq!(
+                decoded.as_ref(),
+                Some(&with_extra_state),
+                "{state_format:?}"
+            );
+        }
+
+        let without_extra = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let encoded = match encode_default(&without_extra, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        assert!(!String::from_utf8_lossy(&encoded).contains("extra"));
+    }
+
+    #[test]
+    fn focused_round_trips_and_is_omitted_unless_set() {
+        let unfocused = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let encoded = match encode_default(&unfocused, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        assert!(!String::from_utf8_lossy(&encoded).contains("focused"));
+
+        let mut focused_state = sample_state();
+        focused_state.set_focused(true);
+        let focused = HashMap::from([(WindowKey::Primary, focused_state)]);
+        let encoded = match encode_default(&focused, StateFormat::Ron) {
+            Ok(encoded) => encoded,
+            Err(error) => panic!("failed to encode state: {error}"),
+        };
+        let decoded = decode_default(&encoded, StateFormat::Ron)
+            .and_then(|mut states| states.remove(&WindowKey::Primary));
+        assert_eq!(decoded.as_ref().map(WindowState::focused), Some(true));
+    }
+
+    #[test]
+    fn unknown_window_mode_decodes_as_windowed() {
+        let states = HashMap::from([(
+            WindowKey::Primary,
+            WindowState {
+                saved_window_mode: SavedWindowMode::BorderlessFullscreen,
+                ..sample_state()
+            },
+        )]);
+        for (state_format, known_mode, future_mode) in [
+            (
+                StateFormat::Ron,
+                "BorderlessFullscreen",
+                "PictureInPicture(corner: TopRight)",
+            ),
+            (
+                StateFormat::Json,
+                r#""BorderlessFullscreen""#,
+                r#"{"PictureInPicture":{"corner":"TopRight"}}"#,
+            ),
+        ] {
+            let encoded = match encode_default(&states, state_format) {
+                Ok(encoded) => String::from_utf8_lossy(&encoded).into_owned(),
+                Err(error) => panic!("failed to encode state: {error}"),
+            };
+            assert!(encoded.contains(known_mode));
+            let from_newer_version = encoded.replace(known_mode, future_mode);
+
+            let decoded = decode_default(from_newer_version.as_bytes(), state_format)
+                .and_then(|mut states| states.remove(&WindowKey::Primary));
+            assert_eq!(decoded, Some(sample_state()));
+        }
+    }
+
+    #[test]
+    fn unavailable_video_mode_is_replaced_by_closest() {
+        let video_mode = |width, height, refresh_rate_millihertz| VideoMode {
+            physical_size: UVec2::new(width, height),
+            bit_depth: 32,
+            refresh_rate_millihertz,
+        };
+        let saved_mode = |width, height, refresh_rate_millihertz| SavedWindowMode::Fullscreen {
+            video_mode: Some(SavedVideoMode::from(&video_mode(
+                width,
+                height,
+                refresh_rate_millihertz,
+            ))),
+        };
+        let available = [
+            video_mode(1920, 1080, 60_000),
+            video_mode(2560, 1440, 60_000),
+            video_mode(2560, 1440, 144_000),
+        ];
+
+        let mut present = saved_mode(2560, 1440, 60_000);
+        assert_eq!(present.substitute_unavailable_video_mode(&available), None);
+        assert_eq!(present, saved_mode(2560, 1440, 60_000));
+
+        let mut missing = saved_mode(2560, 1440, 120_000);
+        assert!(
+            missing
+                .substitute_unavailable_video_mode(&available)
+                .is_some()
+        );
+        assert_eq!(missing, saved_mode(2560, 1440, 144_000));
+
+        let mut unreported = saved_mode(3840, 2160, 60_000);
+        assert_eq!(unreported.substitute_unavailable_video_mode(&[]), None);
+    }
 }
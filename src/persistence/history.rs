@@ -0,0 +1,112 @@
+//! In-memory history of recent window geometry, for undoing an accidental move or resize.
+//!
+//! `save_window_state` pushes the geometry a window had before each change it detects;
+//! [`undo_window_geometry`](crate::undo_window_geometry) pops the newest entry and restores
+//! that window to it. The history is never written to the state file.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use super::constants::GEOMETRY_HISTORY_LEN;
+use super::format::WindowKey;
+use super::window_state::WindowState;
+
+/// The last few distinct geometries across all windows, oldest first.
+#[derive(Resource, Default)]
+pub(crate) struct WindowGeometryHistory {
+    entries:        VecDeque<(WindowKey, WindowState)>,
+    /// Windows being restored by an undo. Their next change is the undo itself, which
+    /// must not be pushed back onto the history.
+    pending_undoes: HashSet<WindowKey>,
+}
+
+impl WindowGeometryHistory {
+    /// Remember `window_state` as the geometry `window_key` had before its latest change.
+    /// Dropped when it matches the newest entry for that window, and the oldest entry is
+    /// evicted once the history is full.
+    pub(crate) fn push(&mut self, window_key: WindowKey, window_state: WindowState) {
+        if self.pending_undoes.remove(&window_key) {
+            return;
+        }
+        let newest_for_window = self
+            .entries
+            .iter()
+            .rev()
+            .find(|(entry_key, _)| *entry_key == window_key);
+        if newest_for_window.is_some_and(|(_, newest)| same_geometry(newest, &window_state)) {
+            return;
+        }
+        if self.entries.len() == GEOMETRY_HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        debug!("[WindowGeometryHistory::push] [{window_key}] {window_state:?}");
+        self.entries.push_back((window_key, window_state));
+    }
+
+    /// Take the newest entry and mark its window as being restored by an undo.
+    pub(crate) fn pop_for_undo(&mut self) -> Option<(WindowKey, WindowState)> {
+        let (window_key, window_state) = self.entries.pop_back()?;
+        self.pending_undoes.insert(window_key.clone());
+        Some((window_key, window_state))
+    }
+
+    /// Forget that `window_key` is being restored by an undo, when the restore never
+    /// started.
+    pub(crate) fn cancel_undo(&mut self, window_key: &WindowKey) {
+        self.pending_undoes.remove(window_key);
+    }
+}
+
+fn same_geometry(left: &WindowState, right: &WindowState) -> bool {
+    left.logical_position == right.logical_position
+        && left.logical_width == right.logical_width
+        && left.logical_height == right.logical_height
+        && left.monitor == right.monitor
+        && left.saved_window_mode == right.saved_window_mode
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn state_at(logical_x: i32) -> WindowState {
+        WindowState {
+            logical_position: Some((logical_x, 0)),
+            ..WindowState::new(UVec2::new(800, 600))
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_distinct_geometries() {
+        let mut history = WindowGeometryHistory::default();
+        for logical_x in 0..8 {
+            history.push(WindowKey::Primary, state_at(logical_x));
+            history.push(WindowKey::Primary, state_at(logical_x));
+        }
+
+        let undone: Vec<_> = std::iter::from_fn(|| history.pop_for_undo())
+            .map(|(_, window_state)| window_state.logical_position)
+            .collect();
+        let expected: Vec<_> = (3..8).rev().map(|logical_x| Some((logical_x, 0))).collect();
+        assert_eq!(undone, expected);
+    }
+
+    #[test]
+    fn change_made_by_an_undo_is_not_pushed() {
+        let mut history = WindowGeometryHistory::default();
+        history.push(WindowKey::Primary, state_at(0));
+        history.push(WindowKey::Primary, state_at(1));
+
+        assert!(history.pop_for_undo().is_some());
+        history.push(WindowKey::Primary, state_at(2));
+        history.push(WindowKey::Primary, state_at(3));
+
+        let undone: Vec<_> = std::iter::from_fn(|| history.pop_for_undo())
+            .map(|(_, window_state)| window_state.logical_position)
+            .collect();
+        assert_eq!(undone, vec![Some((3, 0)), Some((0, 0))]);
+    }
+}
@@ -3,19 +3,27 @@
 use std::collections::HashMap;
 use std::env::current_exe;
 use std::fs;
+use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
 
+use bevy::prelude::*;
 use dirs::config_dir;
 
+use super::constants::BACKUP_EXTENSION;
 use super::constants::EXAMPLES_DIRECTORY_NAME;
 use super::constants::RON_EXTENSION;
 use super::format;
 use super::format::WindowKey;
-#[cfg(test)]
-use super::window_state::SavedWindowMode;
+use super::repair;
+use super::state_format::StateFormat;
 use super::window_state::WindowState;
+use super::writer;
+use super::writer::Profiles;
+use crate::WindowManagerError;
+use crate::WindowStateLoadFailed;
 use crate::constants::STATE_FILE;
+use crate::restore_window_config::RestoreWindowConfig;
 
 /// Get the default state file path using the executable name.
 ///
@@ -47,13 +55,166 @@ pub(crate) fn get_state_path_for_app(app_name: &str) -> Option<PathBuf> {
     config_dir().map(|config_dir| config_dir.join(app_name).join(STATE_FILE))
 }
 
-/// Load all window states from the given path.
+/// Load the window states of every profile from the given path.
+///
+/// Returns `Ok(None)` when the file does not exist (no state saved yet), and an error
+/// when it exists but cannot be read or decoded.
+///
+/// The format is detected from the file extension (see [`StateFormat::from_path`]), so
+/// existing `.ron` files keep loading regardless of the configured save format.
 ///
 /// Supports migration from the old single-window format: if the file contains
-/// a single `WindowState`, it is wrapped as `{"primary": state}`.
-pub(crate) fn load_all_states(path: &Path) -> Option<HashMap<WindowKey, WindowState>> {
+/// a single `WindowState`, it is wrapped as `{"primary": state}` in the default profile.
+///
+/// When the file is missing or cannot be decoded, the backup of the previous file kept by
+/// each save (`<path>.bak`) is loaded instead if it decodes. A RON file that cannot be
+/// decoded and has no usable backup is repaired as far as possible.
+pub(crate) fn load_all_profiles(path: &Path) -> Result<Option<Profiles>, WindowManagerError> {
+    let state_format = StateFormat::from_path(path);
+    let result = read_profiles(path, state_format);
+    if !matches!(
+        result,
+        Ok(None) | Err(WindowManagerError::Deserialize { .. })
+    ) {
+        return result;
+    }
+
+    let backup_path = writer::sibling_path(path, BACKUP_EXTENSION);
+    match read_profiles(&backup_path, state_format) {
+        Ok(Some(profiles)) => {
+            warn!(
+                "[load_all_profiles] {} is missing or corrupt, loaded backup {}",
+                path.display(),
+                backup_path.display()
+            );
+            Ok(Some(profiles))
+        },
+        _ if result.is_err() => {
+            repair_profiles(path, state_format).map_or(result, |profiles| Ok(Some(profiles)))
+        },
+        _ => result,
+    }
+}
+
+/// Recover what still decodes from the damaged RON state file at `path`.
+fn repair_profiles(path: &Path, state_format: StateFormat) -> Option<Profiles> {
+    if state_format != StateFormat::Ron {
+        return None;
+    }
     let contents = fs::read_to_string(path).ok()?;
-    format::decode(&contents)
+    let profiles = repair::repair_ron(&contents)?;
+    warn!(
+        "[load_all_profiles] {} is corrupt and has no usable backup, recovered {} window states from it",
+        path.display(),
+        profiles.values().map(HashMap::len).sum::<usize>()
+    );
+    Some(profiles)
+}
+
+/// Read and decode one state file. `Ok(None)` when it does not exist.
+fn read_profiles(
+    path: &Path,
+    state_format: StateFormat,
+) -> Result<Option<Profiles>, WindowManagerError> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(WindowManagerError::Io {
+                path: path.to_path_buf(),
+                source,
+            });
+        },
+    };
+    format::decode(&contents, state_format)
+        .map(Some)
+        .ok_or_else(|| WindowManagerError::Deserialize {
+            path: path.to_path_buf(),
+        })
+}
+
+/// Load the window states saved under `profile`.
+///
+/// Returns `Ok(None)` when the file does not exist or has no entry for `profile`.
+#[cfg(test)]
+pub(crate) fn load_all_states(
+    path: &Path,
+    profile: &str,
+) -> Result<Option<HashMap<WindowKey, WindowState>>, WindowManagerError> {
+    Ok(load_all_profiles(path)?.and_then(|mut profiles| profiles.remove(profile)))
+}
+
+/// Load every profile from the copy kept in memory. Only when there is none yet, i.e. at
+/// startup and after a profile switch, is it read from the newest state still queued for
+/// writing or else the file on disk. Reads the memory store instead when one is set.
+pub(crate) fn load_current_profiles(
+    config: &RestoreWindowConfig,
+) -> Result<Option<Profiles>, WindowManagerError> {
+    if let Some(memory_store) = &config.memory_store {
+        return Ok(memory_store.profiles());
+    }
+    if let Some(profiles) = config.profile_cache.profiles() {
+        return Ok((!profiles.is_empty()).then_some(profiles));
+    }
+    let result = config
+        .state_file_writer
+        .unwritten_profiles(&config.path)
+        .map_or_else(
+            || load_mirrored_profiles(&config.path, &config.mirror_paths),
+            |profiles| Ok(Some(profiles)),
+        );
+    if let Ok(profiles) = &result {
+        config
+            .profile_cache
+            .replace(profiles.clone().unwrap_or_default());
+    }
+    result
+}
+
+/// Load every profile from `path`, or from the first of `mirror_paths` that loads when
+/// `path` is missing or cannot be read. The error or absence of `path` is returned when no
+/// mirror loads either.
+fn load_mirrored_profiles(
+    path: &Path,
+    mirror_paths: &[PathBuf],
+) -> Result<Option<Profiles>, WindowManagerError> {
+    let result = load_all_profiles(path);
+    if matches!(result, Ok(Some(_))) {
+        return result;
+    }
+    for mirror_path in mirror_paths {
+        if let Ok(Some(profiles)) = load_all_profiles(mirror_path) {
+            warn!(
+                "[load_mirrored_profiles] {} is missing or unreadable, loaded mirror {}",
+                path.display(),
+                mirror_path.display()
+            );
+            return Ok(Some(profiles));
+        }
+    }
+    result
+}
+
+/// Load the active profile's window states, logging and firing [`WindowStateLoadFailed`]
+/// on error.
+///
+/// For internal systems that continue as if no state had been saved when loading fails.
+pub(crate) fn load_all_states_or_notify(
+    config: &RestoreWindowConfig,
+    commands: &mut Commands,
+) -> Option<HashMap<WindowKey, WindowState>> {
+    let path = &config.path;
+    match load_current_profiles(config) {
+        Ok(profiles) => profiles.and_then(|mut profiles| profiles.remove(&config.profile)),
+        Err(error) => {
+            warn!("[load_all_states_or_notify] {error}");
+            commands.trigger(WindowStateLoadFailed {
+                path: path.clone(),
+                error,
+            });
+            None
+        },
+    }
 }
 
 #[cfg(test)]
@@ -62,25 +223,29 @@ mod tests {
     use std::collections::HashMap;
     use std::fs;
 
+    use bevy::math::UVec2;
+    use bevy::tasks::IoTaskPool;
+    use bevy::tasks::TaskPool;
     use tempfile::NamedTempFile;
+    use tempfile::tempdir;
 
-    use super::SavedWindowMode;
+    use super::StateFormat;
     use super::WindowKey;
     use super::WindowState;
+    use crate::WindowManagerError;
     use crate::constants::CURRENT_STATE_VERSION;
-    use crate::constants::DEFAULT_SCALE_FACTOR;
+    use crate::constants::DEFAULT_PROFILE;
+    use crate::persistence::constants::TEMPORARY_EXTENSION;
     use crate::persistence::load;
     use crate::persistence::save;
+    use crate::persistence::writer;
+    use crate::persistence::writer::StateFileWriter;
 
     fn sample_state() -> WindowState {
         WindowState {
-            logical_position:  Some((10, 20)),
-            logical_width:     800,
-            logical_height:    600,
-            scale:             DEFAULT_SCALE_FACTOR,
-            monitor:           0,
-            saved_window_mode: SavedWindowMode::Windowed,
-            app_name:          "test-app".to_string(),
+            logical_position: Some((10, 20)),
+            app_name: "test-app".to_string(),
+            ..WindowState::new(UVec2::new(800, 600))
         }
     }
 
@@ -96,15 +261,74 @@ mod tests {
             (WindowKey::Primary, sample_state()),
             (WindowKey::Managed("primary".to_string()), sample_state()),
         ]);
-        save::save_all_states(path, &states);
+        if let Err(error) = save::save_all_states(path, StateFormat::Ron, DEFAULT_PROFILE, &states)
+        {
+            panic!("failed to save state: {error}");
+        }
 
-        let loaded = load::load_all_states(path);
+        let loaded = load::load_all_states(path, DEFAULT_PROFILE).ok().flatten();
         assert!(loaded.is_some(), "expected saved v1 state to load");
         let loaded = loaded.unwrap_or_default();
         assert!(loaded.contains_key(&WindowKey::Primary));
         assert!(loaded.contains_key(&WindowKey::Managed("primary".to_string())));
     }
 
+    #[test]
+    fn missing_file_is_not_an_error() {
+        let directory = match tempfile::tempdir() {
+            Ok(directory) => directory,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = directory.path().join("windows.ron");
+
+        assert!(matches!(
+            load::load_all_states(&path, DEFAULT_PROFILE),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn corrupt_file_is_a_deserialize_error() {
+        let file = match NamedTempFile::new() {
+            Ok(file) => file,
+            Err(error) => panic!("failed to create temp file: {error}"),
+        };
+        let path = file.path();
+        if let Err(error) = fs::write(path, "not a state file") {
+            panic!("failed to write corrupt content: {error}");
+        }
+
+        assert!(matches!(
+            load::load_all_states(path, DEFAULT_PROFILE),
+            Err(WindowManagerError::Deserialize { .. })
+        ));
+    }
+
+    #[test]
+    fn json_extension_is_detected_on_load() {
+        let file = match tempfile::Builder::new().suffix(".json").tempfile() {
+            Ok(file) => file,
+            Err(error) => panic!("failed to create temp file: {error}"),
+        };
+        let path = file.path();
+
+        let states = HashMap::from([(WindowKey::Primary, sample_state())]);
+        if let Err(error) = save::save_all_states(path, StateFormat::Json, DEFAULT_PROFILE, &states)
+        {
+            panic!("failed to save state: {error}");
+        }
+
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        assert!(
+            contents.trim_start().starts_with('{'),
+            "expected JSON output for a .json path"
+        );
+        assert_eq!(
+            load::load_all_states(path, DEFAULT_PROFILE).ok().flatten(),
+            Some(states)
+        );
+    }
+
     #[test]
     fn legacy_single_window_read_then_save_rewrites_as_v2() {
         let file = match NamedTempFile::new() {
@@ -127,10 +351,13 @@ mod tests {
             panic!("failed to write legacy content: {error}");
         }
 
-        let states = load::load_all_states(path);
+        let states = load::load_all_states(path, DEFAULT_PROFILE).ok().flatten();
         assert!(states.is_some(), "expected legacy content to decode");
         let states = states.unwrap_or_default();
-        save::save_all_states(path, &states);
+        if let Err(error) = save::save_all_states(path, StateFormat::Ron, DEFAULT_PROFILE, &states)
+        {
+            panic!("failed to save state: {error}");
+        }
 
         let contents = fs::read_to_string(path);
         assert!(contents.is_ok(), "expected rewritten file to be readable");
@@ -144,4 +371,179 @@ mod tests {
             "expected rewritten file to contain logical_width"
         );
     }
+
+    #[test]
+    fn saving_one_profile_keeps_the_others() {
+        let file = match NamedTempFile::new() {
+            Ok(file) => file,
+            Err(error) => panic!("failed to create temp file: {error}"),
+        };
+        let path = file.path();
+
+        let coding = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let presentation = HashMap::from([(
+            WindowKey::Primary,
+            WindowState {
+                logical_width: 1920,
+                logical_height: 1080,
+                ..sample_state()
+            },
+        )]);
+        for (profile, states) in [("coding", &coding), ("presentation", &presentation)] {
+            if let Err(error) = save::save_all_states(path, StateFormat::Ron, profile, states) {
+                panic!("failed to save {profile} state: {error}");
+            }
+        }
+
+        assert_eq!(
+            load::load_all_states(path, "coding").ok().flatten(),
+            Some(coding)
+        );
+        assert_eq!(
+            load::load_all_states(path, "presentation").ok().flatten(),
+            Some(presentation)
+        );
+        assert!(matches!(
+            load::load_all_states(path, DEFAULT_PROFILE),
+            Ok(Some(states)) if states.is_empty()
+        ));
+        assert!(matches!(load::load_all_states(path, "unknown"), Ok(None)));
+    }
+
+    #[test]
+    fn background_writer_coalesces_to_newest_state() {
+        IoTaskPool::get_or_init(TaskPool::new);
+        let directory = match tempdir() {
+            Ok(directory) => directory,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = directory.path().join("nested").join("windows.ron");
+        let state_file_writer = StateFileWriter::default();
+
+        let first = HashMap::from([(
+            DEFAULT_PROFILE.to_string(),
+            HashMap::from([(WindowKey::Primary, sample_state())]),
+        )]);
+        let newest = HashMap::from([(
+            DEFAULT_PROFILE.to_string(),
+            HashMap::from([(
+                WindowKey::Primary,
+                WindowState {
+                    logical_width: 1920,
+                    ..sample_state()
+                },
+            )]),
+        )]);
+        state_file_writer.queue(&path, &[], StateFormat::Ron, first);
+        state_file_writer.queue(&path, &[], StateFormat::Ron, newest.clone());
+        assert_eq!(
+            state_file_writer.unwritten_profiles(&path),
+            Some(newest.clone())
+        );
+
+        assert!(state_file_writer.flush().is_empty());
+        assert!(state_file_writer.unwritten_profiles(&path).is_none());
+        assert_eq!(load::load_all_profiles(&path).ok().flatten(), Some(newest));
+    }
+
+    #[test]
+    fn mirrored_state_loads_when_the_state_file_is_gone() {
+        IoTaskPool::get_or_init(TaskPool::new);
+        let directory = match tempdir() {
+            Ok(directory) => directory,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = directory.path().join("windows.ron");
+        let mirror_path = directory.path().join("mirror").join("windows.ron");
+        let state_file_writer = StateFileWriter::default();
+        let profiles = HashMap::from([(
+            DEFAULT_PROFILE.to_string(),
+            HashMap::from([(WindowKey::Primary, sample_state())]),
+        )]);
+
+        state_file_writer.queue(
+            &path,
+            std::slice::from_ref(&mirror_path),
+            StateFormat::Ron,
+            profiles.clone(),
+        );
+        assert!(state_file_writer.flush().is_empty());
+        assert!(writer::delete_state_file(&path).is_ok());
+
+        assert_eq!(
+            load::load_mirrored_profiles(&path, &[mirror_path])
+                .ok()
+                .flatten(),
+            Some(profiles)
+        );
+    }
+
+    #[test]
+    fn corrupt_file_falls_back_to_previous_save() {
+        let directory = match tempdir() {
+            Ok(directory) => directory,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = directory.path().join("windows.ron");
+        let first = HashMap::from([(WindowKey::Primary, sample_state())]);
+        let second = HashMap::from([(
+            WindowKey::Primary,
+            WindowState {
+                logical_width: 1920,
+                ..sample_state()
+            },
+        )]);
+        for states in [&first, &second] {
+            if let Err(error) =
+                save::save_all_states(&path, StateFormat::Ron, DEFAULT_PROFILE, states)
+            {
+                panic!("failed to save state: {error}");
+            }
+        }
+        assert_eq!(
+            load::load_all_states(&path, DEFAULT_PROFILE).ok().flatten(),
+            Some(second)
+        );
+
+        if let Err(error) = fs::write(&path, "not a state file") {
+            panic!("failed to write corrupt content: {error}");
+        }
+        assert_eq!(
+            load::load_all_states(&path, DEFAULT_PROFILE).ok().flatten(),
+            Some(first)
+        );
+    }
+
+    #[test]
+    fn cleared_state_file_loads_as_missing() {
+        let directory = match tempdir() {
+            Ok(directory) => directory,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = directory.path().join("windows.ron");
+        let states = HashMap::from([(WindowKey::Primary, sample_state())]);
+        for _ in 0..2 {
+            if let Err(error) =
+                save::save_all_states(&path, StateFormat::Ron, DEFAULT_PROFILE, &states)
+            {
+                panic!("failed to save state: {error}");
+            }
+        }
+        // Left behind by a write interrupted before its rename.
+        let temporary_path = writer::sibling_path(&path, TEMPORARY_EXTENSION);
+        if let Err(error) = fs::write(&temporary_path, "(") {
+            panic!("failed to write temporary file: {error}");
+        }
+
+        for _ in 0..2 {
+            if let Err(error) = writer::delete_state_file(&path) {
+                panic!("failed to delete state file: {error}");
+            }
+        }
+        assert!(matches!(
+            load::load_all_states(&path, DEFAULT_PROFILE),
+            Ok(None)
+        ));
+        assert!(!temporary_path.exists());
+    }
 }
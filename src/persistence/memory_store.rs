@@ -0,0 +1,135 @@
+//! Window states kept in memory instead of a state file.
+//!
+//! With a [`MemoryStateStore`] set on the plugin builder, every load reads the store and
+//! every save replaces its contents, so nothing is read from or written to disk. Meant for
+//! tests of apps using the plugin.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use super::format::WindowKey;
+use super::window_state::WindowState;
+use super::writer::Profiles;
+
+/// In-memory replacement for the state file.
+///
+/// Cloning shares the same store, so a test can keep a clone to seed states before the app
+/// starts and to check what the plugin saved. A store handed to a second app restores what
+/// the first one saved.
+///
+/// ```ignore
+/// let memory_store = MemoryStateStore::default();
+/// app.add_plugins(
+///     WindowManagerPlugin::builder()
+///         .memory_store(memory_store.clone())
+///         .build(),
+/// );
+/// app.update();
+///
+/// let saved = memory_store.states("default");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStateStore(Arc<Mutex<Profiles>>);
+
+impl MemoryStateStore {
+    fn lock(&self) -> MutexGuard<'_, Profiles> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Window states saved under `profile`, or `None` when it has none.
+    #[must_use]
+    pub fn states(&self, profile: &str) -> Option<HashMap<WindowKey, WindowState>> {
+        self.lock().get(profile).cloned()
+    }
+
+    /// Replace the window states saved under `profile`, e.g. to seed the state a test
+    /// restores from. Other profiles are kept.
+    pub fn set_states(&self, profile: &str, states: HashMap<WindowKey, WindowState>) {
+        self.lock().insert(profile.to_string(), states);
+    }
+
+    /// Every profile, or `None` when nothing has been saved, as for a missing state file.
+    pub(crate) fn profiles(&self) -> Option<Profiles> {
+        let profiles = self.lock();
+        (!profiles.is_empty()).then(|| profiles.clone())
+    }
+
+    /// Replace every profile, as a save replaces the state file.
+    pub(crate) fn replace(&self, profiles: Profiles) { *self.lock() = profiles; }
+
+    /// Forget every saved state, as deleting the state file does.
+    pub(crate) fn clear(&self) { self.lock().clear(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy::window::PrimaryWindow;
+
+    use super::*;
+    use crate::ManagedWindow;
+    use crate::WindowManagerPlugin;
+    use crate::constants::DEFAULT_PROFILE;
+    use crate::restore::TargetPosition;
+    use crate::restore_window_config::RestoreWindowConfig;
+    use crate::test_support;
+
+    #[test]
+    fn clones_share_saved_states() {
+        let memory_store = MemoryStateStore::default();
+        assert_eq!(memory_store.profiles(), None);
+
+        let shared = memory_store.clone();
+        shared.set_states("default", HashMap::new());
+        assert_eq!(memory_store.states("default"), Some(HashMap::new()));
+        assert_eq!(memory_store.states("work"), None);
+
+        memory_store.clear();
+        assert_eq!(shared.profiles(), None);
+    }
+
+    #[test]
+    fn plugin_restores_from_and_saves_to_the_store() {
+        let memory_store = MemoryStateStore::default();
+        let saved_primary = WindowState::new(UVec2::new(1280, 720));
+        memory_store.set_states(
+            DEFAULT_PROFILE,
+            HashMap::from([(WindowKey::Primary, saved_primary.clone())]),
+        );
+
+        let mut app = test_support::headless_app(
+            WindowManagerPlugin::builder().memory_store(memory_store.clone()),
+        );
+        let primary_window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        app.update();
+
+        let restore_window_config = app.world().resource::<RestoreWindowConfig>();
+        assert_eq!(
+            restore_window_config.loaded_states.get(&WindowKey::Primary),
+            Some(&saved_primary)
+        );
+        assert!(
+            app.world()
+                .entity(primary_window)
+                .contains::<TargetPosition>()
+        );
+
+        app.world_mut().spawn((
+            Window::default(),
+            ManagedWindow {
+                name: "inspector".to_string(),
+            },
+        ));
+        app.update();
+
+        let saved = memory_store.states(DEFAULT_PROFILE).unwrap_or_default();
+        assert_eq!(saved.get(&WindowKey::Primary), Some(&saved_primary));
+        assert!(saved.contains_key(&WindowKey::Managed("inspector".to_string())));
+    }
+}
@@ -2,16 +2,43 @@
 
 mod constants;
 mod format;
+mod history;
 mod load;
+mod memory_store;
+mod profile_cache;
+mod repair;
 mod save;
+mod state_format;
 mod window_state;
+mod writer;
 
 pub use format::WindowKey;
+pub(crate) use history::WindowGeometryHistory;
 pub(crate) use load::get_default_state_path;
 pub(crate) use load::get_state_path_for_app;
-pub(crate) use load::load_all_states;
+pub(crate) use load::load_all_states_or_notify;
+pub(crate) use load::load_current_profiles;
+pub use memory_store::MemoryStateStore;
+pub(crate) use profile_cache::ProfileCache;
+pub(crate) use save::WindowStateCache;
+pub(crate) use save::flush_pending_window_state;
+pub(crate) use save::get_window_position;
+pub(crate) use save::is_minimized;
 pub(crate) use save::save_active_window_state;
-pub(crate) use save::save_all_states;
+pub(crate) use save::save_all_states_or_notify;
+pub(crate) use save::save_open_window_states;
 pub(crate) use save::save_window_state;
+pub use state_format::StateFormat;
+#[cfg(test)]
+pub(crate) use window_state::MonitorGeometry;
+#[cfg(test)]
+pub(crate) use window_state::RestoreBounds;
+pub(crate) use window_state::SavedSpaceBehavior;
+pub use window_state::SavedVideoMode;
+pub(crate) use window_state::SavedWindowAttributes;
 pub(crate) use window_state::SavedWindowMode;
-pub(crate) use window_state::WindowState;
+pub use window_state::WindowState;
+pub(crate) use writer::StateFileWriter;
+pub(crate) use writer::delete_state_file;
+pub(crate) use writer::flush_state_file_writes;
+pub(crate) use writer::poll_state_file_writes;
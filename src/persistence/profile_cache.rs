@@ -0,0 +1,58 @@
+//! In-memory copy of the saved profiles.
+//!
+//! Every save keeps the other profiles and builds on the previous states of its windows.
+//! Reading and decoding the state file for that would put disk reads back on the main
+//! thread, so the profiles are read once at startup and after each profile switch, and
+//! every save updates this copy before the writer gets its own.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use super::writer::Profiles;
+
+/// The saved profiles, or nothing until they are read.
+///
+/// Cloning shares the same copy. An empty copy stands for a state file that does not
+/// exist.
+#[derive(Clone, Default)]
+pub(crate) struct ProfileCache(Arc<Mutex<Option<Profiles>>>);
+
+impl ProfileCache {
+    fn lock(&self) -> MutexGuard<'_, Option<Profiles>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The saved profiles, or `None` when they have to be read first.
+    pub(crate) fn profiles(&self) -> Option<Profiles> { self.lock().clone() }
+
+    /// Replace the copy with `profiles`, as just read or saved.
+    pub(crate) fn replace(&self, profiles: Profiles) { *self.lock() = Some(profiles); }
+
+    /// Drop the copy, so the next load reads the state file again.
+    pub(crate) fn forget(&self) { *self.lock() = None; }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn profiles_are_kept_until_forgotten() {
+        let profile_cache = ProfileCache::default();
+        assert_eq!(profile_cache.profiles(), None);
+
+        let shared = profile_cache.clone();
+        shared.replace(HashMap::from([("default".to_string(), HashMap::new())]));
+        assert_eq!(
+            profile_cache.profiles().map(|profiles| profiles.len()),
+            Some(1)
+        );
+
+        profile_cache.forget();
+        assert_eq!(shared.profiles(), None);
+    }
+}
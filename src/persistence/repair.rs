@@ -0,0 +1,331 @@
+//! Best-effort recovery of a RON state file that no longer decodes.
+//!
+//! A crash mid-write or a hand edit gone wrong usually damages only the end of the file,
+//! or drops a single field. [`repair_ron`] cuts the text back to a field boundary, closes
+//! every bracket still open there, and parses the result into a mirror of the persisted
+//! layout in which every window state field may be missing. Entries that still have a
+//! size are rebuilt with defaults for the fields they lost; the rest are dropped. The
+//! latest boundary that parses wins, so as much of the file as possible is kept.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use ron::from_str;
+use serde::Deserialize;
+
+use super::format::WindowKey;
+use super::window_state::MonitorGeometry;
+use super::window_state::RestoreBounds;
+use super::window_state::SavedSpaceBehavior;
+use super::window_state::SavedWindowAttributes;
+use super::window_state::SavedWindowMode;
+use super::window_state::WindowState;
+use super::writer::Profiles;
+use crate::constants::CURRENT_STATE_VERSION;
+use crate::constants::DEFAULT_PROFILE;
+use crate::constants::DEFAULT_SCALE_FACTOR;
+use crate::constants::FULLY_OPAQUE;
+use crate::constants::PRIMARY_MONITOR_INDEX;
+
+/// `PersistedState` with its entry lists allowed to be missing.
+#[derive(Deserialize)]
+struct PartialPersistedState {
+    version:  u8,
+    #[serde(default)]
+    entries:  Vec<PartialEntry>,
+    #[serde(default)]
+    profiles: Vec<PartialProfile>,
+}
+
+/// `PersistedProfile` with its entries allowed to be missing.
+#[derive(Deserialize)]
+struct PartialProfile {
+    name:    String,
+    #[serde(default)]
+    entries: Vec<PartialEntry>,
+}
+
+/// `PersistedEntry` holding a [`PartialWindowState`].
+#[derive(Deserialize)]
+struct PartialEntry {
+    #[serde(rename = "key")]
+    window_key:   WindowKey,
+    #[serde(default, rename = "state")]
+    window_state: PartialWindowState,
+}
+
+/// `WindowState` with every field allowed to be missing. A missing size is read as zero,
+/// which marks the state as unrecoverable.
+#[derive(Deserialize)]
+#[serde(default)]
+struct PartialWindowState {
+    logical_position:         Option<(i32, i32)>,
+    logical_width:            u32,
+    logical_height:           u32,
+    #[serde(rename = "monitor_scale")]
+    scale:                    f64,
+    #[serde(rename = "monitor_index")]
+    monitor:                  usize,
+    monitor_name:             Option<String>,
+    logical_monitor_offset:   Option<(i32, i32)>,
+    monitor_geometries:       Vec<MonitorGeometry>,
+    minimized:                bool,
+    restore_bounds:           Option<RestoreBounds>,
+    #[serde(rename = "mode")]
+    saved_window_mode:        SavedWindowMode,
+    #[serde(rename = "attributes")]
+    window_attributes:        SavedWindowAttributes,
+    opacity:                  f32,
+    title:                    Option<String>,
+    icon_path:                Option<PathBuf>,
+    extra:                    BTreeMap<String, String>,
+    virtual_desktop:          Option<String>,
+    space_behavior:           Option<SavedSpaceBehavior>,
+    focused:                  bool,
+    logical_invisible_border: Option<(i32, i32)>,
+    follows_current_monitor:  bool,
+    app_name:                 String,
+}
+
+impl Default for PartialWindowState {
+    fn default() -> Self {
+        Self {
+            logical_position:         None,
+            logical_width:            0,
+            logical_height:           0,
+            scale:                    DEFAULT_SCALE_FACTOR,
+            monitor:                  PRIMARY_MONITOR_INDEX,
+            monitor_name:             None,
+            logical_monitor_offset:   None,
+            monitor_geometries:       Vec::new(),
+            minimized:                false,
+            restore_bounds:           None,
+            saved_window_mode:        SavedWindowMode::Windowed,
+            window_attributes:        SavedWindowAttributes::default(),
+            opacity:                  FULLY_OPAQUE,
+            title:                    None,
+            icon_path:                None,
+            extra:                    BTreeMap::new(),
+            virtual_desktop:          None,
+            space_behavior:           None,
+            focused:                  false,
+            logical_invisible_border: None,
+            follows_current_monitor:  false,
+            app_name:                 String::new(),
+        }
+    }
+}
+
+impl PartialWindowState {
+    /// The recovered state, or `None` when its size was lost.
+    fn into_window_state(self) -> Option<WindowState> {
+        if self.logical_width == 0 || self.logical_height == 0 {
+            return None;
+        }
+        Some(WindowState {
+            logical_position:         self.logical_position,
+            logical_width:            self.logical_width,
+            logical_height:           self.logical_height,
+            scale:                    self.scale,
+            monitor:                  self.monitor,
+            saved_window_mode:        self.saved_window_mode,
+            monitor_name:             self.monitor_name,
+            logical_monitor_offset:   self.logical_monitor_offset,
+            monitor_geometries:       self.monitor_geometries,
+            minimized:                self.minimized,
+            restore_bounds:           self.restore_bounds,
+            window_attributes:        self.window_attributes,
+            opacity:                  self.opacity,
+            title:                    self.title,
+            icon_path:                self.icon_path,
+            extra:                    self.extra,
+            virtual_desktop:          self.virtual_desktop,
+            space_behavior:           self.space_behavior,
+            focused:                  self.focused,
+            logical_invisible_border: self.logical_invisible_border,
+            follows_current_monitor:  self.follows_current_monitor,
+            app_name:                 self.app_name,
+        })
+    }
+}
+
+/// Recover the window states a damaged current-version RON state file still holds.
+///
+/// `None` when no boundary of the text parses, the file is not the current version, or no
+/// window state with a size survives.
+pub(super) fn repair_ron(contents: &str) -> Option<Profiles> {
+    let partial_persisted_state = closed_prefixes(contents)
+        .into_iter()
+        .rev()
+        .find_map(|candidate| from_str::<PartialPersistedState>(&candidate).ok())?;
+    if partial_persisted_state.version != CURRENT_STATE_VERSION {
+        return None;
+    }
+
+    let mut profiles = HashMap::from([(
+        DEFAULT_PROFILE.to_string(),
+        recovered_entries(partial_persisted_state.entries),
+    )]);
+    for partial_profile in partial_persisted_state.profiles {
+        profiles
+            .entry(partial_profile.name)
+            .or_insert_with(|| recovered_entries(partial_profile.entries));
+    }
+    profiles.retain(|_, states| !states.is_empty());
+    (!profiles.is_empty()).then_some(profiles)
+}
+
+/// Rebuild the entries that kept their size, keeping the first of duplicate keys.
+fn recovered_entries(entries: Vec<PartialEntry>) -> HashMap<WindowKey, WindowState> {
+    let mut states = HashMap::with_capacity(entries.len());
+    for partial_entry in entries {
+        let window_key = partial_entry.window_key;
+        let Some(window_state) = partial_entry.window_state.into_window_state() else {
+            warn!("[repair_ron] [{window_key}] Dropped: its size was lost");
+            continue;
+        };
+        debug!("[repair_ron] [{window_key}] Recovered {window_state:?}");
+        states.entry(window_key).or_insert(window_state);
+    }
+    states
+}
+
+/// The text up to each `,` or closing bracket, in file order, each followed by the
+/// closing brackets still open at that point. Stops at the first mismatched bracket,
+/// since nothing after it can be trusted.
+fn closed_prefixes(contents: &str) -> Vec<String> {
+    let mut prefixes = Vec::new();
+    let mut open_brackets = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut characters = contents.char_indices().peekable();
+    while let Some((index, character)) = characters.next() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if character == '\\' {
+                escaped = true;
+            } else if character == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match character {
+            '"' => in_string = true,
+            // Line comments, such as the file header.
+            '/' if characters.next_if(|&(_, next)| next == '/').is_some() => {
+                while characters.next_if(|&(_, next)| next != '\n').is_some() {}
+            },
+            '(' => open_brackets.push(')'),
+            '[' => open_brackets.push(']'),
+            '{' => open_brackets.push('}'),
+            ')' | ']' | '}' => {
+                if open_brackets.pop() != Some(character) {
+                    break;
+                }
+                prefixes.push(closed_prefix(&contents[..=index], &open_brackets));
+            },
+            ',' => prefixes.push(closed_prefix(&contents[..=index], &open_brackets)),
+            _ => {},
+        }
+    }
+    prefixes
+}
+
+fn closed_prefix(prefix: &str, open_brackets: &[char]) -> String {
+    let mut closed = prefix.to_string();
+    closed.extend(open_brackets.iter().rev());
+    closed
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, reason = "tests should panic on unexpected values")]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::persistence::StateFormat;
+    use crate::persistence::format;
+
+    fn sample_state(logical_width: u32) -> WindowState {
+        WindowState {
+            logical_position: Some((100, 200)),
+            scale: 2.0,
+            monitor: 1,
+            saved_window_mode: SavedWindowMode::BorderlessFullscreen,
+            monitor_name: Some("External".to_string()),
+            app_name: "test".to_string(),
+            ..WindowState::new(UVec2::new(logical_width, 600))
+        }
+    }
+
+    fn encoded(states: HashMap<WindowKey, WindowState>) -> String {
+        let profiles = HashMap::from([(DEFAULT_PROFILE.to_string(), states)]);
+        match format::encode(&profiles, StateFormat::Ron).map(String::from_utf8) {
+            Ok(Ok(contents)) => contents,
+            _ => panic!("failed to encode state"),
+        }
+    }
+
+    #[test]
+    fn truncated_file_keeps_complete_entries_and_defaults_lost_fields() {
+        let contents = encoded(HashMap::from([
+            (WindowKey::Primary, sample_state(800)),
+            (
+                WindowKey::Managed("inspector".to_string()),
+                sample_state(400),
+            ),
+        ]));
+        // Cut inside the last entry, before its `mode`.
+        let Some(cut) = contents.rfind("minimized") else {
+            panic!("encoded state has no `minimized` field");
+        };
+
+        let recovered = repair_ron(&contents[..cut + 4])
+            .and_then(|mut profiles| profiles.remove(DEFAULT_PROFILE));
+        let Some(recovered) = recovered else {
+            panic!("expected recovered states");
+        };
+        assert_eq!(recovered.get(&WindowKey::Primary), Some(&sample_state(800)));
+        assert_eq!(
+            recovered
+                .get(&WindowKey::Managed("inspector".to_string()))
+                .map(|window_state| (window_state.logical_width, &window_state.saved_window_mode)),
+            Some((400, &SavedWindowMode::Windowed))
+        );
+    }
+
+    #[test]
+    fn missing_field_is_filled_with_its_default() {
+        let contents = encoded(HashMap::from([(WindowKey::Primary, sample_state(800))]));
+        let without_monitor_index = contents
+            .lines()
+            .filter(|line| !line.contains("monitor_index"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let recovered = repair_ron(&without_monitor_index)
+            .and_then(|mut profiles| profiles.remove(DEFAULT_PROFILE))
+            .and_then(|mut states| states.remove(&WindowKey::Primary));
+        assert_eq!(
+            recovered,
+            Some(WindowState {
+                monitor: PRIMARY_MONITOR_INDEX,
+                ..sample_state(800)
+            })
+        );
+    }
+
+    #[test]
+    fn unrecoverable_text_is_not_repaired() {
+        assert_eq!(repair_ron("not a state file"), None);
+
+        let contents = encoded(HashMap::from([(WindowKey::Primary, sample_state(800))]));
+        let Some(cut) = contents.find("logical_width") else {
+            panic!("encoded state has no `logical_width` field");
+        };
+        assert_eq!(repair_ron(&contents[..cut]), None);
+    }
+}
@@ -2,70 +2,301 @@
 //!
 //! Saves window position, size, and mode to the state file on change.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::env::current_exe;
-use std::fs::create_dir_all;
-use std::fs::write;
+use std::mem;
+#[cfg(test)]
 use std::path::Path;
+use std::path::PathBuf;
 
 use bevy::ecs::system::NonSendMarker;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
-#[cfg(any(
-    target_os = "macos",
-    all(target_os = "linux", feature = "workaround-winit-4443")
-))]
-use bevy::winit::WINIT_WINDOWS;
 use bevy_kana::ToI32;
 use bevy_kana::ToU32;
 
-use super::format;
+use super::constants::MIN_SAVED_LOGICAL_SIZE;
+use super::constants::RESTORED_SIZE_TOLERANCE;
 use super::format::WindowKey;
+use super::history::WindowGeometryHistory;
 use super::load;
+#[cfg(test)]
+use super::state_format::StateFormat;
+use super::window_state::RestoreBounds;
+use super::window_state::SavedSpaceBehavior;
+use super::window_state::SavedWindowAttributes;
 use super::window_state::SavedWindowMode;
 use super::window_state::WindowState;
+use super::window_state::follows_current_monitor;
+#[cfg(test)]
+use super::writer;
+use crate::DoNotRestore;
 use crate::ManagedWindow;
 use crate::ManagedWindowPersistence;
+use crate::WindowExtra;
+use crate::WindowIconPath;
+#[cfg(test)]
+use crate::WindowManagerError;
 use crate::constants::DEFAULT_SCALE_FACTOR;
+use crate::constants::FULLY_OPAQUE;
 use crate::constants::PRIMARY_MONITOR_INDEX;
+use crate::macos_spaces;
 use crate::monitors::CurrentMonitor;
+use crate::monitors::MonitorInfo;
 use crate::monitors::Monitors;
+use crate::opacity;
+use crate::restore_window_config::GeometryMemory;
 use crate::restore_window_config::RestoreWindowConfig;
+use crate::restore_window_config::SavePolicy;
+use crate::virtual_desktop;
+use crate::windows_invisible_border;
+#[cfg(any(
+    target_os = "macos",
+    all(target_os = "linux", feature = "workaround-winit-4443")
+))]
+use crate::winit_access::LiveWinitWindows;
+#[cfg(any(
+    target_os = "macos",
+    all(target_os = "linux", feature = "workaround-winit-4443")
+))]
+use crate::winit_access::WinitWindowAccess;
 
-/// Save all window states to the given path.
-pub(crate) fn save_all_states(path: &Path, states: &HashMap<WindowKey, WindowState>) {
-    if let Some(parent) = path.parent()
-        && let Err(e) = create_dir_all(parent)
-    {
-        warn!("[save_all_states] Failed to create directory {parent:?}: {e}");
-        return;
+/// Save all window states under `profile` to the given path in the given format,
+/// synchronously.
+///
+/// Other profiles already in the file are kept. A file that cannot be read or decoded is
+/// replaced.
+#[cfg(test)]
+pub(crate) fn save_all_states(
+    path: &Path,
+    state_format: StateFormat,
+    profile: &str,
+    states: &HashMap<WindowKey, WindowState>,
+) -> Result<(), WindowManagerError> {
+    let mut profiles = load::load_all_profiles(path)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    profiles.insert(profile.to_string(), states.clone());
+    writer::write_profiles(path, state_format, &profiles)
+}
+
+/// Queue all window states under the active profile for a background write, or store
+/// them in the memory store when one is set.
+///
+/// Other profiles are kept, taken from the copy of the saved profiles kept in memory,
+/// which this updates, so a save does not read the state file. A failed write is logged and fires
+/// [`WindowStateSaveFailed`](crate::WindowStateSaveFailed) once it finishes.
+///
+/// Each state first goes through the `on_save` hook. A maximized state without restore
+/// bounds keeps the windowed geometry last saved for its window. Under
+/// [`GeometryMemory::PerMonitor`] it then records its geometry for its monitor, on top of
+/// the per-monitor geometry already saved.
+pub(crate) fn save_all_states_or_notify(
+    config: &RestoreWindowConfig,
+    states: &HashMap<WindowKey, WindowState>,
+) {
+    let mut profiles = load::load_current_profiles(config)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let mut states = states.clone();
+    let previous_states = profiles.get(&config.profile);
+    for (window_key, window_state) in &mut states {
+        config.run_on_save(window_state);
+        let previous_state =
+            previous_states.and_then(|previous_states| previous_states.get(window_key));
+        if window_state.saved_window_mode.is_maximized() && window_state.restore_bounds.is_none() {
+            window_state.restore_bounds = previous_state.and_then(WindowState::windowed_bounds);
+        }
+        if config.geometry_memory == GeometryMemory::PerMonitor {
+            window_state.remember_monitor_geometry(previous_state);
+        }
     }
-    match format::encode(states) {
-        Ok(contents) => {
-            if let Err(e) = write(path, &contents) {
-                warn!("[save_all_states] Failed to write state file {path:?}: {e}");
-            }
-        },
-        Err(e) => {
-            warn!("[save_all_states] Failed to serialize state: {e}");
-        },
+    profiles.insert(config.profile.clone(), states);
+    if let Some(memory_store) = &config.memory_store {
+        memory_store.replace(profiles);
+        return;
     }
+    config.profile_cache.replace(profiles.clone());
+    config.state_file_writer.queue(
+        &config.path,
+        &config.mirror_paths,
+        config.state_format,
+        profiles,
+    );
 }
 
 /// Cached window state for change detection comparison.
-#[derive(Default)]
+#[derive(Default, PartialEq)]
 struct CachedWindowState {
-    physical_position: Option<IVec2>,
-    logical_size:      UVec2,
-    saved_window_mode: Option<SavedWindowMode>,
-    monitor:           Option<usize>,
+    physical_position:        Option<IVec2>,
+    logical_size:             UVec2,
+    saved_window_mode:        Option<SavedWindowMode>,
+    monitor:                  Option<usize>,
+    minimized:                bool,
+    window_attributes:        SavedWindowAttributes,
+    opacity:                  f32,
+    title:                    Option<String>,
+    icon_path:                Option<PathBuf>,
+    extra:                    BTreeMap<String, String>,
+    /// Captured by `capture_write_time_state` rather than compared on every change check.
+    virtual_desktop:          Option<String>,
+    space_behavior:           Option<SavedSpaceBehavior>,
+    /// Captured by `capture_write_time_state`, so a focus change alone writes nothing.
+    focused:                  bool,
+    logical_invisible_border: Option<(i32, i32)>,
+    follows_current_monitor:  bool,
+    /// Windowed geometry last observed, kept while the window is maximized.
+    windowed_bounds:          Option<RestoreBounds>,
+}
+
+impl CachedWindowState {
+    /// Convert to a `WindowState`, or `None` before the window's mode is known.
+    fn to_window_state(&self, monitors: &Monitors, app_name: &str) -> Option<WindowState> {
+        let saved_window_mode = self.saved_window_mode.clone()?;
+        let monitor_index = self.monitor.unwrap_or(PRIMARY_MONITOR_INDEX);
+        let monitor_info = monitors.by_index(monitor_index);
+        let monitor_scale = monitor_info.map_or(DEFAULT_SCALE_FACTOR, |monitor| monitor.scale);
+        let logical_position = self
+            .physical_position
+            .map(|physical_position| to_logical_position(physical_position, monitor_scale));
+        let restore_bounds = saved_window_mode
+            .is_maximized()
+            .then_some(self.windowed_bounds)
+            .flatten();
+        Some(WindowState {
+            logical_position,
+            logical_width: self.logical_size.x,
+            logical_height: self.logical_size.y,
+            scale: monitor_scale,
+            monitor: monitor_index,
+            saved_window_mode,
+            monitor_name: monitor_info.and_then(|monitor| monitor.name.clone()),
+            logical_monitor_offset: monitor_info
+                .zip(self.physical_position)
+                .map(|(monitor, physical_position)| monitor.logical_offset(physical_position)),
+            monitor_geometries: Vec::new(),
+            minimized: self.minimized,
+            restore_bounds,
+            window_attributes: self.window_attributes,
+            opacity: self.opacity,
+            title: self.title.clone(),
+            icon_path: self.icon_path.clone(),
+            extra: self.extra.clone(),
+            virtual_desktop: self.virtual_desktop.clone(),
+            space_behavior: self.space_behavior,
+            focused: self.focused,
+            logical_invisible_border: self.logical_invisible_border,
+            follows_current_monitor: self.follows_current_monitor,
+            app_name: app_name.to_string(),
+        })
+    }
 }
 
-/// Newtype wrapper around the change-detection cache so the inner
-/// `CachedWindowState` stays private to this module while still being usable
-/// as a `Local<_>` system parameter in `save_window_state`.
-#[derive(Default)]
-pub(crate) struct WindowStateCache(HashMap<Entity, CachedWindowState>);
+/// Change-detection cache of the last known state of every window, keyed by state file key.
+///
+/// Keeps the inner `CachedWindowState` private to this module. Under `SavePolicy::OnExit`
+/// this is the only record of window changes until `flush_pending_window_state` writes it,
+/// so it is a resource rather than a `Local` and survives windows being despawned at exit.
+#[derive(Resource, Default)]
+pub(crate) struct WindowStateCache {
+    entries:        HashMap<WindowKey, CachedWindowState>,
+    pending_write:  StateWrite,
+    /// Write held back until the startup save delay ends.
+    startup_write:  StateWrite,
+    /// Logical size each window was last restored to, until it is resized beyond
+    /// `RESTORED_SIZE_TOLERANCE`.
+    restored_sizes: HashMap<WindowKey, UVec2>,
+}
+
+impl WindowStateCache {
+    /// Forget every window not in `open_window_keys` and mark the cache for writing, so the
+    /// exit flush treats those windows as closed.
+    pub(crate) fn retain_open(&mut self, open_window_keys: &HashSet<WindowKey>) {
+        self.entries
+            .retain(|window_key, _| open_window_keys.contains(window_key));
+        self.pending_write = StateWrite::Needed;
+    }
+
+    /// Flag a cached window as minimized, keeping its last known geometry. Returns whether
+    /// the flag changed, i.e. whether the state file needs a write.
+    fn record_minimized(&mut self, window_key: &WindowKey) -> bool {
+        let cached_window_state = self.entries.entry(window_key.clone()).or_default();
+        if cached_window_state.minimized || cached_window_state.saved_window_mode.is_none() {
+            return false;
+        }
+        cached_window_state.minimized = true;
+        debug!("[save_window_state] [{window_key}] minimized, keeping last geometry");
+        true
+    }
+
+    /// Remember the logical size a restore just applied to `window_key`.
+    pub(crate) fn record_restored_size(&mut self, window_key: WindowKey, logical_size: UVec2) {
+        self.restored_sizes.insert(window_key, logical_size);
+    }
+
+    /// The size to save for a window reporting `logical_size`: its restored size while it
+    /// is within `RESTORED_SIZE_TOLERANCE` of it, so the pixel a scale conversion or
+    /// decoration adjustment gains or loses is not saved and compounded on the next
+    /// restore. A larger difference is a real resize and ends the stabilization.
+    fn stabilize_logical_size(&mut self, window_key: &WindowKey, logical_size: UVec2) -> UVec2 {
+        let Some(&restored_size) = self.restored_sizes.get(window_key) else {
+            return logical_size;
+        };
+        let difference = UVec2::new(
+            logical_size.x.abs_diff(restored_size.x),
+            logical_size.y.abs_diff(restored_size.y),
+        );
+        if difference.max_element() <= RESTORED_SIZE_TOLERANCE {
+            if difference != UVec2::ZERO {
+                debug!(
+                    "[stabilize_logical_size] [{window_key}] {logical_size} -> restored {restored_size}"
+                );
+            }
+            return restored_size;
+        }
+        self.restored_sizes.remove(window_key);
+        logical_size
+    }
+
+    /// Capture the state of the open windows that is only read when the state file is
+    /// written: the virtual desktop, too costly to query for every change check, and
+    /// focus, which changes too often to be worth a write of its own. Returns whether any
+    /// of it changed since the last capture.
+    fn capture_write_time_state<'a>(
+        &mut self,
+        windows: impl IntoIterator<Item = (Entity, &'a Window, Option<&'a ManagedWindow>)>,
+        primary_query: &Query<(), With<PrimaryWindow>>,
+    ) -> StateWrite {
+        let mut state_write = StateWrite::NotNeeded;
+        for (entity, window, managed) in windows {
+            let Some(cached_window_state) = window_key(entity, managed, primary_query)
+                .and_then(|window_key| self.entries.get_mut(&window_key))
+            else {
+                continue;
+            };
+            let virtual_desktop = virtual_desktop::window_virtual_desktop(entity);
+            if cached_window_state.virtual_desktop != virtual_desktop
+                || cached_window_state.focused != window.focused
+            {
+                cached_window_state.virtual_desktop = virtual_desktop;
+                cached_window_state.focused = window.focused;
+                state_write = StateWrite::Needed;
+            }
+        }
+        state_write
+    }
+
+    /// Drop the pending exit write and any write held back by the startup save delay. The
+    /// cached states stay, so windows are only saved again once they change.
+    pub(crate) const fn discard_pending_write(&mut self) {
+        self.pending_write = StateWrite::NotNeeded;
+        self.startup_write = StateWrite::NotNeeded;
+    }
+}
 
 #[derive(Clone, Copy, Default, Eq, PartialEq)]
 enum StateWrite {
@@ -92,78 +323,203 @@ pub(crate) fn save_active_window_state(
             &Window,
             Option<&CurrentMonitor>,
             Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
         ),
         Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
     >,
     primary_query: &Query<(), With<PrimaryWindow>>,
     exclude_entity: Option<Entity>,
+    cached: &mut WindowStateCache,
+    commands: &mut Commands,
 ) {
     if monitors.is_empty() {
         return;
     }
 
-    let app_name = current_exe()
-        .ok()
-        .and_then(|executable_path| {
-            executable_path
-                .file_stem()
-                .and_then(|file_stem| file_stem.to_str())
-                .map(String::from)
-        })
-        .unwrap_or_default();
+    let states = collect_active_window_states(
+        config,
+        monitors,
+        all_windows,
+        primary_query,
+        exclude_entity,
+        cached,
+        commands,
+    );
+    save_all_states_or_notify(config, &states);
+}
+
+/// Save every open window right now, honoring `ManagedWindowPersistence`.
+///
+/// `RememberAll` merges the open windows into the existing file so entries for closed
+/// windows survive; `ActiveOnly` rewrites the file with only the open windows.
+pub(crate) fn save_open_window_states(
+    config: &RestoreWindowConfig,
+    managed_window_persistence: &ManagedWindowPersistence,
+    monitors: &Monitors,
+    all_windows: &Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: &Query<(), With<PrimaryWindow>>,
+    cached: &mut WindowStateCache,
+    commands: &mut Commands,
+) {
+    if monitors.is_empty() {
+        return;
+    }
+
+    let mut states = match managed_window_persistence {
+        ManagedWindowPersistence::RememberAll => {
+            load::load_all_states_or_notify(config, commands).unwrap_or_default()
+        },
+        ManagedWindowPersistence::ActiveOnly => HashMap::new(),
+    };
+    states.extend(collect_active_window_states(
+        config,
+        monitors,
+        all_windows,
+        primary_query,
+        None,
+        cached,
+        commands,
+    ));
+    save_all_states_or_notify(config, &states);
+}
+
+/// Monitor a window is saved on: the one showing most of it when its position is known,
+/// so a window spanning two monitors is not assigned by its center point alone.
+/// Otherwise `CurrentMonitor`, then the first monitor.
+fn saved_monitor<'a>(
+    monitors: &'a Monitors,
+    existing_monitor: Option<&'a CurrentMonitor>,
+    physical_position: Option<IVec2>,
+    window: &Window,
+) -> &'a MonitorInfo {
+    match (physical_position, existing_monitor) {
+        (Some(physical_position), _) if !monitors.is_empty() => {
+            monitors.monitor_with_most_overlap(IRect::from_corners(
+                physical_position,
+                physical_position + window.resolution.physical_size().as_ivec2(),
+            ))
+        },
+        (_, Some(current_monitor)) => &current_monitor.monitor_info,
+        _ => monitors.first(),
+    }
+}
+
+/// Capture position/size/monitor/mode for every primary and managed window.
+///
+/// Minimized windows keep their previously saved entry (flagged as minimized) and are
+/// skipped if they have none, as are windows at a transient size and windows with
+/// `DoNotRestore`.
+fn collect_active_window_states(
+    config: &RestoreWindowConfig,
+    monitors: &Monitors,
+    all_windows: &Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: &Query<(), With<PrimaryWindow>>,
+    exclude_entity: Option<Entity>,
+    cached: &mut WindowStateCache,
+    commands: &mut Commands,
+) -> HashMap<WindowKey, WindowState> {
+    let app_name = current_app_name();
 
     let mut states = HashMap::new();
+    let mut previous_states: Option<HashMap<WindowKey, WindowState>> = None;
 
-    for (entity, window, existing_monitor, managed) in all_windows {
+    for (entity, window, existing_monitor, managed, icon_path, extra, do_not_restore) in all_windows
+    {
         if exclude_entity == Some(entity) {
             continue;
         }
 
-        let window_key = if primary_query.get(entity).is_ok() {
-            WindowKey::Primary
-        } else if let Some(managed_window) = managed {
-            WindowKey::Managed(managed_window.name.clone())
-        } else {
+        let Some(window_key) = window_key(entity, managed, primary_query) else {
             continue;
         };
 
-        let physical_position = get_window_position(entity, window);
+        // Geometry is bogus while minimized or at a transient size, and is not saved while
+        // `DoNotRestore` suppresses saving, so carry over the last saved entry instead.
+        let minimized = is_minimized(&LiveWinitWindows, entity);
+        if do_not_restore || minimized || has_transient_size(window) {
+            let previous_states = previous_states.get_or_insert_with(|| {
+                load::load_all_states_or_notify(config, commands).unwrap_or_default()
+            });
+            if let Some(previous_state) = previous_states.get(&window_key) {
+                let mut carried_state = previous_state.clone();
+                if !do_not_restore {
+                    carried_state.minimized = minimized;
+                }
+                states.insert(window_key, carried_state);
+            }
+            continue;
+        }
 
-        let (monitor_index, monitor_scale) = existing_monitor.map_or_else(
-            || {
-                let monitor_info = monitors.first();
-                (monitor_info.index, monitor_info.scale)
-            },
-            |current_monitor| (current_monitor.index, current_monitor.scale),
-        );
-        let saved_window_mode: SavedWindowMode = existing_monitor.map_or_else(
-            || (&window.mode).into(),
-            |current_monitor| (&current_monitor.effective_window_mode).into(),
+        let physical_position = get_window_position(&LiveWinitWindows, entity, window);
+
+        let monitor_info = saved_monitor(monitors, existing_monitor, physical_position, window);
+        let monitor_scale = monitor_info.scale;
+        let saved_window_mode =
+            resolve_saved_window_mode(&LiveWinitWindows, entity, window, existing_monitor);
+        let logical_position = physical_position
+            .map(|physical_position| to_logical_position(physical_position, monitor_scale));
+        let logical_size = cached.stabilize_logical_size(
+            &window_key,
+            UVec2::new(
+                window.resolution.width().round().to_u32(),
+                window.resolution.height().round().to_u32(),
+            ),
         );
-        let logical_position = physical_position.map(|physical_position| {
-            let logical_x = (f64::from(physical_position.x) / monitor_scale)
-                .round()
-                .to_i32();
-            let logical_y = (f64::from(physical_position.y) / monitor_scale)
-                .round()
-                .to_i32();
-            (logical_x, logical_y)
-        });
         states.insert(
             window_key,
             WindowState {
                 logical_position,
-                logical_width: window.resolution.width().to_u32(),
-                logical_height: window.resolution.height().to_u32(),
+                logical_width: logical_size.x,
+                logical_height: logical_size.y,
                 scale: monitor_scale,
-                monitor: monitor_index,
+                monitor: monitor_info.index,
                 saved_window_mode,
+                monitor_name: monitor_info.name.clone(),
+                logical_monitor_offset: physical_position
+                    .map(|physical_position| monitor_info.logical_offset(physical_position)),
+                monitor_geometries: Vec::new(),
+                minimized: false,
+                restore_bounds: None,
+                window_attributes: SavedWindowAttributes::from_window(window),
+                opacity: opacity::window_opacity(entity).unwrap_or(FULLY_OPAQUE),
+                title: config.saved_title(window),
+                icon_path: icon_path.map(|WindowIconPath(icon_path)| icon_path.clone()),
+                extra: saved_extra(extra),
+                virtual_desktop: virtual_desktop::window_virtual_desktop(entity),
+                space_behavior: macos_spaces::window_space_behavior(entity),
+                focused: window.focused,
+                logical_invisible_border: logical_invisible_border(entity, monitor_scale),
+                follows_current_monitor: follows_current_monitor(&window.mode),
                 app_name: app_name.clone(),
             },
         );
     }
 
-    save_all_states(&config.path, &states);
+    states
 }
 
 /// Persist window states using the `RememberAll` strategy: load existing file,
@@ -172,18 +528,34 @@ fn persist_remember_all(
     config: &RestoreWindowConfig,
     monitors: &Monitors,
     cached: &WindowStateCache,
-    all_windows: &Query<
-        (
-            Entity,
-            &Window,
-            Option<&CurrentMonitor>,
-            Option<&ManagedWindow>,
-        ),
-        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
-    >,
-    primary_query: &Query<(), With<PrimaryWindow>>,
+    commands: &mut Commands,
 ) {
-    let app_name = current_exe()
+    let mut states = load::load_all_states_or_notify(config, commands).unwrap_or_default();
+    states.extend(cached_window_states(monitors, cached));
+    save_all_states_or_notify(config, &states);
+}
+
+/// Convert every cached entry with a known mode into a `WindowState`.
+fn cached_window_states(
+    monitors: &Monitors,
+    cached: &WindowStateCache,
+) -> HashMap<WindowKey, WindowState> {
+    let app_name = current_app_name();
+
+    cached
+        .entries
+        .iter()
+        .filter_map(|(window_key, entry)| {
+            entry
+                .to_window_state(monitors, &app_name)
+                .map(|window_state| (window_key.clone(), window_state))
+        })
+        .collect()
+}
+
+/// Name of the running executable, saved with every window state.
+fn current_app_name() -> String {
+    current_exe()
         .ok()
         .and_then(|executable_path| {
             executable_path
@@ -191,58 +563,69 @@ fn persist_remember_all(
                 .and_then(|file_stem| file_stem.to_str())
                 .map(String::from)
         })
-        .unwrap_or_default();
-
-    let mut states = load::load_all_states(&config.path).unwrap_or_default();
+        .unwrap_or_default()
+}
 
-    // Update with current window states from cache
-    for (entity, entry) in &cached.0 {
-        let window_key = if primary_query.get(*entity).is_ok() {
-            WindowKey::Primary
-        } else if let Ok((_, _, _, Some(managed))) = all_windows.get(*entity) {
-            WindowKey::Managed(managed.name.clone())
-        } else {
-            // Entity may have been despawned - skip stale cached entry
-            continue;
-        };
+/// Write the cached window states under the active profile. Under `SavePolicy::OnExit`
+/// only does work with changes pending; under `SavePolicy::Continuous` only when focus or
+/// the virtual desktop changed since the last write, as those never trigger one alone.
+///
+/// Runs in `Last` when an `AppExit` is written, which includes the one written when the
+/// last window closes, and before switching profiles. Windows may already be despawned
+/// at exit, so `ActiveOnly` writes the cache as-is; closed managed windows were dropped
+/// from it by `on_managed_window_removed`.
+pub(crate) fn flush_pending_window_state(
+    mut commands: Commands,
+    restore_window_config: Res<RestoreWindowConfig>,
+    managed_window_persistence: Res<ManagedWindowPersistence>,
+    monitors: Res<Monitors>,
+    windows: Query<
+        (Entity, &Window, Option<&ManagedWindow>),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: Query<(), With<PrimaryWindow>>,
+    mut cached: ResMut<WindowStateCache>,
+    _: NonSendMarker,
+) {
+    let captured_change = cached.capture_write_time_state(windows, &primary_query);
+    let state_write = match restore_window_config.save_policy {
+        SavePolicy::OnExit => cached.pending_write,
+        // Changes held back by the startup save delay are startup noise, not worth a write.
+        SavePolicy::Continuous if cached.startup_write == StateWrite::NotNeeded => captured_change,
+        SavePolicy::Continuous => StateWrite::NotNeeded,
+    };
+    if state_write == StateWrite::NotNeeded {
+        return;
+    }
 
-        if let Some(saved_window_mode) = &entry.saved_window_mode {
-            let monitor_index = entry.monitor.unwrap_or(PRIMARY_MONITOR_INDEX);
-            let monitor_scale = monitors
-                .by_index(monitor_index)
-                .map_or(DEFAULT_SCALE_FACTOR, |monitor| monitor.scale);
-            let logical_position = entry.physical_position.map(|physical_position| {
-                let logical_x = (f64::from(physical_position.x) / monitor_scale)
-                    .round()
-                    .to_i32();
-                let logical_y = (f64::from(physical_position.y) / monitor_scale)
-                    .round()
-                    .to_i32();
-                (logical_x, logical_y)
-            });
-            states.insert(
-                window_key,
-                WindowState {
-                    logical_position,
-                    logical_width: entry.logical_size.x,
-                    logical_height: entry.logical_size.y,
-                    scale: monitor_scale,
-                    monitor: monitor_index,
-                    saved_window_mode: saved_window_mode.clone(),
-                    app_name: app_name.clone(),
-                },
+    match *managed_window_persistence {
+        ManagedWindowPersistence::ActiveOnly => {
+            save_all_states_or_notify(
+                &restore_window_config,
+                &cached_window_states(&monitors, &cached),
             );
-        }
+        },
+        ManagedWindowPersistence::RememberAll => {
+            persist_remember_all(&restore_window_config, &monitors, &cached, &mut commands);
+        },
     }
-
-    save_all_states(&config.path, &states);
+    cached.pending_write = StateWrite::NotNeeded;
+    debug!(
+        "[flush_pending_window_state] Saved window state to {:?}",
+        restore_window_config.path
+    );
 }
 
 /// Save window state when position, size, or mode changes. Runs only when not restoring.
 ///
-/// Handles both the primary window and any `ManagedWindow` entities. Uses
-/// `ManagedWindowPersistence` to decide whether closed windows keep their saved state.
+/// Until `startup_save_delay` has passed, changes only update the cache; the write they
+/// need happens once the delay ends, so startup noise never reaches the state file.
+///
+/// Handles both the primary window and any `ManagedWindow` entities, skipping those marked
+/// `DoNotRestore`. Uses `ManagedWindowPersistence` to decide whether closed windows keep
+/// their saved state.
 pub(crate) fn save_window_state(
+    mut commands: Commands,
     restore_window_config: Res<RestoreWindowConfig>,
     monitors: Res<Monitors>,
     managed_window_persistence: Res<ManagedWindowPersistence>,
@@ -252,10 +635,18 @@ pub(crate) fn save_window_state(
             &Window,
             Option<&CurrentMonitor>,
             Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
         ),
         (
             Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
-            Or<(Changed<Window>, Changed<CurrentMonitor>)>,
+            Without<DoNotRestore>,
+            Or<(
+                Changed<Window>,
+                Changed<CurrentMonitor>,
+                Changed<WindowIconPath>,
+                Changed<WindowExtra>,
+            )>,
         ),
     >,
     all_windows: Query<
@@ -264,11 +655,16 @@ pub(crate) fn save_window_state(
             &Window,
             Option<&CurrentMonitor>,
             Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
         ),
         Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
     >,
     primary_query: Query<(), With<PrimaryWindow>>,
-    mut cached: Local<WindowStateCache>,
+    mut cached: ResMut<WindowStateCache>,
+    mut geometry_history: ResMut<WindowGeometryHistory>,
+    time: Res<Time<Real>>,
     _: NonSendMarker,
 ) {
     // Can't save state if no monitors exist (e.g., laptop lid closed).
@@ -276,141 +672,511 @@ pub(crate) fn save_window_state(
         return;
     }
 
-    let mut state_write = StateWrite::NotNeeded;
+    let in_startup_delay = time.elapsed() < restore_window_config.startup_save_delay;
+    let mut state_write = if in_startup_delay {
+        StateWrite::NotNeeded
+    } else {
+        mem::take(&mut cached.startup_write)
+    };
 
-    for (window_entity, window, existing_monitor, managed) in &windows {
-        // Determine the key for this window in the state file
-        let window_key = if primary_query.get(window_entity).is_ok() {
-            WindowKey::Primary
-        } else if let Some(managed_window) = managed {
-            WindowKey::Managed(managed_window.name.clone())
-        } else {
+    for (window_entity, window, existing_monitor, managed, icon_path, extra) in &windows {
+        let Some(window_key) = window_key(window_entity, managed, &primary_query) else {
             continue;
         };
 
-        // Get window position for saving state.
-        let physical_position = get_window_position(window_entity, window);
+        // While minimized, the OS reports bogus geometry (Windows moves the window to
+        // (-32000, -32000) at 0x0), so keep the last known good values and only record
+        // the flag.
+        if is_minimized(&LiveWinitWindows, window_entity) {
+            if cached.record_minimized(&window_key) {
+                state_write = StateWrite::Needed;
+            }
+            continue;
+        }
+        if has_transient_size(window) {
+            debug!("[save_window_state] [{window_key}] transient size, keeping last geometry");
+            continue;
+        }
+
+        let physical_position = get_window_position(&LiveWinitWindows, window_entity, window);
 
         let physical_width = window.resolution.physical_width();
         let physical_height = window.resolution.physical_height();
-        let logical_width = window.resolution.width().to_u32();
-        let logical_height = window.resolution.height().to_u32();
+        let logical_size = cached.stabilize_logical_size(
+            &window_key,
+            UVec2::new(
+                window.resolution.width().round().to_u32(),
+                window.resolution.height().round().to_u32(),
+            ),
+        );
+        let (logical_width, logical_height) = (logical_size.x, logical_size.y);
         let resolution_scale = window.resolution.scale_factor();
+        let cached_window_state = cached.entries.entry(window_key.clone()).or_default();
 
         // Read monitor and effective mode from `CurrentMonitor` (maintained by
         // `update_current_monitor`)
-        let (monitor_index, monitor_scale) = existing_monitor.map_or_else(
-            || {
-                let monitor_info = monitors.first();
-                (monitor_info.index, monitor_info.scale)
-            },
-            |current_monitor| (current_monitor.index, current_monitor.scale),
-        );
-        let saved_window_mode: SavedWindowMode = existing_monitor.map_or_else(
-            || (&window.mode).into(),
-            |current_monitor| (&current_monitor.effective_window_mode).into(),
-        );
-
-        let cached_window_state = cached.0.entry(window_entity).or_default();
+        let monitor_info = saved_monitor(&monitors, existing_monitor, physical_position, window);
+        let (monitor_index, monitor_scale) = (monitor_info.index, monitor_info.scale);
+        let saved_window_mode =
+            resolve_saved_window_mode(&LiveWinitWindows, window_entity, window, existing_monitor);
 
-        // Only save if position, size, or mode actually changed
-        let position_changed = cached_window_state.physical_position != physical_position;
-        let size_changed =
-            cached_window_state.logical_size != UVec2::new(logical_width, logical_height);
-        let mode_changed =
-            cached_window_state.saved_window_mode.as_ref() != Some(&saved_window_mode);
-        let monitor_changed = cached_window_state.monitor != Some(monitor_index);
-        if !position_changed && !size_changed && !mode_changed && !monitor_changed {
+        // Only save if something persisted actually changed
+        let observed_window_state = CachedWindowState {
+            physical_position,
+            logical_size: UVec2::new(logical_width, logical_height),
+            saved_window_mode: Some(saved_window_mode.clone()),
+            monitor: Some(monitor_index),
+            minimized: false,
+            window_attributes: SavedWindowAttributes::from_window(window),
+            opacity: opacity::window_opacity(window_entity).unwrap_or(FULLY_OPAQUE),
+            title: restore_window_config.saved_title(window),
+            icon_path: icon_path.map(|WindowIconPath(icon_path)| icon_path.clone()),
+            extra: saved_extra(extra),
+            virtual_desktop: cached_window_state.virtual_desktop.clone(),
+            space_behavior: macos_spaces::window_space_behavior(window_entity),
+            focused: cached_window_state.focused,
+            logical_invisible_border: logical_invisible_border(window_entity, monitor_scale),
+            follows_current_monitor: follows_current_monitor(&window.mode),
+            windowed_bounds: observed_windowed_bounds(
+                &saved_window_mode,
+                physical_position,
+                UVec2::new(logical_width, logical_height),
+                monitor_scale,
+                cached_window_state,
+            ),
+        };
+        if *cached_window_state == observed_window_state {
             continue;
         }
 
-        debug!(
-            "[save_window_state] [{window_key}] SAVE DETAIL: position={physical_position:?} physical={physical_width}x{physical_height} logical={logical_width}x{logical_height} resolution_scale={resolution_scale} monitor={monitor_index} mode={saved_window_mode:?}",
+        replace_cached_state(
+            &mut geometry_history,
+            &window_key,
+            cached_window_state,
+            observed_window_state,
+            &monitors,
         );
-
-        // Log monitor transitions with detailed info
-        if monitor_changed {
-            let previous_scale = cached_window_state
-                .monitor
-                .and_then(|monitor_index| monitors.by_index(monitor_index))
-                .map(|monitor| monitor.scale);
-            debug!(
-                "[save_window_state] [{window_key}] MONITOR CHANGE: {:?} (scale={previous_scale:?}) -> {monitor_index} (scale={monitor_scale})",
-                cached_window_state.monitor,
-            );
-        }
-
-        // Update cache
-        cached_window_state.physical_position = physical_position;
-        cached_window_state.logical_size = UVec2::new(logical_width, logical_height);
-        cached_window_state.saved_window_mode = Some(saved_window_mode.clone());
-        cached_window_state.monitor = Some(monitor_index);
-
         state_write = StateWrite::Needed;
 
         debug!(
-            "[save_window_state] [{window_key}] position={physical_position:?} logical={logical_width}x{logical_height} physical={physical_width}x{physical_height} monitor={monitor_index} scale={monitor_scale} mode={saved_window_mode:?}",
+            "[save_window_state] [{window_key}] position={physical_position:?} logical={logical_width}x{logical_height} physical={physical_width}x{physical_height} monitor={monitor_index} scale={monitor_scale} resolution_scale={resolution_scale} mode={saved_window_mode:?}",
         );
     }
 
     if state_write == StateWrite::NotNeeded {
         return;
     }
+    if restore_window_config.save_policy == SavePolicy::OnExit {
+        cached.pending_write = StateWrite::Needed;
+        return;
+    }
+    if in_startup_delay {
+        cached.startup_write = StateWrite::Needed;
+        debug!("[save_window_state] Within startup save delay, deferring write");
+        return;
+    }
 
-    match *managed_window_persistence {
+    write_changed_states(
+        &restore_window_config,
+        &managed_window_persistence,
+        &monitors,
+        &all_windows,
+        &primary_query,
+        &mut cached,
+        &mut commands,
+    );
+}
+
+/// Replace a window's cached state with the changed `observed_window_state`, logging a
+/// monitor change and pushing the previous geometry onto the undo history.
+fn replace_cached_state(
+    geometry_history: &mut WindowGeometryHistory,
+    window_key: &WindowKey,
+    cached_window_state: &mut CachedWindowState,
+    observed_window_state: CachedWindowState,
+    monitors: &Monitors,
+) {
+    if let Some(monitor_index) = observed_window_state.monitor
+        && cached_window_state.monitor != Some(monitor_index)
+    {
+        log_monitor_change(
+            window_key,
+            cached_window_state.monitor,
+            monitor_index,
+            monitors,
+        );
+    }
+    remember_previous_geometry(geometry_history, window_key, cached_window_state, monitors);
+    *cached_window_state = observed_window_state;
+}
+
+/// Write the state file after `save_window_state` saw a change, per
+/// `ManagedWindowPersistence`.
+fn write_changed_states(
+    config: &RestoreWindowConfig,
+    managed_window_persistence: &ManagedWindowPersistence,
+    monitors: &Monitors,
+    all_windows: &Query<
+        (
+            Entity,
+            &Window,
+            Option<&CurrentMonitor>,
+            Option<&ManagedWindow>,
+            Option<&WindowIconPath>,
+            Option<&WindowExtra>,
+            Has<DoNotRestore>,
+        ),
+        Or<(With<PrimaryWindow>, With<ManagedWindow>)>,
+    >,
+    primary_query: &Query<(), With<PrimaryWindow>>,
+    cached: &mut WindowStateCache,
+    commands: &mut Commands,
+) {
+    match managed_window_persistence {
         ManagedWindowPersistence::ActiveOnly => {
             // Build state from all active windows and write in one shot
             save_active_window_state(
-                &restore_window_config,
-                &monitors,
-                &all_windows,
-                &primary_query,
+                config,
+                monitors,
+                all_windows,
+                primary_query,
                 None,
+                cached,
+                commands,
             );
         },
         ManagedWindowPersistence::RememberAll => {
-            persist_remember_all(
-                &restore_window_config,
-                &monitors,
-                &cached,
-                &all_windows,
-                &primary_query,
+            cached.capture_write_time_state(
+                all_windows
+                    .iter()
+                    .map(|(entity, window, _, managed, ..)| (entity, window, managed)),
+                primary_query,
             );
+            persist_remember_all(config, monitors, cached, commands);
         },
     }
 }
 
+/// Settings the app set through `WindowExtra`, or none.
+fn saved_extra(extra: Option<&WindowExtra>) -> BTreeMap<String, String> {
+    extra
+        .map(|WindowExtra(extra)| extra.clone())
+        .unwrap_or_default()
+}
+
+/// Convert a physical window position to logical pixels at `monitor_scale`.
+fn to_logical_position(physical_position: IVec2, monitor_scale: f64) -> (i32, i32) {
+    let logical_x = (f64::from(physical_position.x) / monitor_scale)
+        .round()
+        .to_i32();
+    let logical_y = (f64::from(physical_position.y) / monitor_scale)
+        .round()
+        .to_i32();
+    (logical_x, logical_y)
+}
+
+/// Windowed geometry to remember for a window: its current geometry while windowed,
+/// otherwise the geometry remembered before it left windowed mode.
+fn observed_windowed_bounds(
+    saved_window_mode: &SavedWindowMode,
+    physical_position: Option<IVec2>,
+    logical_size: UVec2,
+    monitor_scale: f64,
+    cached_window_state: &CachedWindowState,
+) -> Option<RestoreBounds> {
+    if *saved_window_mode != SavedWindowMode::Windowed {
+        return cached_window_state.windowed_bounds;
+    }
+    Some(RestoreBounds {
+        logical_position: physical_position
+            .map(|physical_position| to_logical_position(physical_position, monitor_scale)),
+        logical_width:    logical_size.x,
+        logical_height:   logical_size.y,
+    })
+}
+
+/// Key of a primary or managed window in the state file.
+fn window_key(
+    entity: Entity,
+    managed: Option<&ManagedWindow>,
+    primary_query: &Query<(), With<PrimaryWindow>>,
+) -> Option<WindowKey> {
+    if primary_query.get(entity).is_ok() {
+        Some(WindowKey::Primary)
+    } else {
+        managed.map(|managed_window| WindowKey::Managed(managed_window.name.clone()))
+    }
+}
+
+/// Remember the geometry a window had before its latest change, so
+/// `undo_window_geometry` can return to it.
+fn remember_previous_geometry(
+    geometry_history: &mut WindowGeometryHistory,
+    window_key: &WindowKey,
+    previous_window_state: &CachedWindowState,
+    monitors: &Monitors,
+) {
+    if let Some(previous_window_state) =
+        previous_window_state.to_window_state(monitors, &current_app_name())
+    {
+        geometry_history.push(window_key.clone(), previous_window_state);
+    }
+}
+
+/// Invisible resize border of the window (winit #4107), converted to logical pixels at
+/// `monitor_scale`.
+fn logical_invisible_border(entity: Entity, monitor_scale: f64) -> Option<(i32, i32)> {
+    windows_invisible_border::physical_invisible_border(entity).map(|physical_border| {
+        let logical_border = physical_border.as_dvec2() / monitor_scale;
+        (
+            logical_border.x.round().to_i32(),
+            logical_border.y.round().to_i32(),
+        )
+    })
+}
+
+/// Log a window's monitor transition with the scale on each side.
+fn log_monitor_change(
+    window_key: &WindowKey,
+    previous_monitor: Option<usize>,
+    monitor_index: usize,
+    monitors: &Monitors,
+) {
+    let scale_of = |monitor_index: usize| {
+        monitors
+            .by_index(monitor_index)
+            .map(|monitor| monitor.scale)
+    };
+    debug!(
+        "[save_window_state] [{window_key}] MONITOR CHANGE: {previous_monitor:?} (scale={:?}) -> {monitor_index} (scale={:?})",
+        previous_monitor.and_then(scale_of),
+        scale_of(monitor_index),
+    );
+}
+
+/// Resolve the window mode to persist.
+///
+/// Prefers `CurrentMonitor.effective_window_mode` over `Window.mode`, then refines
+/// `Windowed` to `Maximized` when winit reports the window maximized, since Bevy's
+/// `WindowMode` has no maximized state.
+fn resolve_saved_window_mode(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    window: &Window,
+    existing_monitor: Option<&CurrentMonitor>,
+) -> SavedWindowMode {
+    let saved_window_mode: SavedWindowMode = existing_monitor.map_or_else(
+        || (&window.mode).into(),
+        |current_monitor| (&current_monitor.effective_window_mode).into(),
+    );
+    if saved_window_mode != SavedWindowMode::Windowed {
+        return saved_window_mode;
+    }
+
+    if winit_windows.is_maximized(entity) == Some(true) {
+        SavedWindowMode::Maximized
+    } else {
+        saved_window_mode
+    }
+}
+
+/// Whether the window is smaller than [`MIN_SAVED_LOGICAL_SIZE`] on either axis, which no
+/// real layout is. Such a size is transient, e.g. left by a restore interrupted mid-way,
+/// and saving it would restore an unusable window on the next launch.
+fn has_transient_size(window: &Window) -> bool {
+    window.resolution.width().round().to_u32() < MIN_SAVED_LOGICAL_SIZE
+        || window.resolution.height().round().to_u32() < MIN_SAVED_LOGICAL_SIZE
+}
+
+/// Whether winit reports the window as minimized. Unsupported platforms report `false`.
+pub(crate) fn is_minimized(winit_windows: &impl WinitWindowAccess, entity: Entity) -> bool {
+    winit_windows.is_minimized(entity).unwrap_or(false)
+}
+
 /// Get window position from the OS via winit, falling back to `Window.position`.
 ///
 /// On macOS, `Window.position` stays `Automatic` even after the OS places the window,
 /// so we must query winit directly. On Linux with W5 workaround, we also use winit
 /// to get `outer_position` (frame origin). On other platforms, `Window.position` suffices.
-pub(super) fn get_window_position(entity: Entity, window: &Window) -> Option<IVec2> {
+pub(crate) fn get_window_position(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    window: &Window,
+) -> Option<IVec2> {
     #[cfg(any(
         target_os = "macos",
         all(target_os = "linux", feature = "workaround-winit-4443")
     ))]
     {
         let _ = window;
-        WINIT_WINDOWS.with(|winit_windows| {
-            let winit_windows = winit_windows.borrow();
-            let winit_window = winit_windows.get_window(entity)?;
-            let physical_outer_position = winit_window.outer_position().ok()?;
-            Some(IVec2::new(
-                physical_outer_position.x,
-                physical_outer_position.y,
-            ))
-        })
+        winit_windows.outer_position(entity)
     }
     #[cfg(not(any(
         target_os = "macos",
         all(target_os = "linux", feature = "workaround-winit-4443")
     )))]
     {
-        let _ = entity;
+        let _ = (winit_windows, entity);
         match window.position {
             WindowPosition::At(p) => Some(p),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::MemoryStateStore;
+    use crate::WindowManagerPlugin;
+    use crate::constants::DEFAULT_PROFILE;
+    use crate::restore::TargetPosition;
+    use crate::test_support;
+    use crate::test_support::FakeWinitWindow;
+
+    #[test]
+    fn restored_size_does_not_drift_across_scale_round_trips() {
+        let mut cached = WindowStateCache::default();
+        let mut saved_logical_size = UVec2::new(801, 601);
+
+        for scale in [2.0, 1.0, 2.0, 1.0, 2.0, 1.0] {
+            // Restore at `scale`, then let the OS hand back one physical pixel more, as a
+            // decoration adjustment rounding the other way does.
+            let physical_size = (saved_logical_size.as_dvec2() * scale).round() + 1.0;
+            let reported_logical_size = (physical_size / scale).round().as_uvec2();
+            cached.record_restored_size(WindowKey::Primary, saved_logical_size);
+            saved_logical_size =
+                cached.stabilize_logical_size(&WindowKey::Primary, reported_logical_size);
+        }
+
+        assert_eq!(saved_logical_size, UVec2::new(801, 601));
+    }
+
+    #[test]
+    fn real_resize_after_restore_is_saved() {
+        let mut cached = WindowStateCache::default();
+        cached.record_restored_size(WindowKey::Primary, UVec2::new(800, 600));
+
+        let resized = UVec2::new(900, 600);
+        assert_eq!(
+            cached.stabilize_logical_size(&WindowKey::Primary, resized),
+            resized
+        );
+        let nudged = UVec2::new(801, 600);
+        assert_eq!(
+            cached.stabilize_logical_size(&WindowKey::Primary, nudged),
+            nudged
+        );
+    }
+
+    #[test]
+    fn windowed_window_winit_reports_maximized_is_saved_maximized() {
+        let window = Window::default();
+        let restored = FakeWinitWindow::default();
+        let maximized = FakeWinitWindow {
+            maximized: true,
+            ..default()
+        };
+
+        assert_eq!(
+            resolve_saved_window_mode(&restored, Entity::PLACEHOLDER, &window, None),
+            SavedWindowMode::Windowed
+        );
+        assert_eq!(
+            resolve_saved_window_mode(&maximized, Entity::PLACEHOLDER, &window, None),
+            SavedWindowMode::Maximized
+        );
+        assert!(!is_minimized(&maximized, Entity::PLACEHOLDER));
+    }
+
+    #[test]
+    fn active_only_save_keeps_the_restored_size() {
+        let memory_store = MemoryStateStore::default();
+        let mut app = test_support::headless_app(
+            WindowManagerPlugin::builder()
+                .memory_store(memory_store.clone())
+                .persistence(ManagedWindowPersistence::ActiveOnly),
+        );
+        let primary_window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        app.update();
+        app.world_mut()
+            .entity_mut(primary_window)
+            .remove::<TargetPosition>();
+
+        // Restored at 801x601, then reported a pixel larger after the scale conversion.
+        app.world_mut()
+            .resource_mut::<WindowStateCache>()
+            .record_restored_size(WindowKey::Primary, UVec2::new(801, 601));
+        if let Some(mut window) = app.world_mut().get_mut::<Window>(primary_window) {
+            window.resolution.set(802.0, 602.0);
+        }
+        crate::save_current_window_state(app.world_mut());
+
+        let saved = memory_store.states(DEFAULT_PROFILE).unwrap_or_default();
+        assert_eq!(
+            saved
+                .get(&WindowKey::Primary)
+                .map(WindowState::logical_size),
+            Some(UVec2::new(801, 601))
+        );
+    }
+
+    #[test]
+    fn do_not_restore_window_keeps_its_saved_state_when_another_window_saves() {
+        let memory_store = MemoryStateStore::default();
+        let saved_primary = WindowState::new(UVec2::new(1280, 720));
+        memory_store.set_states(
+            DEFAULT_PROFILE,
+            HashMap::from([(WindowKey::Primary, saved_primary.clone())]),
+        );
+        let mut app = test_support::headless_app(
+            WindowManagerPlugin::builder()
+                .memory_store(memory_store.clone())
+                .persistence(ManagedWindowPersistence::ActiveOnly)
+                .startup_save_delay(Duration::ZERO),
+        );
+        let primary_window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow, DoNotRestore))
+            .id();
+        app.update();
+        app.world_mut()
+            .entity_mut(primary_window)
+            .remove::<TargetPosition>();
+        let inspector = app
+            .world_mut()
+            .spawn((
+                Window::default(),
+                ManagedWindow {
+                    name: "inspector".to_string(),
+                },
+            ))
+            .id();
+        app.update();
+
+        // The primary window is mid-animation while the inspector is resized.
+        for (entity, logical_size) in [
+            (primary_window, (640.0, 480.0)),
+            (inspector, (900.0, 700.0)),
+        ] {
+            if let Some(mut window) = app.world_mut().get_mut::<Window>(entity) {
+                window.resolution.set(logical_size.0, logical_size.1);
+            }
+        }
+        app.update();
+
+        let saved = memory_store.states(DEFAULT_PROFILE).unwrap_or_default();
+        assert_eq!(saved.get(&WindowKey::Primary), Some(&saved_primary));
+        assert_eq!(
+            saved
+                .get(&WindowKey::Managed("inspector".to_string()))
+                .map(WindowState::logical_size),
+            Some(UVec2::new(900, 700))
+        );
+    }
+}
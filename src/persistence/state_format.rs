@@ -0,0 +1,49 @@
+//! Serialization backend selection for the state file.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use super::constants::CBOR_EXTENSION;
+use super::constants::JSON_EXTENSION;
+
+/// Serialization format of the state file.
+///
+/// Inferred from the state file extension: a `.json` path selects [`StateFormat::Json`],
+/// a `.cbor` path selects [`StateFormat::Cbor`], anything else selects [`StateFormat::Ron`].
+/// All formats store identical data, so switching formats only changes how the file reads
+/// on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum StateFormat {
+    /// Rusty Object Notation (default), prefixed with a header comment documenting units.
+    #[default]
+    Ron,
+    /// JSON, for tools and users that inspect or hand-edit the state file.
+    Json,
+    /// Compact binary CBOR, for deployments that want small, fast state files and never
+    /// read them by hand. Files start with the CBOR self-describe tag, so they load as
+    /// CBOR whatever the path's extension.
+    ///
+    /// CBOR is self-describing, unlike `bincode` or `postcard`, so fields that are left
+    /// out when unset still decode.
+    Cbor,
+}
+
+impl StateFormat {
+    /// Infer the format from a state file path's extension.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let has_extension = |expected: &str| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
+        };
+        if has_extension(JSON_EXTENSION) {
+            Self::Json
+        } else if has_extension(CBOR_EXTENSION) {
+            Self::Cbor
+        } else {
+            Self::Ron
+        }
+    }
+}
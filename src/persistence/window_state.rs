@@ -5,40 +5,89 @@
     reason = "false positive on enum variant fields"
 )]
 
+use std::collections::BTreeMap;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+
 use bevy::prelude::*;
 use bevy::window::MonitorSelection;
 use bevy::window::VideoMode;
 use bevy::window::VideoModeSelection;
+use bevy::window::WindowLevel;
 use bevy::window::WindowMode;
 use serde::Deserialize;
 use serde::Serialize;
+use serde::de::IgnoredAny;
 
+use super::constants::MAX_REMEMBERED_MONITORS;
 use crate::constants::DEFAULT_SCALE_FACTOR;
+use crate::constants::FULLY_OPAQUE;
+use crate::constants::PRIMARY_MONITOR_INDEX;
 
-/// Saved video mode for exclusive fullscreen.
+/// A monitor video mode, as saved for exclusive fullscreen and listed in
+/// [`MonitorInfo::video_modes`](crate::MonitorInfo::video_modes).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
-pub(crate) struct SavedVideoMode {
-    pub(super) physical_size:           UVec2,
-    pub(super) bit_depth:               u16,
-    pub(super) refresh_rate_millihertz: u32,
+pub struct SavedVideoMode {
+    /// Resolution in physical pixels.
+    pub physical_size:           UVec2,
+    /// Bits per pixel.
+    pub bit_depth:               u16,
+    /// Refresh rate in millihertz.
+    pub refresh_rate_millihertz: u32,
 }
 
 impl SavedVideoMode {
-    /// Convert to Bevy's `VideoMode`.
+    /// Convert to Bevy's `VideoMode`, e.g. for `VideoModeSelection::Specific`.
     #[must_use]
-    const fn to_video_mode(&self) -> VideoMode {
+    pub const fn to_video_mode(&self) -> VideoMode {
         VideoMode {
             physical_size:           self.physical_size,
             bit_depth:               self.bit_depth,
             refresh_rate_millihertz: self.refresh_rate_millihertz,
         }
     }
+
+    /// The mode in `video_modes` closest to this one: nearest resolution first, then
+    /// nearest refresh rate, then nearest bit depth. `None` when `video_modes` is empty.
+    #[must_use]
+    fn closest_available(&self, video_modes: &[VideoMode]) -> Option<Self> {
+        video_modes
+            .iter()
+            .min_by_key(|video_mode| {
+                (
+                    self.physical_size.x.abs_diff(video_mode.physical_size.x)
+                        + self.physical_size.y.abs_diff(video_mode.physical_size.y),
+                    self.refresh_rate_millihertz
+                        .abs_diff(video_mode.refresh_rate_millihertz),
+                    self.bit_depth.abs_diff(video_mode.bit_depth),
+                )
+            })
+            .map(Self::from)
+    }
+}
+
+impl From<&VideoMode> for SavedVideoMode {
+    fn from(video_mode: &VideoMode) -> Self {
+        Self {
+            physical_size:           video_mode.physical_size,
+            bit_depth:               video_mode.bit_depth,
+            refresh_rate_millihertz: video_mode.refresh_rate_millihertz,
+        }
+    }
 }
 
 /// Serializable window mode.
+///
+/// Deserialization is tolerant: a mode this version does not know, such as one added by
+/// a newer version, loads as `Windowed` with a warning instead of failing the whole state.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[serde(from = "PersistedWindowMode")]
 pub(crate) enum SavedWindowMode {
     Windowed,
+    /// Windowed, but maximized by the OS. Bevy's `WindowMode` has no maximized state, so
+    /// this is detected through winit and restored with `Window::set_maximized`.
+    Maximized,
     BorderlessFullscreen,
     /// Exclusive fullscreen with optional specific video mode.
     Fullscreen {
@@ -47,13 +96,50 @@ pub(crate) enum SavedWindowMode {
     },
 }
 
+/// A window mode as read from the state file: one this version knows, or anything else.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PersistedWindowMode {
+    Known(KnownWindowMode),
+    Unknown(IgnoredAny),
+}
+
+/// `SavedWindowMode` with its derived, strict `Deserialize`.
+#[derive(Deserialize)]
+enum KnownWindowMode {
+    Windowed,
+    Maximized,
+    BorderlessFullscreen,
+    Fullscreen { video_mode: Option<SavedVideoMode> },
+}
+
+impl From<PersistedWindowMode> for SavedWindowMode {
+    fn from(persisted_window_mode: PersistedWindowMode) -> Self {
+        match persisted_window_mode {
+            PersistedWindowMode::Known(KnownWindowMode::Windowed) => Self::Windowed,
+            PersistedWindowMode::Known(KnownWindowMode::Maximized) => Self::Maximized,
+            PersistedWindowMode::Known(KnownWindowMode::BorderlessFullscreen) => {
+                Self::BorderlessFullscreen
+            },
+            PersistedWindowMode::Known(KnownWindowMode::Fullscreen { video_mode }) => {
+                Self::Fullscreen { video_mode }
+            },
+            PersistedWindowMode::Unknown(_) => {
+                warn!(
+                    "[SavedWindowMode::from] Unrecognized window mode in state file, using Windowed"
+                );
+                Self::Windowed
+            },
+        }
+    }
+}
+
 impl SavedWindowMode {
-    /// Convert to Bevy's `WindowMode` with the given monitor index.
+    /// Convert to Bevy's `WindowMode` on the given monitor.
     #[must_use]
-    pub(crate) const fn to_window_mode(&self, monitor_index: usize) -> WindowMode {
-        let monitor_selection = MonitorSelection::Index(monitor_index);
+    pub(crate) const fn to_window_mode(&self, monitor_selection: MonitorSelection) -> WindowMode {
         match self {
-            Self::Windowed => WindowMode::Windowed,
+            Self::Windowed | Self::Maximized => WindowMode::Windowed,
             Self::BorderlessFullscreen => WindowMode::BorderlessFullscreen(monitor_selection),
             Self::Fullscreen { video_mode: None } => {
                 WindowMode::Fullscreen(monitor_selection, VideoModeSelection::Current)
@@ -69,7 +155,35 @@ impl SavedWindowMode {
 
     /// Check if this is a fullscreen mode (borderless or exclusive).
     #[must_use]
-    pub(crate) const fn is_fullscreen(&self) -> bool { !matches!(self, Self::Windowed) }
+    pub(crate) const fn is_fullscreen(&self) -> bool {
+        !matches!(self, Self::Windowed | Self::Maximized)
+    }
+
+    /// Check if this is the maximized windowed state.
+    #[must_use]
+    pub(crate) const fn is_maximized(&self) -> bool { matches!(self, Self::Maximized) }
+
+    /// Replace a saved exclusive fullscreen video mode that is not among `video_modes` with
+    /// the closest one that is, e.g. after a monitor or cable change.
+    ///
+    /// Returns the replaced and substituted modes. Leaves the mode alone when `video_modes`
+    /// is empty, since the monitor reported nothing to pick from.
+    pub(crate) fn substitute_unavailable_video_mode(
+        &mut self,
+        video_modes: &[VideoMode],
+    ) -> Option<(SavedVideoMode, SavedVideoMode)> {
+        let Self::Fullscreen {
+            video_mode: Some(saved),
+        } = self
+        else {
+            return None;
+        };
+        if video_modes.contains(&saved.to_video_mode()) {
+            return None;
+        }
+        let closest = saved.closest_available(video_modes)?;
+        Some((mem::replace(saved, closest.clone()), closest))
+    }
 }
 
 impl From<&WindowMode> for SavedWindowMode {
@@ -80,45 +194,421 @@ impl From<&WindowMode> for SavedWindowMode {
             WindowMode::Fullscreen(_, video_mode_selection) => Self::Fullscreen {
                 video_mode: match video_mode_selection {
                     VideoModeSelection::Current => None,
-                    VideoModeSelection::Specific(mode) => Some(SavedVideoMode {
-                        physical_size:           mode.physical_size,
-                        bit_depth:               mode.bit_depth,
-                        refresh_rate_millihertz: mode.refresh_rate_millihertz,
-                    }),
+                    VideoModeSelection::Specific(video_mode) => {
+                        Some(SavedVideoMode::from(video_mode))
+                    },
                 },
             },
         }
     }
 }
 
+/// Whether `window_mode` is fullscreen on `MonitorSelection::Current`, following the
+/// window's monitor rather than a fixed one.
+#[must_use]
+pub(crate) const fn follows_current_monitor(window_mode: &WindowMode) -> bool {
+    matches!(
+        window_mode,
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            | WindowMode::Fullscreen(MonitorSelection::Current, _)
+    )
+}
+
+/// Serializable window stacking level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub(crate) enum SavedWindowLevel {
+    AlwaysOnBottom,
+    #[default]
+    Normal,
+    AlwaysOnTop,
+}
+
+impl From<WindowLevel> for SavedWindowLevel {
+    fn from(window_level: WindowLevel) -> Self {
+        match window_level {
+            WindowLevel::AlwaysOnBottom => Self::AlwaysOnBottom,
+            WindowLevel::Normal => Self::Normal,
+            WindowLevel::AlwaysOnTop => Self::AlwaysOnTop,
+        }
+    }
+}
+
+impl From<SavedWindowLevel> for WindowLevel {
+    fn from(saved_window_level: SavedWindowLevel) -> Self {
+        match saved_window_level {
+            SavedWindowLevel::AlwaysOnBottom => Self::AlwaysOnBottom,
+            SavedWindowLevel::Normal => Self::Normal,
+            SavedWindowLevel::AlwaysOnTop => Self::AlwaysOnTop,
+        }
+    }
+}
+
+/// How a macOS window takes part in Spaces, from the Spaces bits of
+/// `NSWindow.collectionBehavior`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub(crate) enum SavedSpaceBehavior {
+    /// Shown on every Space (`CanJoinAllSpaces`).
+    AllSpaces,
+    /// Follows the user to the active Space when shown (`MoveToActiveSpace`).
+    MoveToActiveSpace,
+}
+
+/// User-toggleable window attributes persisted alongside geometry.
+///
+/// Defaults match Bevy's `Window` defaults, so files written before these were saved
+/// restore windows unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[serde(default)]
+pub(crate) struct SavedWindowAttributes {
+    /// Whether the window has OS decorations (title bar and border).
+    pub(crate) decorations:  bool,
+    /// Whether the user can resize the window.
+    pub(crate) resizable:    bool,
+    /// Stacking level, e.g. always on top.
+    pub(crate) window_level: SavedWindowLevel,
+}
+
+impl Default for SavedWindowAttributes {
+    fn default() -> Self {
+        Self {
+            decorations:  true,
+            resizable:    true,
+            window_level: SavedWindowLevel::Normal,
+        }
+    }
+}
+
+impl SavedWindowAttributes {
+    /// Capture the attributes of a live window.
+    #[must_use]
+    pub(crate) fn from_window(window: &Window) -> Self {
+        Self {
+            decorations:  window.decorations,
+            resizable:    window.resizable,
+            window_level: window.window_level.into(),
+        }
+    }
+
+    /// Apply the attributes to a window.
+    ///
+    /// Restore applies them before the window's saved geometry, since the outer size
+    /// restore computes leaves room for a title bar only when `decorations` is set.
+    pub(crate) fn apply_to(self, window: &mut Window) {
+        window.decorations = self.decorations;
+        window.resizable = self.resizable;
+        window.window_level = self.window_level.into();
+    }
+}
+
+/// Window geometry last seen on one monitor, kept under
+/// [`GeometryMemory::PerMonitor`](crate::GeometryMemory::PerMonitor).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub(crate) struct MonitorGeometry {
+    pub(crate) monitor_name:           String,
+    /// Top-left corner relative to the monitor's top-left, in logical pixels.
+    pub(crate) logical_monitor_offset: Option<(i32, i32)>,
+    pub(crate) logical_width:          u32,
+    pub(crate) logical_height:         u32,
+}
+
+/// Windowed geometry of a maximized window, which it returns to when the user
+/// unmaximizes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[expect(
+    clippy::struct_field_names,
+    reason = "named like the matching `WindowState` fields, which are all logical pixels"
+)]
+pub(crate) struct RestoreBounds {
+    /// Top-left corner in logical pixels. `None` on Wayland.
+    pub(crate) logical_position: Option<(i32, i32)>,
+    pub(crate) logical_width:    u32,
+    pub(crate) logical_height:   u32,
+}
+
+impl RestoreBounds {
+    /// Content area size in logical pixels.
+    #[must_use]
+    pub(crate) const fn logical_size(&self) -> UVec2 {
+        UVec2::new(self.logical_width, self.logical_height)
+    }
+}
+
 /// Saved window state persisted to the RON file.
 ///
 /// All spatial values are in **logical pixels** — they represent the user's visual intent
 /// and are independent of scale factor. On restore, both position and size are converted
-/// to physical pixels using the target monitor's scale factor in
-/// [`compute_target_position`](crate::restore::compute_target_position).
+/// to physical pixels using the target monitor's scale factor.
 ///
-/// `scale` records the scale factor of the monitor at save time. It is informational
-/// only — restore uses the target monitor's live scale factor, not this saved value.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct WindowState {
+/// `scale` records the scale factor of the monitor at save time, so the physical size at
+/// save time is the logical size times `scale`. Restore converts with the target
+/// monitor's live scale factor; only [`SizePolicy::PreservePhysical`](crate::SizePolicy)
+/// reads the saved one, to keep that physical size.
+///
+/// Apps see it through the `on_save` and `on_load` hooks of
+/// [`WindowManagerPluginBuilder`](crate::WindowManagerPluginBuilder).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct WindowState {
     /// Top-left corner of the window content area in logical pixels.
     /// `None` on Wayland where clients cannot access window position.
-    pub(crate) logical_position:  Option<(i32, i32)>,
+    pub(crate) logical_position:         Option<(i32, i32)>,
     /// Content area width in logical pixels (excludes window decoration).
-    pub(crate) logical_width:     u32,
+    pub(crate) logical_width:            u32,
     /// Content area height in logical pixels (excludes window decoration).
-    pub(crate) logical_height:    u32,
-    /// Scale factor of the monitor at save time (informational, not used during restore).
+    pub(crate) logical_height:           u32,
+    /// Scale factor of the monitor at save time. Files without it load as `1.0`.
     #[serde(default = "default_monitor_scale", rename = "monitor_scale")]
-    pub(crate) scale:             f64,
+    pub(crate) scale:                    f64,
     #[serde(rename = "monitor_index")]
-    pub(crate) monitor:           usize,
+    pub(crate) monitor:                  usize,
+    /// Name of the monitor at save time, when the platform reports one.
+    /// Preferred over `monitor` on restore because indices shift when displays change.
+    #[serde(default)]
+    pub(crate) monitor_name:             Option<String>,
+    /// Top-left corner relative to the saved monitor's top-left, in logical pixels. Lets
+    /// a restore onto another monitor keep the window's place within it.
+    #[serde(default)]
+    pub(crate) logical_monitor_offset:   Option<(i32, i32)>,
+    /// Geometry per monitor the window has been saved on, most recent first. Empty unless
+    /// `GeometryMemory::PerMonitor` is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) monitor_geometries:       Vec<MonitorGeometry>,
+    /// Whether the window was minimized at save time. Position and size hold the last
+    /// geometry seen before it was minimized.
+    #[serde(default)]
+    pub(crate) minimized:                bool,
+    /// Windowed geometry the window had before it was maximized. Only saved for maximized
+    /// windows seen windowed first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) restore_bounds:           Option<RestoreBounds>,
     #[serde(rename = "mode")]
-    pub(crate) saved_window_mode: SavedWindowMode,
+    pub(crate) saved_window_mode:        SavedWindowMode,
+    /// Decorations, resizability, and stacking level.
+    #[serde(default, rename = "attributes")]
+    pub(crate) window_attributes:        SavedWindowAttributes,
+    /// Whole-window opacity, from `0.0` (transparent) to `1.0` (opaque). Honored on
+    /// macOS, Windows, and X11 under a compositing window manager; always `1.0` on
+    /// Wayland.
+    #[serde(default = "default_opacity")]
+    pub(crate) opacity:                  f32,
+    /// Window title at save time. Only saved under `TitleRestore::Restore`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) title:                    Option<String>,
+    /// Custom icon path the app set through [`WindowIconPath`](crate::WindowIconPath).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) icon_path:                Option<PathBuf>,
+    /// App-defined settings the app set through [`WindowExtra`](crate::WindowExtra).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) extra:                    BTreeMap<String, String>,
+    /// Windows virtual desktop the window was on, as the desktop GUID in hex. Only saved
+    /// with the `windows-virtual-desktops` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) virtual_desktop:          Option<String>,
+    /// macOS Spaces behavior the window had. Only saved with the `macos-spaces` feature,
+    /// and only when the window is not tied to a single Space.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) space_behavior:           Option<SavedSpaceBehavior>,
+    /// Whether the window had focus at save time. Read by
+    /// `FocusOnRestore::WhenSavedFocused`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) focused:                  bool,
+    /// Offset of the visible frame from `logical_position` on Windows, whose outer
+    /// position includes an invisible resize border (winit #4107). Only saved with the
+    /// `workaround-winit-4107` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) logical_invisible_border: Option<(i32, i32)>,
+    /// Whether the window was fullscreen on `MonitorSelection::Current`, following
+    /// whichever monitor it is on rather than a fixed one. Restored the same way instead
+    /// of pinning fullscreen to the saved monitor index.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) follows_current_monitor:  bool,
     #[serde(default)]
-    pub(crate) app_name:          String,
+    pub(crate) app_name:                 String,
+}
+
+impl WindowState {
+    /// A windowed state of `logical_size` on the first monitor at scale `1.0`, with no
+    /// position, e.g. to seed a [`MemoryStateStore`](crate::MemoryStateStore) in a test.
+    /// Set the rest through the setters.
+    ///
+    /// ```ignore
+    /// let mut window_state = WindowState::new(UVec2::new(1280, 720));
+    /// window_state.set_logical_position(IVec2::new(100, 50));
+    /// memory_store.set_states(
+    ///     "default",
+    ///     HashMap::from([(WindowKey::Primary, window_state)]),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn new(logical_size: UVec2) -> Self {
+        Self {
+            logical_position:         None,
+            logical_width:            logical_size.x,
+            logical_height:           logical_size.y,
+            scale:                    DEFAULT_SCALE_FACTOR,
+            monitor:                  PRIMARY_MONITOR_INDEX,
+            monitor_name:             None,
+            logical_monitor_offset:   None,
+            monitor_geometries:       Vec::new(),
+            minimized:                false,
+            restore_bounds:           None,
+            saved_window_mode:        SavedWindowMode::Windowed,
+            window_attributes:        SavedWindowAttributes::default(),
+            opacity:                  FULLY_OPAQUE,
+            title:                    None,
+            icon_path:                None,
+            extra:                    BTreeMap::new(),
+            virtual_desktop:          None,
+            space_behavior:           None,
+            focused:                  false,
+            logical_invisible_border: None,
+            follows_current_monitor:  false,
+            app_name:                 String::new(),
+        }
+    }
+
+    /// Top-left corner of the window in logical pixels. `None` on Wayland, where clients
+    /// cannot read the window position.
+    #[must_use]
+    pub fn logical_position(&self) -> Option<IVec2> {
+        self.logical_position.map(|(x, y)| IVec2::new(x, y))
+    }
+
+    /// Move the saved window to `logical_position`. Its offset within the saved monitor
+    /// moves by the same amount.
+    pub fn set_logical_position(&mut self, logical_position: IVec2) {
+        let delta = self
+            .logical_position()
+            .map_or(IVec2::ZERO, |previous| logical_position - previous);
+        self.logical_position = Some((logical_position.x, logical_position.y));
+        if let Some((x, y)) = self.logical_monitor_offset {
+            self.logical_monitor_offset = Some((x + delta.x, y + delta.y));
+        }
+    }
+
+    /// Content area size in logical pixels, excluding window decoration.
+    #[must_use]
+    pub const fn logical_size(&self) -> UVec2 {
+        UVec2::new(self.logical_width, self.logical_height)
+    }
+
+    /// Content area size in physical pixels at save time: the logical size times the saved
+    /// monitor's scale factor.
+    #[must_use]
+    pub fn physical_size(&self) -> UVec2 {
+        (self.logical_size().as_dvec2() * self.scale)
+            .round()
+            .as_uvec2()
+    }
+
+    /// Set the saved content area size in logical pixels.
+    pub const fn set_logical_size(&mut self, logical_size: UVec2) {
+        self.logical_width = logical_size.x;
+        self.logical_height = logical_size.y;
+    }
+
+    /// Index of the monitor the window was saved on, in [`Monitors`](crate::Monitors)
+    /// order at save time.
+    #[must_use]
+    pub const fn monitor_index(&self) -> usize { self.monitor }
+
+    /// Restore the window onto the monitor at `monitor_index`. Clears the saved monitor
+    /// name, which restore otherwise prefers over the index. An index with no monitor is
+    /// handled like a disconnected monitor, per [`MonitorFallback`](crate::MonitorFallback).
+    pub fn set_monitor_index(&mut self, monitor_index: usize) {
+        self.monitor = monitor_index;
+        self.monitor_name = None;
+    }
+
+    /// Name of the monitor the window was saved on, when the platform reports one.
+    #[must_use]
+    pub fn monitor_name(&self) -> Option<&str> { self.monitor_name.as_deref() }
+
+    /// Scale factor of the monitor the window was saved on.
+    #[must_use]
+    pub const fn monitor_scale(&self) -> f64 { self.scale }
+
+    /// Custom icon path the app set through [`WindowIconPath`](crate::WindowIconPath), if
+    /// any.
+    #[must_use]
+    pub fn icon_path(&self) -> Option<&Path> { self.icon_path.as_deref() }
+
+    /// Set or clear the saved icon path, e.g. to migrate a path after assets moved.
+    pub fn set_icon_path(&mut self, icon_path: Option<PathBuf>) { self.icon_path = icon_path; }
+
+    /// App-defined settings the app set through [`WindowExtra`](crate::WindowExtra).
+    #[must_use]
+    pub const fn extra(&self) -> &BTreeMap<String, String> { &self.extra }
+
+    /// App-defined settings, e.g. to rename a key in an `on_load` hook.
+    pub const fn extra_mut(&mut self) -> &mut BTreeMap<String, String> { &mut self.extra }
+
+    /// Whether the window had focus when this state was saved.
+    #[must_use]
+    pub const fn focused(&self) -> bool { self.focused }
+
+    /// Set whether the window counts as focused at save time, e.g. in an `on_load` hook to
+    /// keep a window from taking focus under `FocusOnRestore::WhenSavedFocused`.
+    pub const fn set_focused(&mut self, focused: bool) { self.focused = focused; }
+
+    /// Windowed geometry a window maximized after this state returns to: this state's own
+    /// geometry when windowed, otherwise its saved restore bounds.
+    #[must_use]
+    pub(crate) const fn windowed_bounds(&self) -> Option<RestoreBounds> {
+        match self.saved_window_mode {
+            SavedWindowMode::Windowed => Some(RestoreBounds {
+                logical_position: self.logical_position,
+                logical_width:    self.logical_width,
+                logical_height:   self.logical_height,
+            }),
+            _ => self.restore_bounds,
+        }
+    }
+
+    /// Geometry on the monitor the window was saved on. `None` when that monitor reported
+    /// no name, since geometry is remembered by monitor name.
+    fn monitor_geometry(&self) -> Option<MonitorGeometry> {
+        Some(MonitorGeometry {
+            monitor_name:           self.monitor_name.clone()?,
+            logical_monitor_offset: self.logical_monitor_offset,
+            logical_width:          self.logical_width,
+            logical_height:         self.logical_height,
+        })
+    }
+
+    /// Carry over the per-monitor geometry of `previous_state` and record this state's
+    /// geometry for its monitor, dropping the least recently used monitors past the limit.
+    pub(crate) fn remember_monitor_geometry(&mut self, previous_state: Option<&Self>) {
+        let mut monitor_geometries = previous_state
+            .map(|previous_state| previous_state.monitor_geometries.clone())
+            .unwrap_or_default();
+        let newest = [
+            previous_state.and_then(Self::monitor_geometry),
+            self.monitor_geometry(),
+        ];
+        for monitor_geometry in newest.into_iter().flatten() {
+            monitor_geometries
+                .retain(|remembered| remembered.monitor_name != monitor_geometry.monitor_name);
+            monitor_geometries.insert(0, monitor_geometry);
+        }
+        monitor_geometries.truncate(MAX_REMEMBERED_MONITORS);
+        self.monitor_geometries = monitor_geometries;
+    }
+
+    /// Geometry remembered for `monitor_name`, when it is not the monitor this state was
+    /// saved on. The saved monitor's geometry is the state itself.
+    #[must_use]
+    pub(crate) fn remembered_geometry(&self, monitor_name: &str) -> Option<&MonitorGeometry> {
+        if self.monitor_name.as_deref() == Some(monitor_name) {
+            return None;
+        }
+        self.monitor_geometries
+            .iter()
+            .find(|remembered| remembered.monitor_name == monitor_name)
+    }
 }
 
 /// Default monitor scale for deserialization of legacy files missing the field.
 const fn default_monitor_scale() -> f64 { DEFAULT_SCALE_FACTOR }
+
+/// Default opacity for deserialization of files saved before opacity was persisted.
+const fn default_opacity() -> f32 { FULLY_OPAQUE }
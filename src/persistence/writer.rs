@@ -0,0 +1,345 @@
+//! Background state file writes.
+//!
+//! Saves run on every window change, including each frame of a drag, so encoding and
+//! writing the state file happens on the `IoTaskPool` instead of the main schedule.
+//! Only the newest state is kept while a write is running, so a slow disk never builds a
+//! backlog, and a reload of the profiles sees that newest state rather than the stale file.
+//! Each write goes to the state file and then every mirror path. A read-only location is
+//! skipped for the rest of the session after its first failed write; the others keep
+//! being written.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::fs::create_dir_all;
+use std::fs::remove_file;
+use std::fs::rename;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use bevy::prelude::*;
+use bevy::tasks::IoTaskPool;
+use bevy::tasks::Task;
+use bevy::tasks::block_on;
+
+use super::constants::BACKUP_EXTENSION;
+use super::constants::TEMPORARY_EXTENSION;
+use super::format;
+use super::format::WindowKey;
+use super::state_format::StateFormat;
+use super::window_state::WindowState;
+use crate::WindowManagerError;
+use crate::WindowStateSaveFailed;
+use crate::restore_window_config::RestoreWindowConfig;
+
+/// Every profile's window states, keyed by profile name.
+pub(crate) type Profiles = HashMap<String, HashMap<WindowKey, WindowState>>;
+
+/// Locations that failed to write, each paired with its error.
+type WriteFailures = Vec<(PathBuf, WindowManagerError)>;
+
+/// A complete state file waiting to be written.
+#[derive(Clone)]
+struct PendingWrite {
+    path:         PathBuf,
+    mirror_paths: Vec<PathBuf>,
+    state_format: StateFormat,
+    profiles:     Profiles,
+}
+
+#[derive(Default)]
+struct WriterState {
+    /// Newest state not yet known to be on disk.
+    unwritten:       Option<PendingWrite>,
+    /// Whether `unwritten` still has to be handed to a write task.
+    queued:          bool,
+    /// Task of the write currently running.
+    in_flight:       Option<Task<WriteFailures>>,
+    /// Locations whose write failed because they are read-only. They are skipped for the
+    /// rest of the session.
+    read_only_paths: HashSet<PathBuf>,
+}
+
+impl WriterState {
+    /// The state file and mirror paths of `pending_write` that are not read-only, state
+    /// file first.
+    fn writable_paths(&self, pending_write: &PendingWrite) -> Vec<PathBuf> {
+        std::iter::once(&pending_write.path)
+            .chain(&pending_write.mirror_paths)
+            .filter(|path| !self.read_only_paths.contains(*path))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Coalescing background writer for the state file.
+///
+/// Cloning shares the same writer.
+#[derive(Clone, Default)]
+pub(crate) struct StateFileWriter(Arc<Mutex<WriterState>>);
+
+impl StateFileWriter {
+    fn lock(&self) -> MutexGuard<'_, WriterState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Queue `profiles` to be written to `path` and each of `mirror_paths`, replacing any
+    /// write still queued.
+    pub(crate) fn queue(
+        &self,
+        path: &Path,
+        mirror_paths: &[PathBuf],
+        state_format: StateFormat,
+        profiles: Profiles,
+    ) {
+        let mut writer_state = self.lock();
+        let pending_write = PendingWrite {
+            path: path.to_path_buf(),
+            mirror_paths: mirror_paths.to_vec(),
+            state_format,
+            profiles,
+        };
+        if writer_state.writable_paths(&pending_write).is_empty() {
+            return;
+        }
+        writer_state.unwritten = Some(pending_write);
+        writer_state.queued = true;
+        if writer_state.in_flight.is_none() {
+            start_next_write(&mut writer_state);
+        }
+    }
+
+    /// The newest profiles queued for `path` that may not be on disk yet.
+    pub(crate) fn unwritten_profiles(&self, path: &Path) -> Option<Profiles> {
+        self.lock()
+            .unwritten
+            .as_ref()
+            .filter(|pending_write| pending_write.path == path)
+            .map(|pending_write| pending_write.profiles.clone())
+    }
+
+    /// Collect a finished write and start the next one if a newer state is queued.
+    fn poll(&self) -> WriteFailures {
+        let mut writer_state = self.lock();
+        if !writer_state
+            .in_flight
+            .as_ref()
+            .is_some_and(Task::is_finished)
+        {
+            return Vec::new();
+        }
+        let failures = writer_state
+            .in_flight
+            .take()
+            .map(block_on)
+            .unwrap_or_default();
+        for (path, error) in &failures {
+            if is_read_only(error) && writer_state.read_only_paths.insert(path.clone()) {
+                warn!(
+                    "[poll] {} is not writable, window state will not be saved there this session",
+                    path.display()
+                );
+            }
+        }
+        let writable = writer_state
+            .unwritten
+            .as_ref()
+            .is_some_and(|pending_write| !writer_state.writable_paths(pending_write).is_empty());
+        if writer_state.queued && writable {
+            start_next_write(&mut writer_state);
+        } else {
+            writer_state.unwritten = None;
+            writer_state.queued = false;
+        }
+        failures
+    }
+
+    /// Drop any queued state and wait for the running write, so nothing is written after
+    /// this returns.
+    pub(crate) fn discard(&self) {
+        let in_flight = {
+            let mut writer_state = self.lock();
+            writer_state.unwritten = None;
+            writer_state.queued = false;
+            writer_state.in_flight.take()
+        };
+        for (path, error) in in_flight.map(block_on).unwrap_or_default() {
+            warn!("[discard] Write to {} failed: {error}", path.display());
+        }
+    }
+
+    /// Wait for the running write, then write any queued state on this thread.
+    pub(super) fn flush(&self) -> WriteFailures {
+        let (in_flight, pending_write) = {
+            let mut writer_state = self.lock();
+            let queued = mem::take(&mut writer_state.queued);
+            let pending_write = writer_state
+                .unwritten
+                .take()
+                .filter(|_| queued)
+                .map(|pending_write| (writer_state.writable_paths(&pending_write), pending_write));
+            (writer_state.in_flight.take(), pending_write)
+        };
+
+        let mut failures = in_flight.map(block_on).unwrap_or_default();
+        if let Some((paths, pending_write)) = pending_write {
+            failures.extend(write_to_all(
+                &paths,
+                pending_write.state_format,
+                &pending_write.profiles,
+            ));
+        }
+        failures
+    }
+}
+
+/// Whether `error` means the state file location cannot be written at all, e.g. a
+/// read-only filesystem or a sandboxed config directory, so retrying is pointless.
+fn is_read_only(error: &WindowManagerError) -> bool {
+    matches!(
+        error,
+        WindowManagerError::Io { source, .. }
+            if matches!(
+                source.kind(),
+                ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem
+            )
+    )
+}
+
+fn start_next_write(writer_state: &mut WriterState) {
+    let Some(pending_write) = writer_state.unwritten.clone() else {
+        return;
+    };
+    writer_state.queued = false;
+    let paths = writer_state.writable_paths(&pending_write);
+    let task = IoTaskPool::get().spawn(async move {
+        write_to_all(&paths, pending_write.state_format, &pending_write.profiles)
+    });
+    writer_state.in_flight = Some(task);
+}
+
+/// Write `profiles` to every path in turn. A failed path does not stop the others.
+fn write_to_all(
+    paths: &[PathBuf],
+    state_format: StateFormat,
+    profiles: &Profiles,
+) -> WriteFailures {
+    paths
+        .iter()
+        .filter_map(|path| {
+            write_profiles(path, state_format, profiles)
+                .err()
+                .map(|error| (path.clone(), error))
+        })
+        .collect()
+}
+
+/// `path` with `.extension` appended, e.g. `windows.ron.bak`.
+pub(super) fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.as_os_str().to_owned();
+    file_name.push(".");
+    file_name.push(extension);
+    PathBuf::from(file_name)
+}
+
+/// Encode every profile and write the state file, creating its directory if needed.
+///
+/// The contents go to a temporary file in the same directory that is then renamed over
+/// the state file, so a process killed mid-write never leaves a truncated file. The
+/// previous state file is kept as `<path>.bak` for `load_all_profiles` to fall back on.
+/// The temporary file is flushed to disk before the renames, so a power loss cannot leave
+/// the renamed state file empty.
+pub(super) fn write_profiles(
+    path: &Path,
+    state_format: StateFormat,
+    profiles: &Profiles,
+) -> Result<(), WindowManagerError> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|source| WindowManagerError::Io {
+            path: parent.to_path_buf(),
+            source,
+        })?;
+    }
+    let contents =
+        format::encode(profiles, state_format).map_err(|error| WindowManagerError::Serialize {
+            message: error.to_string(),
+        })?;
+    let temporary_path = sibling_path(path, TEMPORARY_EXTENSION);
+    write_synced(&temporary_path, &contents).map_err(|source| WindowManagerError::Io {
+        path: temporary_path.clone(),
+        source,
+    })?;
+
+    let backup_path = sibling_path(path, BACKUP_EXTENSION);
+    match rename(path, &backup_path) {
+        Ok(()) => {},
+        Err(error) if error.kind() == ErrorKind::NotFound => {},
+        Err(source) => {
+            return Err(WindowManagerError::Io {
+                path: backup_path,
+                source,
+            });
+        },
+    }
+    rename(&temporary_path, path).map_err(|source| WindowManagerError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Write `contents` to `path` and wait until the file's data has reached the disk.
+fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Delete the state file at `path`, its backup, and any temporary file left by an
+/// interrupted write. Files that do not exist are skipped.
+pub(crate) fn delete_state_file(path: &Path) -> Result<(), WindowManagerError> {
+    for path in [
+        path.to_path_buf(),
+        sibling_path(path, BACKUP_EXTENSION),
+        sibling_path(path, TEMPORARY_EXTENSION),
+    ] {
+        match remove_file(&path) {
+            Ok(()) => debug!("[delete_state_file] Deleted {}", path.display()),
+            Err(error) if error.kind() == ErrorKind::NotFound => {},
+            Err(source) => return Err(WindowManagerError::Io { path, source }),
+        }
+    }
+    Ok(())
+}
+
+/// Report finished background writes and start the next queued one.
+pub(crate) fn poll_state_file_writes(
+    mut commands: Commands,
+    restore_window_config: Res<RestoreWindowConfig>,
+) {
+    for (path, error) in restore_window_config.state_file_writer.poll() {
+        warn!("[poll_state_file_writes] {error}");
+        commands.trigger(WindowStateSaveFailed { path, error });
+    }
+}
+
+/// Write any queued state synchronously so nothing is lost when the app exits.
+///
+/// Runs in `Last` on `AppExit`, after `flush_pending_window_state` has queued the
+/// `SavePolicy::OnExit` state.
+pub(crate) fn flush_state_file_writes(
+    mut commands: Commands,
+    restore_window_config: Res<RestoreWindowConfig>,
+) {
+    for (path, error) in restore_window_config.state_file_writer.flush() {
+        warn!("[flush_state_file_writes] {error}");
+        commands.trigger(WindowStateSaveFailed { path, error });
+    }
+}
@@ -13,7 +13,6 @@ use std::env::var;
 use bevy::prelude::*;
 use bevy::window::WindowMode;
 
-use super::constants::SCALE_FACTOR_EPSILON;
 #[cfg(target_os = "linux")]
 use super::constants::WAYLAND_DISPLAY_ENV_VAR;
 use super::restore::FullscreenRestoreState;
@@ -22,6 +21,9 @@ use super::restore::WindowRestoreState;
 
 /// The display platform, detected once at startup and inserted as a [`Resource`].
 ///
+/// Apps can read it (or call [`Platform::detect`]) to branch on the same backend the
+/// plugin uses, e.g. X11 vs Wayland on Linux.
+///
 /// All platform-specific window restoration behavior is expressed as methods on
 /// this enum rather than ad-hoc `cfg!()` / `is_wayland()` checks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
@@ -71,9 +73,11 @@ impl Platform {
     #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     pub fn detect() -> Self { compile_error!("Unsupported platform") }
 
+    /// Whether the app is running under X11.
     #[must_use]
     pub const fn is_x11(self) -> bool { matches!(self, Self::X11) }
 
+    /// Whether the app is running under Wayland.
     #[must_use]
     pub const fn is_wayland(self) -> bool { matches!(self, Self::Wayland) }
 
@@ -180,7 +184,7 @@ impl Platform {
     ///
     /// - Without `workaround-winit-4440`: always `ApplyUnchanged`.
     /// - **Wayland**: handles DPI natively → `ApplyUnchanged`.
-    /// - **Same scale**: no cross-DPI issue → `ApplyUnchanged`.
+    /// - **Same scale** (within `scale_factor_epsilon`): no cross-DPI issue → `ApplyUnchanged`.
     /// - **Windows**: position unaffected, size goes through scale conversion →
     ///   `CompensateSizeOnly` with two-phase approach.
     /// - **macOS / X11**: both position and size affected → `LowerToHigher` or `HigherToLower`
@@ -190,6 +194,7 @@ impl Platform {
         self,
         starting_scale: f64,
         target_scale: f64,
+        scale_factor_epsilon: f64,
     ) -> MonitorScaleStrategy {
         if !cfg!(feature = "workaround-winit-4440") {
             return MonitorScaleStrategy::ApplyUnchanged;
@@ -199,7 +204,7 @@ impl Platform {
             return MonitorScaleStrategy::ApplyUnchanged;
         }
 
-        if (starting_scale - target_scale).abs() < SCALE_FACTOR_EPSILON {
+        if (starting_scale - target_scale).abs() < scale_factor_epsilon {
             MonitorScaleStrategy::ApplyUnchanged
         } else if matches!(self, Self::Windows) {
             MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::NeedInitialMove)
@@ -220,8 +225,8 @@ impl Platform {
     ///   where the parent/launching window is.
     /// - **macOS / X11**: the primary window migrates to its own restore target during startup, so
     ///   a secondary window spawned alongside it is born on the primary's post-move monitor — a
-    ///   different scale than the primary's launch monitor that `restore_managed_window` sampled
-    ///   for `starting_scale`.
+    ///   different scale than the primary's launch monitor that `restore_saved_window` sampled for
+    ///   `starting_scale`.
     ///
     /// In all three cases the `starting_scale` assumption is wrong, so `restore_windows`
     /// re-reads the window's actual `base_scale_factor()` and recomputes the strategy.
@@ -0,0 +1,132 @@
+//! `GeometrySettled`: the frame after a restore ends in which the window's geometry no
+//! longer changes.
+
+use bevy::prelude::*;
+use bevy_kana::ToU32;
+
+use crate::WindowKey;
+use crate::events::GeometrySettled;
+use crate::persistence;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
+
+/// Physical position and size of a window, as compared between frames.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Geometry {
+    physical_position: Option<IVec2>,
+    physical_size:     UVec2,
+}
+
+impl Geometry {
+    fn of(winit_windows: &impl WinitWindowAccess, entity: Entity, window: &Window) -> Self {
+        Self {
+            physical_position: persistence::get_window_position(winit_windows, entity, window),
+            physical_size:     window.resolution.physical_size(),
+        }
+    }
+}
+
+/// Inserted when a restore ends; removed once `GeometrySettled` fires.
+#[derive(Component)]
+pub(crate) struct PendingGeometrySettled {
+    window_key:    WindowKey,
+    /// Geometry seen in the previous frame. `None` until the first frame after the
+    /// restore ended.
+    last_geometry: Option<Geometry>,
+}
+
+impl PendingGeometrySettled {
+    pub(crate) const fn new(window_key: WindowKey) -> Self {
+        Self {
+            window_key,
+            last_geometry: None,
+        }
+    }
+}
+
+/// Fire `GeometrySettled` for each window whose geometry matches the previous frame's.
+///
+/// Runs before the restore systems, so the first comparison happens the frame after the
+/// restore ended rather than in the same frame.
+pub(crate) fn emit_geometry_settled(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &Window, &mut PendingGeometrySettled)>,
+) {
+    for (entity, window, mut pending_geometry_settled) in &mut pending {
+        let geometry = Geometry::of(&LiveWinitWindows, entity, window);
+        if pending_geometry_settled.last_geometry != Some(geometry) {
+            pending_geometry_settled.last_geometry = Some(geometry);
+            continue;
+        }
+
+        let window_key = pending_geometry_settled.window_key.clone();
+        debug!(
+            "[emit_geometry_settled] [{window_key}] position={:?} size={}",
+            geometry.physical_position, geometry.physical_size
+        );
+        commands
+            .entity(entity)
+            .remove::<PendingGeometrySettled>()
+            .trigger(|entity| GeometrySettled {
+                entity,
+                window_key,
+                physical_position: geometry.physical_position,
+                physical_size: geometry.physical_size,
+                logical_size: UVec2::new(
+                    window.resolution.width().round().to_u32(),
+                    window.resolution.height().round().to_u32(),
+                ),
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::window::WindowResolution;
+
+    use super::*;
+
+    /// Physical sizes reported by the `GeometrySettled` events the test app saw.
+    #[derive(Resource, Default)]
+    struct Settled(Vec<UVec2>);
+
+    fn settled_sizes(app: &App) -> &[UVec2] { &app.world().resource::<Settled>().0 }
+
+    #[test]
+    fn settles_after_one_unchanged_frame_and_fires_once() {
+        let mut app = App::new();
+        app.init_resource::<Settled>()
+            .add_systems(Update, emit_geometry_settled)
+            .add_observer(|settled: On<GeometrySettled>, mut seen: ResMut<Settled>| {
+                seen.0.push(settled.physical_size);
+            });
+        let entity = app
+            .world_mut()
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(800, 600),
+                    ..default()
+                },
+                PendingGeometrySettled::new(WindowKey::Primary),
+            ))
+            .id();
+
+        // First frame only records the geometry.
+        app.update();
+        assert!(settled_sizes(&app).is_empty());
+
+        // A resize restarts the wait.
+        if let Some(mut window) = app.world_mut().get_mut::<Window>(entity) {
+            window.resolution.set_physical_resolution(1024, 768);
+        }
+        app.update();
+        assert!(settled_sizes(&app).is_empty());
+
+        app.update();
+        assert_eq!(settled_sizes(&app), [UVec2::new(1024, 768)]);
+        assert!(app.world().get::<PendingGeometrySettled>(entity).is_none());
+
+        app.update();
+        assert_eq!(settled_sizes(&app).len(), 1);
+    }
+}
@@ -1,28 +1,43 @@
 //! Window restore startup, target state, and settle verification.
 
+mod geometry_settled;
+mod phase;
 mod settle_state;
 mod target_position;
+mod trigger;
 mod winit_info;
 
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use geometry_settled::PendingGeometrySettled;
+use geometry_settled::emit_geometry_settled;
+pub use phase::RestorePhase;
+pub use phase::no_restore_in_progress;
+pub use phase::restore_in_progress;
+use phase::update_restore_phase;
 pub(crate) use settle_state::check_restore_settling;
 pub(crate) use target_position::FullscreenRestoreState;
-pub(crate) use target_position::MonitorResolutionSource;
 pub(crate) use target_position::MonitorScaleStrategy;
-#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
 pub(crate) use target_position::TargetPosition;
 pub(crate) use target_position::WindowRestoreState;
 pub(crate) use target_position::compute_target_position;
 pub(crate) use target_position::has_restoring_windows;
 pub(crate) use target_position::no_restoring_windows;
-pub(crate) use target_position::resolve_target_monitor_and_position;
+pub(crate) use target_position::resolve_restore_target;
 pub(crate) use target_position::restore_windows;
+pub(crate) use trigger::RestoreGate;
+pub(crate) use trigger::restore_triggered;
 pub(crate) use winit_info::WinitInfo;
 pub(crate) use winit_info::X11FrameCompensated;
+use winit_info::decoration_awaiting_resize;
 pub(crate) use winit_info::init_winit_info;
 pub(crate) use winit_info::load_target_position;
 pub(crate) use winit_info::move_to_target_monitor;
+use winit_info::primary_window_awaiting_restore;
+use winit_info::refresh_window_decoration;
+use winit_info::warn_without_primary_window;
 
+use crate::WindowManagerSet;
 use crate::monitors;
 
 pub(crate) struct RestorePlugin;
@@ -34,15 +49,40 @@ impl Plugin for RestorePlugin {
         // `load_target_position` and `move_to_target_monitor` — otherwise the
         // `TargetPosition` component inserted via deferred commands won't exist yet.
         // `move_to_target_monitor` self-guards on `platform.is_x11()`.
+        // Under `RestoreTrigger::Manual`, `trigger_restore` runs it instead.
         app.add_systems(
             PreStartup,
             (
                 init_winit_info,
                 load_target_position,
-                move_to_target_monitor,
+                move_to_target_monitor.run_if(restore_triggered),
             )
                 .chain()
-                .after(monitors::init_monitors),
+                .in_set(WindowManagerSet::Restore)
+                .after(monitors::init_monitors)
+                .run_if(any_with_component::<PrimaryWindow>),
+        );
+
+        app.add_systems(PostStartup, warn_without_primary_window);
+
+        // A primary window the app spawns after `PreStartup`, e.g. one tagged
+        // `RestoreTarget`, is restored once it appears and a monitor is connected.
+        // `init_winit_info` retries each frame until winit has created the window.
+        app.add_systems(
+            Update,
+            (
+                init_winit_info,
+                (
+                    load_target_position,
+                    move_to_target_monitor.run_if(restore_triggered),
+                )
+                    .chain()
+                    .run_if(resource_exists::<WinitInfo>),
+            )
+                .chain()
+                .in_set(WindowManagerSet::Restore)
+                .before(restore_windows)
+                .run_if(primary_window_awaiting_restore),
         );
 
         app.add_systems(
@@ -50,8 +90,29 @@ impl Plugin for RestorePlugin {
             (
                 restore_windows,
                 check_restore_settling.after(restore_windows),
+                update_restore_phase.after(check_restore_settling),
             )
-                .run_if(has_restoring_windows),
+                .in_set(WindowManagerSet::Restore)
+                .run_if(has_restoring_windows)
+                .run_if(restore_triggered),
         );
+
+        app.add_systems(
+            Update,
+            emit_geometry_settled
+                .in_set(WindowManagerSet::Restore)
+                .before(restore_windows)
+                .run_if(any_with_component::<PendingGeometrySettled>),
+        );
+
+        app.add_systems(
+            Update,
+            refresh_window_decoration
+                .in_set(WindowManagerSet::Restore)
+                .run_if(decoration_awaiting_resize),
+        );
+
+        app.register_type::<TargetPosition>()
+            .register_type::<RestorePhase>();
     }
 }
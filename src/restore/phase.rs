@@ -0,0 +1,119 @@
+//! Public view of where each window's multi-frame restore is, and run conditions on it.
+
+use bevy::prelude::*;
+
+use super::FullscreenRestoreState;
+use super::MonitorScaleStrategy;
+use super::TargetPosition;
+use super::WindowRestoreState;
+
+/// Where a window's restore is, for display or logging while it runs.
+///
+/// Inserted on every window the plugin restores (the primary window and
+/// [`ManagedWindow`](crate::ManagedWindow)s) and kept up to date each frame until the
+/// restore finishes with [`RestorePhase::Complete`]. Phases a window does not need, such
+/// as the scale change wait when the target monitor has the same scale, are skipped.
+///
+/// ```ignore
+/// fn log_restore_phase(query: Query<&RestorePhase, (With<PrimaryWindow>, Changed<RestorePhase>)>) {
+///     for phase in &query {
+///         info!("Restore phase: {phase:?}");
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum RestorePhase {
+    /// Waiting for the window to be created before anything is applied.
+    Pending,
+    /// Moving the window onto its target monitor before sizing it or changing its mode.
+    MovingToMonitor,
+    /// Moved across monitors with different scale factors, waiting for winit to report
+    /// the scale change.
+    WaitingForScaleChange,
+    /// Applying the final size now that the window is at the target monitor's scale.
+    ApplyingSize,
+    /// Waiting for the GPU surface before applying fullscreen (Windows, winit #3124).
+    WaitingForSurface,
+    /// Applying the fullscreen or maximized mode.
+    ApplyingMode,
+    /// Everything is applied; waiting for the window to report stable, matching state.
+    Settling,
+    /// The restore finished and `WindowRestored` or `WindowRestoreMismatch` fired.
+    Complete,
+}
+
+impl RestorePhase {
+    /// Phase of a restore still in progress, in the order `restore_windows` advances it.
+    pub(crate) const fn of(target_position: &TargetPosition) -> Self {
+        if target_position.settle_state.is_some() {
+            return Self::Settling;
+        }
+        match target_position.monitor_scale_strategy {
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::NeedInitialMove)
+            | MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::NeedInitialMove) => {
+                return Self::MovingToMonitor;
+            },
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange)
+            | MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::WaitingForScaleChange) =>
+            {
+                return Self::WaitingForScaleChange;
+            },
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize)
+            | MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::ApplySize) => {
+                return Self::ApplyingSize;
+            },
+            MonitorScaleStrategy::ApplyUnchanged | MonitorScaleStrategy::LowerToHigher => {},
+        }
+        match target_position.fullscreen_restore_state {
+            Some(FullscreenRestoreState::MoveToMonitor | FullscreenRestoreState::WaitForMove) => {
+                Self::MovingToMonitor
+            },
+            Some(FullscreenRestoreState::WaitForSurface) => Self::WaitingForSurface,
+            Some(FullscreenRestoreState::ApplyMode) => Self::ApplyingMode,
+            None => Self::Pending,
+        }
+    }
+}
+
+/// Run condition: `true` while any window's restore has not yet finished.
+///
+/// Becomes `true` as soon as the plugin starts restoring a window and stays `true` until
+/// every restoring window reaches [`RestorePhase::Complete`], so systems that would fight
+/// the restore (moving or resizing windows, saving layouts) can wait for it.
+///
+/// ```ignore
+/// app.add_systems(Update, arrange_tool_windows.run_if(not(restore_in_progress)));
+/// ```
+#[must_use]
+pub fn restore_in_progress(restore_phases: Query<&RestorePhase>) -> bool {
+    restore_phases
+        .iter()
+        .any(|restore_phase| *restore_phase != RestorePhase::Complete)
+}
+
+/// Run condition: `true` once no window has a restore in progress. The inverse of
+/// [`restore_in_progress`].
+#[must_use]
+pub fn no_restore_in_progress(restore_phases: Query<&RestorePhase>) -> bool {
+    restore_phases
+        .iter()
+        .all(|restore_phase| *restore_phase == RestorePhase::Complete)
+}
+
+/// Keep each restoring window's [`RestorePhase`] in step with its `TargetPosition`.
+///
+/// Runs after `check_restore_settling`, whose commands have already removed the
+/// `TargetPosition` of windows that finished and marked them `Complete`.
+pub(crate) fn update_restore_phase(
+    mut commands: Commands,
+    windows: Query<(Entity, &TargetPosition, Option<&RestorePhase>)>,
+) {
+    for (entity, target_position, existing) in &windows {
+        let restore_phase = RestorePhase::of(target_position);
+        if existing != Some(&restore_phase) {
+            debug!("[update_restore_phase] {entity:?}: {existing:?} -> {restore_phase:?}");
+            commands.entity(entity).insert(restore_phase);
+        }
+    }
+}
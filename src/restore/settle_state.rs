@@ -3,12 +3,17 @@
 //! After a window restore is applied, monitors the actual window state each frame
 //! to confirm the compositor delivered matching values (or detect mismatches).
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy::window::WindowMode;
 use bevy_kana::ToI32;
 use bevy_kana::ToU32;
 
+use super::geometry_settled::PendingGeometrySettled;
+use super::phase::RestorePhase;
 use super::target_position::TargetPosition;
 use super::winit_info::X11FrameCompensated;
 use crate::ManagedWindow;
@@ -21,6 +26,7 @@ use crate::constants::SETTLE_TIMEOUT_SECS;
 use crate::events::WindowRestoreMismatch;
 use crate::events::WindowRestored;
 use crate::monitors::CurrentMonitor;
+use crate::persistence::WindowStateCache;
 
 /// Tracks the two-timer settling state after restore completes.
 #[derive(Debug, Clone, Reflect)]
@@ -88,8 +94,8 @@ fn build_actual_snapshot(
 
 /// Check whether actual window state matches the target for settle purposes.
 ///
-/// Fullscreen modes skip position and size comparison — the window fills the
-/// monitor so the stored position/size are irrelevant. On macOS, borderless
+/// Fullscreen and maximized windows skip position and size comparison — the window
+/// fills the monitor so the stored position/size are irrelevant. On macOS, borderless
 /// fullscreen reports position offset by the menu bar height; on X11 (W6),
 /// frame vs client coords differ. The physical size can also differ when
 /// scales differ between backends (e.g. Wayland scale 1 vs `XWayland` scale 2).
@@ -102,18 +108,19 @@ fn check_settle_matches(
     settle_snapshot: &SettleSnapshot,
     platform: Platform,
 ) -> SettleComparison {
-    let is_fullscreen = target_position.saved_window_mode.is_fullscreen();
+    let fills_monitor = target_position.saved_window_mode.is_fullscreen()
+        || target_position.saved_window_mode.is_maximized();
     // Skip position comparison when:
-    // - fullscreen (window fills monitor; saved position is irrelevant)
+    // - fullscreen or maximized (window fills monitor; saved position is irrelevant)
     // - no saved position (window was anchored via `WindowPosition::Centered`; the resulting `At`
     //   position is OS-chosen and not part of the comparison)
     // - X11 W6 frame-vs-client coordinate mismatch
-    let skip_position = is_fullscreen
+    let skip_position = fills_monitor
         || target_physical_position.is_none()
         || !platform.position_reliable_for_settle();
     let position_matches =
         skip_position || target_physical_position == settle_snapshot.physical_position;
-    let size_match = is_fullscreen || target_physical_size == settle_snapshot.physical_size;
+    let size_match = fills_monitor || target_physical_size == settle_snapshot.physical_size;
     let mode_match = platform.modes_match(target_window_mode, settle_snapshot.window_mode);
     let monitor_match = target_monitor == settle_snapshot.monitor;
     SettleComparison {
@@ -234,13 +241,11 @@ pub(crate) fn check_restore_settling(
     primary_query: Query<(), With<PrimaryWindow>>,
     managed_query: Query<&ManagedWindow>,
     platform: Res<Platform>,
+    mut window_state_cache: ResMut<WindowStateCache>,
 ) {
     for (entity, mut target_position, window, current_monitor) in &mut windows {
-        let target_window_mode = target_position
-            .saved_window_mode
-            .to_window_mode(target_position.monitor_index);
+        let target_window_mode = target_position.window_mode();
         let target_physical_size = target_position.physical_size;
-        let target_logical_size = target_position.logical_size;
         let target_monitor = target_position.monitor_index;
         let expected_scale = target_position.target_scale;
 
@@ -312,16 +317,18 @@ pub(crate) fn check_restore_settling(
             physical_position: target_physical_position,
             logical_position:  target_logical_position,
             physical_size:     target_physical_size,
-            logical_size:      target_logical_size,
+            logical_size:      target_position.logical_size,
             window_mode:       target_window_mode,
             monitor:           target_monitor,
             scale:             expected_scale,
+            icon_path:         target_position.icon_path.clone(),
+            extra:             target_position.extra.clone(),
         };
         if stable && comparison.all_match() {
             emit_settle_success(
                 &mut commands,
                 entity,
-                window_key,
+                window_key.clone(),
                 &settle_target,
                 total_elapsed_ms,
                 stability_elapsed_ms,
@@ -330,12 +337,46 @@ pub(crate) fn check_restore_settling(
             emit_settle_mismatch(
                 &mut commands,
                 entity,
-                window_key,
+                window_key.clone(),
                 &settle_target,
                 &build_settle_actual(window, current_snapshot, actual_scale),
                 total_elapsed_ms,
             );
+        } else {
+            continue;
         }
+
+        window_state_cache.record_restored_size(window_key, settle_target.logical_size);
+        minimize_or_focus_if_requested(&mut commands, entity, &target_position);
+    }
+}
+
+/// Minimize a window saved while minimized, or focus one restored under
+/// `FocusOnRestore`, once its restored geometry has settled.
+///
+/// Waiting for the settle keeps the comparison on the restored geometry rather than the
+/// placeholder values the OS reports for a minimized window. Setting `Window::focused`
+/// makes `bevy_winit` call `focus_window`, which the OS may ignore where focus stealing
+/// is restricted.
+fn minimize_or_focus_if_requested(
+    commands: &mut Commands,
+    entity: Entity,
+    target_position: &TargetPosition,
+) {
+    if target_position.minimize_after_restore {
+        debug!("[minimize_or_focus_if_requested] Minimizing entity {entity:?} after restore");
+        commands.entity(entity).queue(|mut entity: EntityWorldMut| {
+            if let Some(mut window) = entity.get_mut::<Window>() {
+                window.set_minimized(true);
+            }
+        });
+    } else if target_position.focus_after_restore {
+        debug!("[minimize_or_focus_if_requested] Focusing entity {entity:?} after restore");
+        commands.entity(entity).queue(|mut entity: EntityWorldMut| {
+            if let Some(mut window) = entity.get_mut::<Window>() {
+                window.focused = true;
+            }
+        });
     }
 }
 
@@ -355,8 +396,8 @@ fn build_settle_actual(
         settle_snapshot,
         scale,
         logical_size: UVec2::new(
-            window.resolution.width().to_u32(),
-            window.resolution.height().to_u32(),
+            window.resolution.width().round().to_u32(),
+            window.resolution.height().round().to_u32(),
         ),
     }
 }
@@ -370,6 +411,8 @@ struct SettleTarget {
     window_mode:       WindowMode,
     monitor:           usize,
     scale:             f64,
+    icon_path:         Option<PathBuf>,
+    extra:             BTreeMap<String, String>,
 }
 
 /// Emit `WindowRestored` and clean up `TargetPosition` when settle succeeds.
@@ -385,6 +428,7 @@ fn emit_settle_success(
         "[check_restore_settling] [{window_key}] Settled after {total_elapsed_ms:.0}ms \
          (stable for {stability_elapsed_ms:.0}ms)"
     );
+    let pending_geometry_settled = PendingGeometrySettled::new(window_key.clone());
     commands
         .entity(entity)
         .trigger(|entity| WindowRestored {
@@ -396,9 +440,12 @@ fn emit_settle_success(
             physical_size: settle_target.physical_size,
             window_mode: settle_target.window_mode,
             monitor_index: settle_target.monitor,
+            icon_path: settle_target.icon_path.clone(),
+            extra: settle_target.extra.clone(),
         })
         .remove::<TargetPosition>()
-        .remove::<X11FrameCompensated>();
+        .remove::<X11FrameCompensated>()
+        .insert((RestorePhase::Complete, pending_geometry_settled));
 }
 
 /// Emit `WindowRestoreMismatch` and clean up `TargetPosition` when settle times out.
@@ -442,6 +489,7 @@ fn emit_settle_mismatch(
                     .to_i32(),
             )
         });
+    let pending_geometry_settled = PendingGeometrySettled::new(window_key.clone());
     commands
         .entity(entity)
         .trigger(|entity| WindowRestoreMismatch {
@@ -463,5 +511,6 @@ fn emit_settle_mismatch(
             actual_scale: settle_actual.scale,
         })
         .remove::<TargetPosition>()
-        .remove::<X11FrameCompensated>();
+        .remove::<X11FrameCompensated>()
+        .insert((RestorePhase::Complete, pending_geometry_settled));
 }
@@ -1,99 +1,49 @@
 use bevy::ecs::system::NonSendMarker;
 use bevy::prelude::*;
+use bevy::window::Monitor;
 use bevy::window::MonitorSelection;
+use bevy::window::VideoMode;
 use bevy::window::WindowMode;
 use bevy::window::WindowPosition;
-use bevy::window::WindowScaleFactorChanged;
 use bevy::winit::WINIT_WINDOWS;
-use bevy_kana::ToI32;
-use bevy_kana::ToU32;
+use bevy_kana::ToF32;
 
+use super::plan;
+use super::plan::PlannedGeometry;
+use super::plan::WindowedRestoreStep;
 use super::strategy::FullscreenRestoreState;
 use super::strategy::MonitorScaleStrategy;
 use super::strategy::WindowRestoreState;
 use super::target::TargetPosition;
 use crate::Platform;
 use crate::constants::MILLIS_PER_SECOND;
-use crate::constants::RESTORE_STRATEGY_APPLY_UNCHANGED;
-use crate::constants::RESTORE_STRATEGY_LOWER_TO_HIGHER;
-use crate::constants::SCALE_FACTOR_EPSILON;
 use crate::constants::SETTLE_STABILITY_SECS;
 use crate::constants::SETTLE_TIMEOUT_SECS;
+use crate::macos_spaces;
+use crate::monitors::Monitors;
+use crate::opacity;
+use crate::persistence::SavedWindowAttributes;
 use crate::persistence::SavedWindowMode;
 use crate::restore::settle_state::SettleState;
 use crate::restore::winit_info::X11FrameCompensated;
+use crate::restore_window_config::GeometryRestore;
+use crate::restore_window_config::RestoreExecution;
+use crate::restore_window_config::RestoreWindowConfig;
+use crate::restore_window_config::TitleRestore;
+use crate::scale_factor_changes::ScaleFactorChanges;
+use crate::virtual_desktop;
 
 /// Apply the initial window move to the target monitor.
 fn apply_initial_move(target_position: &TargetPosition, window: &mut Window) {
-    if target_position.saved_window_mode.is_fullscreen() {
-        if let Some(physical_position) = target_position.physical_position {
-            debug!(
-                "[apply_initial_move] Moving to target position {:?} for fullscreen mode {:?}",
-                physical_position, target_position.saved_window_mode
-            );
-            window.position = WindowPosition::At(physical_position);
-        } else {
-            debug!(
-                "[apply_initial_move] No saved position, fullscreen mode {:?} targets monitor {} via WindowMode",
-                target_position.saved_window_mode, target_position.monitor_index
-            );
-        }
-        return;
-    }
-
-    let Some(physical_position) = target_position.physical_position else {
-        debug!(
-            "[apply_initial_move] No saved position, centering on monitor {}",
-            target_position.monitor_index
-        );
-        window.position =
-            WindowPosition::Centered(MonitorSelection::Index(target_position.monitor_index));
-        return;
-    };
-
-    // HigherToLower (macOS/X11 high→low) compensates position by ×ratio
-    // (= starting_scale / target_scale, e.g. ×2 for 2x→1x): `set_outer_position` is
-    // applied at the starting monitor's scale, so crossing to the half-scale target
-    // halves the physical position unless pre-multiplied. Size stays a placeholder —
-    // the WaitingForScaleChange → ApplySize phase re-applies the full physical size
-    // after the scale change settles.
-    let (physical_move_position, physical_move_size) = match target_position.monitor_scale_strategy
-    {
-        MonitorScaleStrategy::HigherToLower(_) => {
-            let ratio = target_position.ratio();
-            let physical_compensated_x = (f64::from(physical_position.x) * ratio).to_i32();
-            let physical_compensated_y = (f64::from(physical_position.y) * ratio).to_i32();
-            debug!(
-                "[apply_initial_move] HigherToLower: compensating position {physical_position:?} -> ({physical_compensated_x}, {physical_compensated_y}) (ratio={ratio})",
-            );
-            (
-                IVec2::new(physical_compensated_x, physical_compensated_y),
-                target_position.physical_size,
-            )
-        },
-        MonitorScaleStrategy::CompensateSizeOnly(_) => {
-            let physical_compensated_size = target_position.compensated_size();
-            debug!(
-                "[apply_initial_move] CompensateSizeOnly: position={:?} compensated_size={}x{} (ratio={})",
-                physical_position,
-                physical_compensated_size.x,
-                physical_compensated_size.y,
-                target_position.ratio()
-            );
-            (physical_position, physical_compensated_size)
-        },
-        _ => (physical_position, target_position.physical_size),
-    };
-
+    let geometry = plan::initial_move_geometry(target_position);
     debug!(
-        "[apply_initial_move] position={physical_move_position:?} size={}x{} visible={}",
-        physical_move_size.x, physical_move_size.y, window.visible
+        "[apply_initial_move] mode={:?} monitor_scale_strategy={:?} ratio={} -> {geometry:?} visible={}",
+        target_position.saved_window_mode,
+        target_position.monitor_scale_strategy,
+        target_position.ratio(),
+        window.visible
     );
-
-    window.position = WindowPosition::At(physical_move_position);
-    window
-        .resolution
-        .set_physical_resolution(physical_move_size.x, physical_move_size.y);
+    apply_planned_geometry(window, geometry);
 }
 
 /// Handle the initial move for cross-DPI strategies.
@@ -111,31 +61,15 @@ fn apply_initial_move(target_position: &TargetPosition, window: &mut Window) {
 /// the resulting state.
 fn begin_cross_dpi_restore(target_position: &mut TargetPosition, window: &mut Window) {
     if target_position.physical_position.is_none() {
-        // Size at `starting_scale`: `set_physical_resolution` is interpreted at the
-        // window's current scale factor, which is `starting_scale` until the move
-        // completes. Storing logical = `starting_size / starting_scale = logical_size`
-        // means the post-move physical size resolves to `logical_size * target_scale`,
-        // matching `target.physical_size` for settle.
-        let physical_width =
-            (f64::from(target_position.logical_size.x) * target_position.starting_scale).to_u32();
-        let physical_height =
-            (f64::from(target_position.logical_size.y) * target_position.starting_scale).to_u32();
+        let geometry = plan::cross_dpi_centered_geometry(target_position);
         debug!(
-            "[begin_cross_dpi_restore] no saved position, centering on monitor {} at \
-             starting_scale={} (physical {}x{} → logical {}x{} after move to target_scale={})",
-            target_position.monitor_index,
+            "[begin_cross_dpi_restore] no saved position, starting_scale={} target_scale={} logical={}x{} -> {geometry:?}",
             target_position.starting_scale,
-            physical_width,
-            physical_height,
+            target_position.target_scale,
             target_position.logical_size.x,
             target_position.logical_size.y,
-            target_position.target_scale
         );
-        window.position =
-            WindowPosition::Centered(MonitorSelection::Index(target_position.monitor_index));
-        window
-            .resolution
-            .set_physical_resolution(physical_width, physical_height);
+        apply_planned_geometry(window, geometry);
         window.visible = true;
         target_position.settle_state = Some(SettleState::new());
         return;
@@ -150,14 +84,92 @@ fn begin_cross_dpi_restore(target_position: &mut TargetPosition, window: &mut Wi
     };
 }
 
+/// Log the restore that would be applied, for `RestoreExecution::DryRun`.
+///
+/// Covers every step the strategy would take, since without the initial move a cross-DPI
+/// strategy never sees the scale change it waits for.
+fn log_dry_run(entity: Entity, target_position: &TargetPosition) {
+    info!(
+        "[restore_windows] Dry run for entity {entity:?}: mode={:?} monitor={} starting_scale={} target_scale={} monitor_scale_strategy={:?} physical_position={:?} physical_size={} logical_size={} attributes={:?} opacity={:?}",
+        target_position.saved_window_mode,
+        target_position.monitor_index,
+        target_position.starting_scale,
+        target_position.target_scale,
+        target_position.monitor_scale_strategy,
+        target_position.physical_position,
+        target_position.physical_size,
+        target_position.logical_size,
+        target_position.window_attributes,
+        target_position.opacity,
+    );
+    if target_position.saved_window_mode.is_fullscreen()
+        || target_position.saved_window_mode.is_maximized()
+    {
+        return;
+    }
+    match target_position.monitor_scale_strategy {
+        MonitorScaleStrategy::HigherToLower(_) | MonitorScaleStrategy::CompensateSizeOnly(_) => {
+            let geometry = if target_position.physical_position.is_some() {
+                plan::initial_move_geometry(target_position)
+            } else {
+                plan::cross_dpi_centered_geometry(target_position)
+            };
+            info!(
+                "[restore_windows] Dry run for entity {entity:?}: initial move (ratio={}) -> {geometry:?}",
+                target_position.ratio()
+            );
+        },
+        MonitorScaleStrategy::ApplyUnchanged | MonitorScaleStrategy::LowerToHigher => {
+            info!(
+                "[restore_windows] Dry run for entity {entity:?}: {:?}",
+                plan::windowed_restore_step(target_position)
+            );
+        },
+    }
+}
+
+/// Re-derive the scale strategy when the window was created at a different scale than
+/// `TargetPosition` assumed.
+fn correct_starting_scale(
+    entity: Entity,
+    target_position: &mut TargetPosition,
+    window: &Window,
+    platform: Platform,
+    scale_factor_epsilon: f64,
+) {
+    let actual_scale = f64::from(window.resolution.base_scale_factor());
+    if (actual_scale - target_position.starting_scale).abs() <= scale_factor_epsilon {
+        return;
+    }
+    let old_monitor_scale_strategy = target_position.monitor_scale_strategy;
+    target_position.starting_scale = actual_scale;
+    target_position.monitor_scale_strategy = platform.scale_strategy(
+        actual_scale,
+        target_position.target_scale,
+        scale_factor_epsilon,
+    );
+    debug!(
+        "[restore_windows] Corrected starting_scale for entity {entity:?}: \
+         monitor_scale_strategy: {old_monitor_scale_strategy:?} -> {:?} \
+         (actual_scale={actual_scale:.2})",
+        target_position.monitor_scale_strategy
+    );
+}
+
 /// Apply pending window restore. Runs only when entities with `TargetPosition` exist.
+///
+/// Under `RestoreExecution::DryRun` the restore is only logged, and the window is shown
+/// and settled where it is.
 pub(crate) fn restore_windows(
-    mut scale_changed_messages: MessageReader<WindowScaleFactorChanged>,
+    scale_factor_changes: Res<ScaleFactorChanges>,
     mut windows: Query<(Entity, &mut TargetPosition, &mut Window), With<X11FrameCompensated>>,
     _: NonSendMarker,
     platform: Res<Platform>,
+    monitors: Res<Monitors>,
+    bevy_monitors: Query<&Monitor>,
+    restore_window_config: Res<RestoreWindowConfig>,
 ) {
-    let scale_changed = scale_changed_messages.read().last().is_some();
+    let scale_changed = scale_factor_changes.any();
 
     for (entity, mut target_position, mut window) in &mut windows {
         if target_position.settle_state.is_some() {
@@ -171,21 +183,52 @@ pub(crate) fn restore_windows(
             continue;
         }
 
-        if platform.needs_managed_scale_fixup() {
-            let actual_scale = f64::from(window.resolution.base_scale_factor());
-            if (actual_scale - target_position.starting_scale).abs() > SCALE_FACTOR_EPSILON {
-                let old_monitor_scale_strategy = target_position.monitor_scale_strategy;
-                target_position.starting_scale = actual_scale;
-                target_position.monitor_scale_strategy =
-                    platform.scale_strategy(actual_scale, target_position.target_scale);
-                debug!(
-                    "[restore_windows] Corrected starting_scale for entity {entity:?}: \
-                     monitor_scale_strategy: {old_monitor_scale_strategy:?} -> {:?} \
-                     (actual_scale={actual_scale:.2})",
-                    target_position.monitor_scale_strategy
-                );
-            }
+        if restore_window_config.restore_execution == RestoreExecution::DryRun {
+            log_dry_run(entity, &target_position);
+            target_position.minimize_after_restore = false;
+            target_position.focus_after_restore = false;
+            target_position.opacity = None;
+            target_position.virtual_desktop = None;
+            target_position.space_behavior = None;
+            window.visible = true;
+            target_position.settle_state = Some(SettleState::new());
+            continue;
+        }
+
+        if let Some(opacity) = target_position.opacity.take() {
+            let applied = opacity::set_window_opacity(entity, opacity);
+            debug!("[restore_windows] Applied opacity {opacity} to entity {entity:?}: {applied}");
+        }
+        if let Some(desktop_id) = target_position.virtual_desktop.take() {
+            let moved = virtual_desktop::move_to_virtual_desktop(entity, &desktop_id);
+            debug!(
+                "[restore_windows] Moved entity {entity:?} to virtual desktop {desktop_id}: {moved}"
+            );
+        }
+        if let Some(space_behavior) = target_position.space_behavior.take() {
+            let applied = macos_spaces::set_space_behavior(entity, space_behavior);
+            debug!(
+                "[restore_windows] Set Spaces behavior {space_behavior:?} on entity {entity:?}: {applied}"
+            );
+        }
+
+        // Before any geometry: the target outer size was computed for the saved
+        // decorations, so they must be in place when the window is first moved or resized.
+        if SavedWindowAttributes::from_window(&window) != target_position.window_attributes {
+            target_position.window_attributes.apply_to(&mut window);
+        }
+
+        if platform.needs_managed_scale_fixup() && !target_position.saved_window_mode.is_maximized()
+        {
+            correct_starting_scale(
+                entity,
+                &mut target_position,
+                &window,
+                *platform,
+                restore_window_config.scale_factor_epsilon,
+            );
         }
+        keep_target_on_screen(entity, &mut target_position, &monitors);
 
         if matches!(
             target_position.monitor_scale_strategy,
@@ -196,55 +239,28 @@ pub(crate) fn restore_windows(
             continue;
         }
 
-        match target_position.monitor_scale_strategy {
-            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange)
-                if scale_changed =>
-            {
-                debug!(
-                    "[Restore] ScaleChanged received, transitioning to WindowRestoreState::ApplySize"
-                );
-                target_position.monitor_scale_strategy =
-                    MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize);
-            },
-            MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::WaitingForScaleChange) => {
-                debug!(
-                    "[Restore] CompensateSizeOnly: transitioning to ApplySize (scale_changed={scale_changed})"
-                );
-                target_position.monitor_scale_strategy =
-                    MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::ApplySize);
-            },
-            _ => {},
+        if scale_changed {
+            debug!(
+                "[restore_windows] ScaleChanged this frame, entity {entity:?} scale={:?}",
+                scale_factor_changes.for_window(entity)
+            );
         }
+        target_position.advance_scale_change_wait(scale_changed);
 
-        if let Some(fullscreen_restore_state) = target_position.fullscreen_restore_state {
-            match fullscreen_restore_state {
-                FullscreenRestoreState::MoveToMonitor => {
-                    if let Some(position) = target_position.physical_position {
-                        debug!("[restore_windows] Fullscreen MoveToMonitor: position={position:?}");
-                        window.position = WindowPosition::At(position);
-                    }
-                    target_position.fullscreen_restore_state =
-                        Some(FullscreenRestoreState::WaitForMove);
-                    continue;
-                },
-                FullscreenRestoreState::WaitForMove => {
-                    debug!("[restore_windows] Fullscreen WaitForMove: waiting for compositor");
-                    target_position.fullscreen_restore_state =
-                        Some(FullscreenRestoreState::ApplyMode);
-                    continue;
-                },
-                FullscreenRestoreState::WaitForSurface => {
-                    debug!("[restore_windows] Fullscreen WaitForSurface: waiting for GPU surface");
-                    target_position.fullscreen_restore_state =
-                        Some(FullscreenRestoreState::ApplyMode);
-                    continue;
-                },
-                FullscreenRestoreState::ApplyMode => {},
-            }
+        if !advance_fullscreen_restore(&mut target_position, &mut window) {
+            continue;
         }
 
+        let video_modes =
+            monitor_video_modes(&monitors, &bevy_monitors, target_position.monitor_index);
         if matches!(
-            try_apply_restore(&target_position, &mut window, *platform),
+            try_apply_restore(
+                &mut target_position,
+                &mut window,
+                *platform,
+                restore_window_config.title_restore,
+                video_modes,
+            ),
             RestoreStatus::Complete
         ) && target_position.settle_state.is_none()
         {
@@ -257,58 +273,117 @@ pub(crate) fn restore_windows(
     }
 }
 
+/// Step a fullscreen (or sequenced maximized) restore through the frames before its mode
+/// is applied. Returns whether the mode can be applied this frame.
+fn advance_fullscreen_restore(target_position: &mut TargetPosition, window: &mut Window) -> bool {
+    let Some(fullscreen_restore_state) = target_position.fullscreen_restore_state else {
+        return true;
+    };
+    match fullscreen_restore_state {
+        FullscreenRestoreState::MoveToMonitor => {
+            if target_position.saved_window_mode.is_maximized() {
+                if target_position.geometry_restore != GeometryRestore::SizeOnly {
+                    debug!(
+                        "[advance_fullscreen_restore] Maximized MoveToMonitor: centering on monitor {}",
+                        target_position.monitor_index
+                    );
+                    window.position = WindowPosition::Centered(MonitorSelection::Index(
+                        target_position.monitor_index,
+                    ));
+                }
+            } else if let Some(position) = target_position.physical_position {
+                debug!(
+                    "[advance_fullscreen_restore] Fullscreen MoveToMonitor: position={position:?}"
+                );
+                window.position = WindowPosition::At(position);
+            }
+            target_position.fullscreen_restore_state = Some(FullscreenRestoreState::WaitForMove);
+            false
+        },
+        FullscreenRestoreState::WaitForMove => {
+            debug!("[advance_fullscreen_restore] Fullscreen WaitForMove: waiting for compositor");
+            target_position.fullscreen_restore_state = Some(FullscreenRestoreState::ApplyMode);
+            false
+        },
+        FullscreenRestoreState::WaitForSurface => {
+            debug!(
+                "[advance_fullscreen_restore] Fullscreen WaitForSurface: waiting for GPU surface"
+            );
+            target_position.fullscreen_restore_state = Some(FullscreenRestoreState::ApplyMode);
+            false
+        },
+        FullscreenRestoreState::ApplyMode => true,
+    }
+}
+
 enum RestoreStatus {
     Complete,
     Waiting,
 }
 
-fn apply_window_geometry(
-    window: &mut Window,
-    physical_position: Option<IVec2>,
-    physical_size: UVec2,
-    strategy: &str,
-    ratio: Option<f64>,
-    monitor_index: usize,
+/// Apply planned geometry, leaving unplanned parts of the window unchanged.
+/// Final guard before any geometry is applied: move a target that would land entirely off
+/// screen, whether from a corrupt state file or a compensation bug, onto the closest monitor.
+fn keep_target_on_screen(
+    entity: Entity,
+    target_position: &mut TargetPosition,
+    monitors: &Monitors,
 ) {
-    if let Some(physical_position) = physical_position {
-        if let Some(ratio) = ratio {
-            debug!(
-                "[try_apply_restore] position={:?} size={}x{} ({strategy}, ratio={ratio})",
-                physical_position, physical_size.x, physical_size.y
-            );
-        } else {
-            debug!(
-                "[try_apply_restore] position={:?} size={}x{} ({strategy})",
-                physical_position, physical_size.x, physical_size.y
-            );
-        }
-        window.position = WindowPosition::At(physical_position);
-    } else {
-        if let Some(ratio) = ratio {
-            debug!(
-                "[try_apply_restore] size={}x{} centered on monitor {monitor_index} ({strategy}, ratio={ratio}, no saved position)",
-                physical_size.x, physical_size.y
-            );
-        } else {
-            debug!(
-                "[try_apply_restore] size={}x{} centered on monitor {monitor_index} ({strategy}, no saved position)",
-                physical_size.x, physical_size.y
-            );
-        }
-        window.position = WindowPosition::Centered(MonitorSelection::Index(monitor_index));
+    let Some(physical_position) = target_position.physical_position else {
+        return;
+    };
+    if let Some(physical_visible_position) =
+        plan::on_screen_position(physical_position, target_position.physical_size, monitors)
+    {
+        warn!(
+            "[keep_target_on_screen] Target position {physical_position} of entity {entity:?} is off every monitor, moving it to {physical_visible_position}"
+        );
+        target_position.physical_position = Some(physical_visible_position);
+    }
+}
+
+fn apply_planned_geometry(window: &mut Window, geometry: PlannedGeometry) {
+    if let Some(position) = geometry.position {
+        window.position = position.to_window_position();
     }
-    window
-        .resolution
-        .set_physical_resolution(physical_size.x, physical_size.y);
+    if let Some(physical_size) = geometry.physical_size {
+        window
+            .resolution
+            .set_physical_resolution(physical_size.x, physical_size.y);
+    }
+}
+
+/// Video modes of the monitor at `monitor_index` in `Monitors`, read from its Bevy
+/// `Monitor` entity.
+fn monitor_video_modes<'a>(
+    monitors: &Monitors,
+    bevy_monitors: &'a Query<&Monitor>,
+    monitor_index: usize,
+) -> &'a [VideoMode] {
+    monitors
+        .by_index(monitor_index)
+        .and_then(|monitor_info| bevy_monitors.get(monitor_info.entity).ok())
+        .map_or(&[], |monitor| monitor.video_modes.as_slice())
 }
 
 fn apply_fullscreen_restore(
-    target_position: &TargetPosition,
+    target_position: &mut TargetPosition,
     window: &mut Window,
     platform: Platform,
+    video_modes: &[VideoMode],
 ) {
     let monitor_index = target_position.monitor_index;
 
+    // Updating the target keeps the settle check comparing against the mode applied.
+    if let Some((saved_video_mode, substituted_video_mode)) = target_position
+        .saved_window_mode
+        .substitute_unavailable_video_mode(video_modes)
+    {
+        warn!(
+            "[apply_fullscreen_restore] Saved video mode {saved_video_mode:?} is not available on monitor {monitor_index}, using closest match {substituted_video_mode:?}"
+        );
+    }
+
     let window_mode = if platform.exclusive_fullscreen_fallback()
         && matches!(
             target_position.saved_window_mode,
@@ -317,11 +392,9 @@ fn apply_fullscreen_restore(
         warn!(
             "Exclusive fullscreen is not supported on Wayland, restoring as BorderlessFullscreen"
         );
-        WindowMode::BorderlessFullscreen(MonitorSelection::Index(monitor_index))
+        WindowMode::BorderlessFullscreen(target_position.monitor_selection())
     } else {
-        target_position
-            .saved_window_mode
-            .to_window_mode(monitor_index)
+        target_position.window_mode()
     };
 
     debug!(
@@ -337,10 +410,18 @@ fn apply_fullscreen_restore(
 }
 
 fn try_apply_restore(
-    target_position: &TargetPosition,
+    target_position: &mut TargetPosition,
     window: &mut Window,
     platform: Platform,
+    title_restore: TitleRestore,
+    video_modes: &[VideoMode],
 ) -> RestoreStatus {
+    if title_restore == TitleRestore::Restore
+        && let Some(title) = &target_position.title
+    {
+        window.title.clone_from(title);
+    }
+
     if target_position.saved_window_mode.is_fullscreen() {
         debug!(
             "[try_apply_restore] fullscreen: mode={:?} target_monitor={} current_physical={}x{} current_mode={:?} current_position={:?}",
@@ -351,7 +432,36 @@ fn try_apply_restore(
             window.mode,
             window.position,
         );
-        apply_fullscreen_restore(target_position, window, platform);
+        apply_fullscreen_restore(target_position, window, platform, video_modes);
+        window.visible = true;
+        return RestoreStatus::Complete;
+    }
+
+    if target_position.saved_window_mode.is_maximized() {
+        // Center on the target monitor first so the OS maximizes onto it; bevy applies
+        // the position change before the maximize request within the same frame.
+        debug!(
+            "[try_apply_restore] maximized: centering on monitor {} then maximizing",
+            target_position.monitor_index
+        );
+        // Under `ModeSequencing::SeparateFrames` the window was centered frames ago.
+        if target_position.geometry_restore != GeometryRestore::SizeOnly
+            && target_position.fullscreen_restore_state.is_none()
+        {
+            window.position =
+                WindowPosition::Centered(MonitorSelection::Index(target_position.monitor_index));
+        }
+        // Size the window to its saved windowed geometry first so unmaximizing returns
+        // to it rather than to the size it was created with.
+        if target_position.geometry_restore != GeometryRestore::PositionOnly
+            && let Some(logical_restore_size) = target_position.logical_restore_size
+        {
+            window.resolution.set(
+                logical_restore_size.x.to_f32(),
+                logical_restore_size.y.to_f32(),
+            );
+        }
+        window.set_maximized(true);
         window.visible = true;
         return RestoreStatus::Complete;
     }
@@ -363,65 +473,20 @@ fn try_apply_restore(
         target_position.monitor_scale_strategy
     );
 
-    match target_position.monitor_scale_strategy {
-        MonitorScaleStrategy::ApplyUnchanged => {
-            apply_window_geometry(
-                window,
-                target_position.physical_position,
-                target_position.physical_size,
-                RESTORE_STRATEGY_APPLY_UNCHANGED,
-                None,
-                target_position.monitor_index,
-            );
-        },
-        MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::ApplySize) => {
+    match plan::windowed_restore_step(target_position) {
+        WindowedRestoreStep::Apply(geometry) => {
             debug!(
-                "[try_apply_restore] size={}x{} ONLY (CompensateSizeOnly::ApplySize, position already set)",
-                target_position.physical_size.x, target_position.physical_size.y
-            );
-            window.resolution.set_physical_resolution(
-                target_position.physical_size.x,
-                target_position.physical_size.y,
-            );
-        },
-        MonitorScaleStrategy::CompensateSizeOnly(
-            WindowRestoreState::NeedInitialMove | WindowRestoreState::WaitingForScaleChange,
-        ) => {
-            debug!(
-                "[Restore] CompensateSizeOnly: waiting for initial move or ScaleChanged message"
-            );
-            return RestoreStatus::Waiting;
-        },
-        MonitorScaleStrategy::LowerToHigher => {
-            // Position still needs ratio compensation: on a low→high cross-scale
-            // move, `set_outer_position` is applied at the starting monitor's scale,
-            // so the move doubles it. Size must NOT be compensated: as of bevy 0.19,
-            // `request_inner_size` resolves at the target monitor's scale, so the
-            // `request_inner_size` produces the requested full physical size
-            // (compensating it would halve it).
-            apply_window_geometry(
-                window,
-                target_position.compensated_position(),
-                target_position.physical_size,
-                RESTORE_STRATEGY_LOWER_TO_HIGHER,
-                Some(target_position.ratio()),
-                target_position.monitor_index,
+                "[try_apply_restore] {:?} (ratio={}) -> {geometry:?}",
+                target_position.monitor_scale_strategy,
+                target_position.ratio()
             );
+            apply_planned_geometry(window, geometry);
         },
-        MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize) => {
+        WindowedRestoreStep::Wait => {
             debug!(
-                "[try_apply_restore] size={}x{} ONLY (HigherToLower::ApplySize, position already set)",
-                target_position.physical_size.x, target_position.physical_size.y
+                "[Restore] {:?}: waiting for initial move or ScaleChanged message",
+                target_position.monitor_scale_strategy
             );
-            window.resolution.set_physical_resolution(
-                target_position.physical_size.x,
-                target_position.physical_size.y,
-            );
-        },
-        MonitorScaleStrategy::HigherToLower(
-            WindowRestoreState::NeedInitialMove | WindowRestoreState::WaitingForScaleChange,
-        ) => {
-            debug!("[Restore] HigherToLower: waiting for initial move or ScaleChanged message");
             return RestoreStatus::Waiting;
         },
     }
@@ -2,13 +2,14 @@
 
 mod application;
 mod monitor;
+mod plan;
 mod run_conditions;
 mod strategy;
 mod target;
 
 pub(crate) use application::restore_windows;
-pub(crate) use monitor::MonitorResolutionSource;
-pub(crate) use monitor::resolve_target_monitor_and_position;
+pub(crate) use monitor::default_placement_state;
+pub(crate) use monitor::resolve_restore_target;
 pub(crate) use run_conditions::has_restoring_windows;
 pub(crate) use run_conditions::no_restoring_windows;
 pub(crate) use strategy::FullscreenRestoreState;
@@ -1,9 +1,36 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy_kana::ToI32;
+use bevy_kana::ToU32;
+
+use crate::constants::FULLY_OPAQUE;
+use crate::cursor;
 use crate::monitors::MonitorInfo;
 use crate::monitors::Monitors;
+use crate::persistence::SavedWindowAttributes;
+use crate::persistence::SavedWindowMode;
+use crate::persistence::WindowState;
+use crate::platform::Platform;
+use crate::restore_window_config::DefaultPlacement;
+use crate::restore_window_config::GeometryMemory;
+use crate::restore_window_config::GeometryRestore;
+use crate::restore_window_config::MonitorFallback;
+use crate::restore_window_config::RestoreWindowConfig;
+use crate::restore_window_config::SizePolicy;
 
 pub(crate) enum MonitorResolutionSource {
+    /// The monitor under the cursor (`RestorePreference::CursorMonitor`).
+    CursorMonitor,
+    /// The monitor of the window named by a `RestoreRelativeTo`.
+    ReferenceWindow,
+    /// Matched the saved monitor name.
+    MatchedByName,
+    /// Matched the saved monitor index.
     Requested,
     FallbackToPrimary,
+    FallbackToClosest,
 }
 
 pub struct ResolvedMonitor<'a> {
@@ -12,23 +39,808 @@ pub struct ResolvedMonitor<'a> {
     pub monitor_resolution_source: MonitorResolutionSource,
 }
 
+impl ResolvedMonitor<'_> {
+    /// Whether the saved monitor was missing and `MonitorFallback` picked this one.
+    pub(crate) const fn is_fallback(&self) -> bool {
+        matches!(
+            self.monitor_resolution_source,
+            MonitorResolutionSource::FallbackToPrimary | MonitorResolutionSource::FallbackToClosest
+        )
+    }
+}
+
 /// Resolve the target monitor from saved state and return an adjusted saved position.
+///
+/// A saved monitor name wins over the saved index, since indices shift when displays are
+/// added, removed, or rearranged. The index is only used when no monitor has that name.
+///
+/// On a matched monitor the position is rebuilt from the monitor's current origin plus the
+/// saved offset from it, so a window follows its monitor when the display arrangement
+/// changes. States saved without an offset use the absolute saved position.
+///
+/// When neither matches, `monitor_fallback` decides the outcome. Returns `None` for
+/// [`MonitorFallback::DoNothing`], meaning the window should not be restored.
+///
+/// `preferred_monitor` overrides all of the above: the monitor under the cursor when
+/// [`RestorePreference::CursorMonitor`](crate::RestorePreference::CursorMonitor) is set, or
+/// the monitor of a [`RestoreRelativeTo`](crate::RestoreRelativeTo) reference window. The
+/// window keeps its saved offset from its monitor's top-left corner there.
 #[must_use]
-pub(crate) fn resolve_target_monitor_and_position(
-    saved_monitor_index: usize,
-    logical_saved_position: Option<(i32, i32)>,
-    monitors: &Monitors,
-) -> ResolvedMonitor<'_> {
-    monitors.by_index(saved_monitor_index).map_or_else(
-        || ResolvedMonitor {
-            monitor_info:              monitors.first(),
-            logical_position:          None,
-            monitor_resolution_source: MonitorResolutionSource::FallbackToPrimary,
-        },
-        |monitor_info| ResolvedMonitor {
+pub(crate) fn resolve_target_monitor_and_position<'a>(
+    saved_window_state: &WindowState,
+    monitors: &'a Monitors,
+    monitor_fallback: MonitorFallback,
+    preferred_monitor: Option<&'a MonitorInfo>,
+) -> Option<ResolvedMonitor<'a>> {
+    if let Some(monitor_info) = preferred_monitor {
+        // Keep `None` when no position was saved (Wayland cannot position windows).
+        let logical_position = saved_window_state.logical_position.map(|_| {
+            saved_logical_monitor_offset(saved_window_state, monitors).map_or_else(
+                || {
+                    centered_logical_position(
+                        monitor_info,
+                        saved_window_state.logical_width,
+                        saved_window_state.logical_height,
+                    )
+                },
+                |logical_offset| offset_from_logical_origin(monitor_info, logical_offset),
+            )
+        });
+        return Some(ResolvedMonitor {
+            monitor_info,
+            logical_position,
+            monitor_resolution_source: MonitorResolutionSource::CursorMonitor,
+        });
+    }
+
+    if let Some(monitor_info) = monitor_matched_by_name(saved_window_state, monitors) {
+        return Some(ResolvedMonitor {
+            monitor_info,
+            logical_position: matched_logical_position(saved_window_state, monitor_info),
+            monitor_resolution_source: MonitorResolutionSource::MatchedByName,
+        });
+    }
+
+    if let Some(monitor_info) = monitors.by_index(saved_window_state.monitor) {
+        return Some(ResolvedMonitor {
             monitor_info,
-            logical_position: logical_saved_position,
+            logical_position: matched_logical_position(saved_window_state, monitor_info),
             monitor_resolution_source: MonitorResolutionSource::Requested,
+        });
+    }
+
+    let (monitor_info, monitor_resolution_source) = match monitor_fallback {
+        MonitorFallback::DoNothing => return None,
+        MonitorFallback::FallbackToPrimary => (
+            monitors.primary(),
+            MonitorResolutionSource::FallbackToPrimary,
+        ),
+        MonitorFallback::FallbackToClosest => {
+            let closest = saved_window_state.logical_position.map_or_else(
+                || monitors.primary(),
+                |(logical_x, logical_y)| {
+                    // Saved position is in the saved monitor's logical space.
+                    let physical_x = (f64::from(logical_x) * saved_window_state.scale)
+                        .round()
+                        .to_i32();
+                    let physical_y = (f64::from(logical_y) * saved_window_state.scale)
+                        .round()
+                        .to_i32();
+                    monitors.closest_to(physical_x, physical_y)
+                },
+            );
+            (closest, MonitorResolutionSource::FallbackToClosest)
+        },
+    };
+
+    // The saved position belongs to a monitor that is gone, so center on the fallback
+    // instead. Keep `None` when no position was saved (Wayland cannot position windows).
+    let logical_position = saved_window_state.logical_position.map(|_| {
+        centered_logical_position(
+            monitor_info,
+            saved_window_state.logical_width,
+            saved_window_state.logical_height,
+        )
+    });
+
+    Some(ResolvedMonitor {
+        monitor_info,
+        logical_position,
+        monitor_resolution_source,
+    })
+}
+
+/// Swap in the geometry remembered for the resolved monitor under
+/// [`GeometryMemory::PerMonitor`].
+///
+/// Returns the state to restore from, with the remembered size, and moves the resolved
+/// position to the remembered offset from the monitor's top-left. Without remembered
+/// geometry for that monitor, the saved state is used as-is.
+#[must_use]
+pub(crate) fn apply_remembered_geometry<'s>(
+    saved_window_state: &'s WindowState,
+    resolved_monitor: &mut ResolvedMonitor,
+    geometry_memory: GeometryMemory,
+) -> Cow<'s, WindowState> {
+    if geometry_memory != GeometryMemory::PerMonitor {
+        return Cow::Borrowed(saved_window_state);
+    }
+    let Some(monitor_geometry) = resolved_monitor
+        .monitor_info
+        .name
+        .as_deref()
+        .and_then(|name| saved_window_state.remembered_geometry(name))
+    else {
+        return Cow::Borrowed(saved_window_state);
+    };
+
+    if let (Some(_), Some(logical_offset)) = (
+        resolved_monitor.logical_position,
+        monitor_geometry.logical_monitor_offset,
+    ) {
+        resolved_monitor.logical_position = Some(offset_from_logical_origin(
+            resolved_monitor.monitor_info,
+            logical_offset,
+        ));
+    }
+    // The remembered size was saved on this monitor, so it is in this monitor's scale.
+    Cow::Owned(WindowState {
+        logical_width: monitor_geometry.logical_width,
+        logical_height: monitor_geometry.logical_height,
+        scale: resolved_monitor.monitor_info.scale,
+        ..saved_window_state.clone()
+    })
+}
+
+/// Drop the parts of the saved geometry that `geometry_restore` leaves alone.
+///
+/// Under [`GeometryRestore::SizeOnly`] the window stays where it was created, so the
+/// resolved monitor becomes `starting_monitor` and the resolved position is cleared. Under
+/// [`GeometryRestore::PositionOnly`] the returned state carries `window`'s current size.
+#[must_use]
+fn apply_geometry_restore<'s, 'a>(
+    saved_window_state: Cow<'s, WindowState>,
+    resolved_monitor: &mut ResolvedMonitor<'a>,
+    geometry_restore: GeometryRestore,
+    starting_monitor: &'a MonitorInfo,
+    window: &Window,
+) -> Cow<'s, WindowState> {
+    match geometry_restore {
+        GeometryRestore::PositionAndSize => saved_window_state,
+        GeometryRestore::SizeOnly => {
+            resolved_monitor.monitor_info = starting_monitor;
+            resolved_monitor.logical_position = None;
+            saved_window_state
         },
+        GeometryRestore::PositionOnly => Cow::Owned(WindowState {
+            logical_width: window.resolution.width().round().to_u32(),
+            logical_height: window.resolution.height().round().to_u32(),
+            ..saved_window_state.into_owned()
+        }),
+    }
+}
+
+/// Under [`SizePolicy::PreservePhysical`], convert the saved logical size to the logical
+/// size that covers the same physical pixels on `target_info`.
+#[must_use]
+fn apply_size_policy<'s>(
+    saved_window_state: Cow<'s, WindowState>,
+    target_info: &MonitorInfo,
+    size_policy: SizePolicy,
+    scale_factor_epsilon: f64,
+) -> Cow<'s, WindowState> {
+    if size_policy == SizePolicy::PreserveLogical
+        || (saved_window_state.scale - target_info.scale).abs() < scale_factor_epsilon
+        || saved_window_state.scale <= 0.0
+    {
+        return saved_window_state;
+    }
+    let physical_size = saved_window_state.physical_size();
+    let logical_size = (physical_size.as_dvec2() / target_info.scale)
+        .round()
+        .as_uvec2();
+    debug!(
+        "[apply_size_policy] Preserving physical size {physical_size}: logical {} -> {logical_size} at scale {}",
+        saved_window_state.logical_size(),
+        target_info.scale,
+    );
+    let mut window_state = saved_window_state.into_owned();
+    window_state.set_logical_size(logical_size);
+    window_state.scale = target_info.scale;
+    Cow::Owned(window_state)
+}
+
+/// Resolve the monitor, position, and size a saved window is restored at, honoring
+/// `RestorePreference`, `MonitorFallback`, `GeometryMemory`, `GeometryRestore`, and
+/// `SizePolicy`.
+///
+/// `starting_monitor` and `window` describe the window as created. `geometry_restore` is
+/// the effective setting, which may differ from the configured one (macOS Stage Manager).
+/// `reference_monitor`, the monitor of a window's `RestoreRelativeTo` reference, replaces
+/// the saved monitor like the cursor monitor does and takes precedence over it.
+/// Returns `None` when the saved monitor is missing under [`MonitorFallback::DoNothing`].
+#[must_use]
+pub(crate) fn resolve_restore_target<'s, 'a>(
+    saved_window_state: &'s WindowState,
+    monitors: &'a Monitors,
+    restore_window_config: &RestoreWindowConfig,
+    geometry_restore: GeometryRestore,
+    platform: Platform,
+    starting_monitor: &'a MonitorInfo,
+    window: &Window,
+    reference_monitor: Option<&'a MonitorInfo>,
+) -> Option<(ResolvedMonitor<'a>, Cow<'s, WindowState>)> {
+    let preferred_monitor = reference_monitor.or_else(|| {
+        cursor::preferred_cursor_monitor(
+            restore_window_config.restore_preference,
+            monitors,
+            platform,
+        )
+    });
+    let Some(mut resolved_monitor) = resolve_target_monitor_and_position(
+        saved_window_state,
+        monitors,
+        restore_window_config.monitor_fallback,
+        preferred_monitor,
+    ) else {
+        warn!(
+            "[resolve_restore_target] Target monitor {} ({:?}) not found, skipping restore",
+            saved_window_state.monitor, saved_window_state.monitor_name,
+        );
+        return None;
+    };
+    if reference_monitor.is_some() {
+        resolved_monitor.monitor_resolution_source = MonitorResolutionSource::ReferenceWindow;
+    }
+    if resolved_monitor.is_fallback() {
+        warn!(
+            "[resolve_restore_target] Target monitor {} ({:?}) not found, falling back to monitor {}",
+            saved_window_state.monitor,
+            saved_window_state.monitor_name,
+            resolved_monitor.monitor_info.index,
+        );
+    }
+
+    let window_state = apply_remembered_geometry(
+        saved_window_state,
+        &mut resolved_monitor,
+        restore_window_config.geometry_memory,
+    );
+    let window_state = apply_geometry_restore(
+        window_state,
+        &mut resolved_monitor,
+        geometry_restore,
+        starting_monitor,
+        window,
+    );
+    // `PositionOnly` keeps the size the window was created with.
+    let window_state = if geometry_restore == GeometryRestore::PositionOnly {
+        window_state
+    } else {
+        apply_size_policy(
+            window_state,
+            resolved_monitor.monitor_info,
+            restore_window_config.size_policy,
+            restore_window_config.scale_factor_epsilon,
+        )
+    };
+    Some((resolved_monitor, window_state))
+}
+
+/// State that places a window without saved state per `default_placement`: windowed,
+/// centered on the chosen monitor, at `window`'s current size or the size given by
+/// [`DefaultPlacement::ScaledDefault`].
+///
+/// Returns `None` for [`DefaultPlacement::OsDefault`], and when the window cannot be
+/// positioned (Wayland) or no monitor is connected.
+#[must_use]
+pub(crate) fn default_placement_state(
+    default_placement: DefaultPlacement,
+    monitors: &Monitors,
+    window: &Window,
+    platform: Platform,
+) -> Option<WindowState> {
+    if !platform.position_available() || monitors.is_empty() {
+        return None;
+    }
+    let monitor_info = match default_placement {
+        DefaultPlacement::OsDefault => return None,
+        DefaultPlacement::CenterPrimary | DefaultPlacement::ScaledDefault(_) => monitors.primary(),
+        DefaultPlacement::CenterMonitor(index) => monitors.by_index(index).unwrap_or_else(|| {
+            warn!("[default_placement_state] Monitor {index} not found, centering on primary");
+            monitors.primary()
+        }),
+    };
+    // Restore converts the logical size to physical with the target monitor's scale.
+    let (logical_width, logical_height) = match default_placement {
+        DefaultPlacement::ScaledDefault(logical_size) => (logical_size.x, logical_size.y),
+        _ => (
+            window.resolution.width().round().to_u32(),
+            window.resolution.height().round().to_u32(),
+        ),
+    };
+    Some(WindowState {
+        logical_position: Some(centered_logical_position(
+            monitor_info,
+            logical_width,
+            logical_height,
+        )),
+        logical_width,
+        logical_height,
+        scale: monitor_info.scale,
+        monitor: monitor_info.index,
+        monitor_name: monitor_info.name.clone(),
+        logical_monitor_offset: None,
+        monitor_geometries: Vec::new(),
+        minimized: false,
+        restore_bounds: None,
+        saved_window_mode: SavedWindowMode::Windowed,
+        window_attributes: SavedWindowAttributes::from_window(window),
+        opacity: FULLY_OPAQUE,
+        title: None,
+        icon_path: None,
+        extra: BTreeMap::new(),
+        virtual_desktop: None,
+        space_behavior: None,
+        focused: false,
+        logical_invisible_border: None,
+        follows_current_monitor: false,
+        app_name: String::new(),
+    })
+}
+
+/// Saved position on the monitor the window was saved on, rebuilt from that monitor's
+/// current origin when the saved offset is known.
+fn matched_logical_position(
+    saved_window_state: &WindowState,
+    monitor_info: &MonitorInfo,
+) -> Option<(i32, i32)> {
+    let logical_position = saved_window_state.logical_position?;
+    Some(
+        saved_window_state
+            .logical_monitor_offset
+            .map_or(logical_position, |logical_offset| {
+                offset_from_logical_origin(monitor_info, logical_offset)
+            }),
     )
 }
+
+/// Logical position `logical_offset` away from the monitor's top-left corner.
+fn offset_from_logical_origin(
+    monitor_info: &MonitorInfo,
+    (logical_offset_x, logical_offset_y): (i32, i32),
+) -> (i32, i32) {
+    let (logical_left, logical_top) = monitor_info.logical_origin();
+    (
+        logical_left + logical_offset_x,
+        logical_top + logical_offset_y,
+    )
+}
+
+/// The saved monitor found by its saved name: the monitor at the saved index when its
+/// name matches, otherwise the only monitor with that name. `None` when no monitor, or
+/// several, have the name.
+fn monitor_matched_by_name<'a>(
+    saved_window_state: &WindowState,
+    monitors: &'a Monitors,
+) -> Option<&'a MonitorInfo> {
+    let name = saved_window_state.monitor_name.as_deref()?;
+    if let Some(monitor_info) = monitors
+        .by_index(saved_window_state.monitor)
+        .filter(|monitor_info| monitor_info.name.as_deref() == Some(name))
+    {
+        return Some(monitor_info);
+    }
+    let mut named = monitors
+        .iter()
+        .filter(|monitor_info| monitor_info.name.as_deref() == Some(name));
+    let monitor_info = named.next()?;
+    named.next().is_none().then_some(monitor_info)
+}
+
+/// The window's saved offset from its monitor's top-left corner, in logical pixels.
+///
+/// Files written before the offset was saved derive it from the saved monitor when that
+/// monitor is still connected.
+fn saved_logical_monitor_offset(
+    saved_window_state: &WindowState,
+    monitors: &Monitors,
+) -> Option<(i32, i32)> {
+    if let Some(logical_monitor_offset) = saved_window_state.logical_monitor_offset {
+        return Some(logical_monitor_offset);
+    }
+    let (logical_x, logical_y) = saved_window_state.logical_position?;
+    let saved_info = monitor_matched_by_name(saved_window_state, monitors)
+        .or_else(|| monitors.by_index(saved_window_state.monitor))?;
+    // Saved positions are in the saved monitor's logical space.
+    let logical_left = (f64::from(saved_info.physical_position.x) / saved_window_state.scale)
+        .round()
+        .to_i32();
+    let logical_top = (f64::from(saved_info.physical_position.y) / saved_window_state.scale)
+        .round()
+        .to_i32();
+    Some((logical_x - logical_left, logical_y - logical_top))
+}
+
+/// Logical top-left position that centers a window of the given logical size on a monitor.
+///
+/// Windows larger than the monitor are pinned to its top-left corner.
+fn centered_logical_position(
+    monitor_info: &MonitorInfo,
+    logical_width: u32,
+    logical_height: u32,
+) -> (i32, i32) {
+    let logical_left = f64::from(monitor_info.physical_position.x) / monitor_info.scale;
+    let logical_top = f64::from(monitor_info.physical_position.y) / monitor_info.scale;
+    let logical_monitor_width = f64::from(monitor_info.physical_size.x) / monitor_info.scale;
+    let logical_monitor_height = f64::from(monitor_info.physical_size.y) / monitor_info.scale;
+
+    let logical_x =
+        logical_left + ((logical_monitor_width - f64::from(logical_width)) / 2.0).max(0.0);
+    let logical_y =
+        logical_top + ((logical_monitor_height - f64::from(logical_height)) / 2.0).max(0.0);
+    (logical_x.round().to_i32(), logical_y.round().to_i32())
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, reason = "tests should panic on unexpected values")]
+mod tests {
+    use std::borrow::Cow;
+
+    use bevy::prelude::*;
+    use bevy::window::MonitorSelection;
+    use bevy::window::WindowMode;
+
+    use super::MonitorResolutionSource;
+    use super::apply_remembered_geometry;
+    use super::apply_size_policy;
+    use super::default_placement_state;
+    use super::resolve_target_monitor_and_position;
+    use crate::constants::DEFAULT_SCALE_FACTOR;
+    use crate::constants::SCALE_FACTOR_EPSILON;
+    use crate::monitors::MonitorInfo;
+    use crate::monitors::Monitors;
+    use crate::persistence::MonitorGeometry;
+    use crate::persistence::SavedWindowMode;
+    use crate::persistence::WindowState;
+    use crate::platform::Platform;
+    use crate::restore::target_position::compute_target_position;
+    use crate::restore_window_config::DefaultPlacement;
+    use crate::restore_window_config::GeometryMemory;
+    use crate::restore_window_config::MonitorFallback;
+    use crate::restore_window_config::SizePolicy;
+    use crate::test_support;
+
+    fn monitor(index: usize, name: &str, physical_x: i32) -> MonitorInfo {
+        MonitorInfo {
+            name: Some(name.to_string()),
+            ..test_support::monitor(index, physical_x)
+        }
+    }
+
+    fn monitors() -> Monitors {
+        Monitors {
+            list: vec![monitor(0, "Built-in", 0), monitor(1, "External", 1920)],
+        }
+    }
+
+    fn saved_state(monitor: usize, monitor_name: &str, logical_x: i32) -> WindowState {
+        WindowState {
+            logical_position: Some((logical_x, 20)),
+            monitor,
+            monitor_name: Some(monitor_name.to_string()),
+            ..WindowState::new(UVec2::new(800, 600))
+        }
+    }
+
+    #[test]
+    fn name_match_wins_over_index() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(0, "External", 10),
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 1);
+        assert_eq!(resolved.logical_position, Some((10, 20)));
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::MatchedByName
+        ));
+    }
+
+    #[test]
+    fn name_match_wins_over_index_only_when_the_name_is_unambiguous() {
+        let monitors = Monitors {
+            list: vec![
+                monitor(0, "Built-in", 0),
+                monitor(1, "DELL U2720Q", 1920),
+                monitor(2, "DELL U2720Q", 3840),
+            ],
+        };
+        for saved_index in [1, 2] {
+            let resolved = resolve_target_monitor_and_position(
+                &saved_state(saved_index, "DELL U2720Q", 10),
+                &monitors,
+                MonitorFallback::FallbackToPrimary,
+                None,
+            );
+            let Some(resolved) = resolved else {
+                panic!("expected a resolved monitor");
+            };
+            assert_eq!(resolved.monitor_info.index, saved_index);
+        }
+
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(0, "DELL U2720Q", 10),
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 0);
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::Requested
+        ));
+    }
+
+    #[test]
+    fn matched_monitor_uses_offset_from_its_current_origin() {
+        // "External" moved from the right of "Built-in" to its left.
+        let monitors = Monitors {
+            list: vec![monitor(0, "External", -1920), monitor(1, "Built-in", 0)],
+        };
+        let mut saved = saved_state(1, "External", 2020);
+        saved.logical_monitor_offset = Some((100, 20));
+        let resolved = resolve_target_monitor_and_position(
+            &saved,
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 0);
+        assert_eq!(resolved.logical_position, Some((-1820, 20)));
+    }
+
+    #[test]
+    fn remembered_geometry_replaces_saved_geometry_on_other_monitor() {
+        let monitors = monitors();
+        let mut saved = saved_state(1, "External", 2020);
+        saved.monitor_geometries = vec![MonitorGeometry {
+            monitor_name:           "Built-in".to_string(),
+            logical_monitor_offset: Some((40, 30)),
+            logical_width:          1024,
+            logical_height:         768,
+        }];
+        let Some(mut resolved) = resolve_target_monitor_and_position(
+            &saved,
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            monitors.by_index(0),
+        ) else {
+            panic!("expected a resolved monitor");
+        };
+
+        let window_state =
+            apply_remembered_geometry(&saved, &mut resolved, GeometryMemory::PerMonitor);
+        assert_eq!(
+            (window_state.logical_width, window_state.logical_height),
+            (1024, 768)
+        );
+        assert_eq!(resolved.logical_position, Some((40, 30)));
+    }
+
+    #[test]
+    fn preserve_physical_rescales_saved_size_to_target_monitor() {
+        let saved = WindowState {
+            scale: 2.0,
+            ..saved_state(0, "Built-in", 10)
+        };
+        let target_info = monitor(1, "External", 1920);
+
+        let preserved = |size_policy| {
+            let window_state = apply_size_policy(
+                Cow::Borrowed(&saved),
+                &target_info,
+                size_policy,
+                SCALE_FACTOR_EPSILON,
+            );
+            (window_state.logical_width, window_state.logical_height)
+        };
+        assert_eq!(preserved(SizePolicy::PreserveLogical), (800, 600));
+        assert_eq!(preserved(SizePolicy::PreservePhysical), (1600, 1200));
+    }
+
+    #[test]
+    fn unknown_name_falls_back_to_index() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(1, "Unplugged", 10),
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 1);
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::Requested
+        ));
+    }
+
+    #[test]
+    fn fullscreen_targets_the_monitor_matched_by_name_not_the_saved_index() {
+        // "External" was monitor 1 when saved and is monitor 0 now.
+        let monitors = Monitors {
+            list: vec![monitor(0, "External", -1920), monitor(1, "Built-in", 0)],
+        };
+        let saved = WindowState {
+            saved_window_mode: SavedWindowMode::BorderlessFullscreen,
+            ..saved_state(1, "External", 2020)
+        };
+        let Some(resolved) = resolve_target_monitor_and_position(
+            &saved,
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        ) else {
+            panic!("expected a resolved monitor");
+        };
+
+        let target_position = compute_target_position(
+            &saved,
+            resolved.monitor_info,
+            resolved.logical_position,
+            UVec2::ZERO,
+            DEFAULT_SCALE_FACTOR,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+        assert_eq!(
+            target_position.window_mode(),
+            WindowMode::BorderlessFullscreen(MonitorSelection::Index(0))
+        );
+    }
+
+    #[test]
+    fn missing_monitor_falls_back_to_primary_centered() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(5, "Unplugged", 5000),
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 0);
+        assert_eq!(resolved.logical_position, Some((560, 240)));
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::FallbackToPrimary
+        ));
+    }
+
+    #[test]
+    fn missing_monitor_falls_back_to_closest_centered() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(5, "Unplugged", 5000),
+            &monitors,
+            MonitorFallback::FallbackToClosest,
+            None,
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 1);
+        assert_eq!(resolved.logical_position, Some((2480, 240)));
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::FallbackToClosest
+        ));
+    }
+
+    #[test]
+    fn missing_monitor_with_do_nothing_skips_restore() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(5, "Unplugged", 5000),
+            &monitors,
+            MonitorFallback::DoNothing,
+            None,
+        );
+        assert!(resolved.is_none());
+    }
+
+    #[test]
+    fn cursor_monitor_keeps_saved_offset() {
+        let monitors = monitors();
+        let mut saved = saved_state(0, "Built-in", 100);
+        saved.logical_monitor_offset = Some((100, 20));
+        let resolved = resolve_target_monitor_and_position(
+            &saved,
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            monitors.by_index(1),
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 1);
+        assert_eq!(resolved.logical_position, Some((2020, 20)));
+        assert!(matches!(
+            resolved.monitor_resolution_source,
+            MonitorResolutionSource::CursorMonitor
+        ));
+    }
+
+    #[test]
+    fn cursor_monitor_derives_offset_from_saved_monitor() {
+        let monitors = monitors();
+        let resolved = resolve_target_monitor_and_position(
+            &saved_state(1, "External", 2020),
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            monitors.by_index(0),
+        );
+        let Some(resolved) = resolved else {
+            panic!("expected a resolved monitor");
+        };
+        assert_eq!(resolved.monitor_info.index, 0);
+        assert_eq!(resolved.logical_position, Some((100, 20)));
+    }
+
+    #[test]
+    fn default_placement_centers_window_on_chosen_monitor() {
+        let monitors = monitors();
+        let window = Window::default();
+        let centered = |default_placement| {
+            default_placement_state(default_placement, &monitors, &window, Platform::Windows)
+                .map(|window_state| (window_state.monitor, window_state.logical_position))
+        };
+
+        // `Window::default()` is 1280x720 on 1920x1080 monitors.
+        assert_eq!(
+            centered(DefaultPlacement::CenterPrimary),
+            Some((0, Some((320, 180))))
+        );
+        assert_eq!(
+            centered(DefaultPlacement::CenterMonitor(1)),
+            Some((1, Some((2240, 180))))
+        );
+        assert_eq!(
+            centered(DefaultPlacement::CenterMonitor(5)),
+            Some((0, Some((320, 180))))
+        );
+        assert_eq!(centered(DefaultPlacement::OsDefault), None);
+        assert_eq!(
+            centered(DefaultPlacement::ScaledDefault(UVec2::new(800, 600))),
+            Some((0, Some((560, 240))))
+        );
+        assert!(
+            default_placement_state(
+                DefaultPlacement::CenterPrimary,
+                &monitors,
+                &window,
+                Platform::Wayland,
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! Pure restore geometry planning.
+//!
+//! Decides where each restore step places and sizes a window from a [`TargetPosition`]
+//! alone, so the [`MonitorScaleStrategy`] transitions can be tested without a winit
+//! window. `application.rs` applies the planned geometry to the `Window`.
+
+use bevy::prelude::*;
+use bevy::window::MonitorSelection;
+use bevy::window::WindowPosition;
+use bevy_kana::ToU32;
+
+use super::strategy::MonitorScaleStrategy;
+use super::strategy::WindowRestoreState;
+use super::target::TargetPosition;
+use crate::monitors::Monitors;
+use crate::restore_window_config::GeometryRestore;
+
+/// Where a restore step places the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PlannedPosition {
+    /// Exact physical position, already compensated for the scale strategy.
+    At(IVec2),
+    /// Centered on the monitor with this index, used when no position was saved.
+    Centered(usize),
+}
+
+impl PlannedPosition {
+    /// Physical position if known, otherwise centered on the target monitor. Under
+    /// `GeometryRestore::SizeOnly` an unknown position leaves the window where it is.
+    fn at_or_centered(
+        physical_position: Option<IVec2>,
+        target_position: &TargetPosition,
+    ) -> Option<Self> {
+        match physical_position {
+            Some(physical_position) => Some(Self::At(physical_position)),
+            None if target_position.geometry_restore == GeometryRestore::SizeOnly => None,
+            None => Some(Self::Centered(target_position.monitor_index)),
+        }
+    }
+
+    pub(super) const fn to_window_position(self) -> WindowPosition {
+        match self {
+            Self::At(physical_position) => WindowPosition::At(physical_position),
+            Self::Centered(monitor_index) => {
+                WindowPosition::Centered(MonitorSelection::Index(monitor_index))
+            },
+        }
+    }
+}
+
+/// Geometry requested by one restore step. `None` leaves that part of the window as is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct PlannedGeometry {
+    pub(super) position:      Option<PlannedPosition>,
+    pub(super) physical_size: Option<UVec2>,
+}
+
+/// Outcome of planning the windowed (not fullscreen or maximized) restore step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum WindowedRestoreStep {
+    /// Apply this geometry and consider the restore applied.
+    Apply(PlannedGeometry),
+    /// The strategy is waiting for its initial move or a scale change.
+    Wait,
+}
+
+/// Geometry for the initial move onto the target monitor.
+///
+/// `HigherToLower` compensates the position by ×ratio (= `starting_scale / target_scale`,
+/// e.g. ×2 for 2x→1x): `set_outer_position` is applied at the starting monitor's scale,
+/// so crossing to the half-scale target halves the physical position unless
+/// pre-multiplied. Its size stays a placeholder until the `ApplySize` phase.
+/// `CompensateSizeOnly` compensates the size instead. Fullscreen targets only move.
+#[must_use]
+pub(super) fn initial_move_geometry(target_position: &TargetPosition) -> PlannedGeometry {
+    if target_position.saved_window_mode.is_fullscreen() {
+        return PlannedGeometry {
+            position:      target_position.physical_position.map(PlannedPosition::At),
+            physical_size: None,
+        };
+    }
+
+    let Some(physical_position) = target_position.physical_position else {
+        return PlannedGeometry {
+            position:      PlannedPosition::at_or_centered(None, target_position),
+            physical_size: None,
+        };
+    };
+
+    let (physical_move_position, physical_move_size) = match target_position.monitor_scale_strategy
+    {
+        MonitorScaleStrategy::HigherToLower(_) => (
+            target_position
+                .compensated_position()
+                .unwrap_or(physical_position),
+            target_position.physical_size,
+        ),
+        MonitorScaleStrategy::CompensateSizeOnly(_) => {
+            (physical_position, target_position.compensated_size())
+        },
+        MonitorScaleStrategy::ApplyUnchanged | MonitorScaleStrategy::LowerToHigher => {
+            (physical_position, target_position.physical_size)
+        },
+    };
+    PlannedGeometry {
+        position:      Some(PlannedPosition::At(physical_move_position)),
+        physical_size: Some(physical_move_size),
+    }
+}
+
+/// Geometry for a cross-DPI restore with no saved position.
+///
+/// Sized at `starting_scale`: `set_physical_resolution` is interpreted at the window's
+/// current scale factor, which is `starting_scale` until the move completes. Storing
+/// logical = `starting_size / starting_scale = logical_size` means the post-move physical
+/// size resolves to `logical_size * target_scale`, matching `physical_size` for settle.
+#[must_use]
+pub(super) fn cross_dpi_centered_geometry(target_position: &TargetPosition) -> PlannedGeometry {
+    let physical_size = UVec2::new(
+        (f64::from(target_position.logical_size.x) * target_position.starting_scale)
+            .round()
+            .to_u32(),
+        (f64::from(target_position.logical_size.y) * target_position.starting_scale)
+            .round()
+            .to_u32(),
+    );
+    PlannedGeometry {
+        position:      PlannedPosition::at_or_centered(None, target_position),
+        physical_size: Some(physical_size),
+    }
+}
+
+/// Plan the restore step for a windowed target in its current strategy state.
+#[must_use]
+pub(super) fn windowed_restore_step(target_position: &TargetPosition) -> WindowedRestoreStep {
+    let physical_size = Some(target_position.physical_size);
+
+    match target_position.monitor_scale_strategy {
+        MonitorScaleStrategy::ApplyUnchanged => WindowedRestoreStep::Apply(PlannedGeometry {
+            position: PlannedPosition::at_or_centered(
+                target_position.physical_position,
+                target_position,
+            ),
+            physical_size,
+        }),
+        // Position still needs ratio compensation: on a low→high cross-scale move,
+        // `set_outer_position` is applied at the starting monitor's scale, so the move
+        // doubles it. Size must NOT be compensated: as of bevy 0.19, `request_inner_size`
+        // resolves at the target monitor's scale, so it produces the requested full
+        // physical size (compensating it would halve it).
+        MonitorScaleStrategy::LowerToHigher => WindowedRestoreStep::Apply(PlannedGeometry {
+            position: PlannedPosition::at_or_centered(
+                target_position.compensated_position(),
+                target_position,
+            ),
+            physical_size,
+        }),
+        // Position was set by the initial move.
+        MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::ApplySize)
+        | MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize) => {
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position: None,
+                physical_size,
+            })
+        },
+        MonitorScaleStrategy::CompensateSizeOnly(
+            WindowRestoreState::NeedInitialMove | WindowRestoreState::WaitingForScaleChange,
+        )
+        | MonitorScaleStrategy::HigherToLower(
+            WindowRestoreState::NeedInitialMove | WindowRestoreState::WaitingForScaleChange,
+        ) => WindowedRestoreStep::Wait,
+    }
+}
+
+/// Position that brings a window of `physical_size` at `physical_position` back on screen.
+///
+/// Returns `None` when any corner of the window lies on a monitor. Otherwise the window is
+/// moved into the usable area of the monitor closest to its center; a window larger than
+/// that area is aligned to its top-left corner.
+#[must_use]
+pub(super) fn on_screen_position(
+    physical_position: IVec2,
+    physical_size: UVec2,
+    monitors: &Monitors,
+) -> Option<IVec2> {
+    if monitors.is_empty() {
+        return None;
+    }
+
+    let physical_far_corner = physical_position + physical_size.as_ivec2().max(IVec2::ONE) - 1;
+    let corners = [
+        physical_position,
+        IVec2::new(physical_far_corner.x, physical_position.y),
+        IVec2::new(physical_position.x, physical_far_corner.y),
+        physical_far_corner,
+    ];
+    if corners
+        .iter()
+        .any(|corner| monitors.at(corner.x, corner.y).is_some())
+    {
+        return None;
+    }
+
+    let physical_center = physical_position + physical_size.as_ivec2() / 2;
+    let physical_area = monitors
+        .closest_to(physical_center.x, physical_center.y)
+        .physical_usable_bounds();
+    let physical_max = (physical_area.max - physical_size.as_ivec2()).max(physical_area.min);
+    Some(physical_position.clamp(physical_area.min, physical_max))
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, reason = "tests should panic on unexpected values")]
+mod tests {
+
+    use super::*;
+    use crate::Platform;
+    use crate::constants::SCALE_FACTOR_EPSILON;
+    use crate::monitors::MonitorInfo;
+    use crate::monitors::Monitors;
+    use crate::persistence::WindowState;
+    use crate::restore::target_position::compute_target_position;
+    use crate::restore::target_position::monitor::resolve_target_monitor_and_position;
+    use crate::restore_window_config::GeometryRestore;
+    use crate::restore_window_config::MonitorFallback;
+    use crate::test_support;
+
+    fn monitor(index: usize, scale: f64, physical_x: i32) -> MonitorInfo {
+        MonitorInfo {
+            scale,
+            physical_size: UVec2::new(3840, 2160),
+            ..test_support::monitor(index, physical_x)
+        }
+    }
+
+    fn saved_state(logical_position: Option<(i32, i32)>, monitor: usize) -> WindowState {
+        WindowState {
+            logical_position,
+            monitor,
+            ..WindowState::new(UVec2::new(800, 600))
+        }
+    }
+
+    fn target(
+        saved_window_state: &WindowState,
+        target_info: &MonitorInfo,
+        starting_scale: f64,
+        platform: Platform,
+    ) -> TargetPosition {
+        compute_target_position(
+            saved_window_state,
+            target_info,
+            saved_window_state.logical_position,
+            UVec2::ZERO,
+            starting_scale,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            platform,
+        )
+    }
+
+    #[test]
+    fn same_scale_applies_saved_geometry_unchanged() {
+        let target_position = target(
+            &saved_state(Some((100, 50)), 0),
+            &monitor(0, 2.0, 0),
+            2.0,
+            Platform::MacOs,
+        );
+
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::ApplyUnchanged
+        );
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      Some(PlannedPosition::At(IVec2::new(200, 100))),
+                physical_size: Some(UVec2::new(1600, 1200)),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "workaround-winit-4440")]
+    fn low_to_high_compensates_position_but_not_size() {
+        let target_position = target(
+            &saved_state(Some((100, 50)), 0),
+            &monitor(0, 2.0, 0),
+            1.0,
+            Platform::MacOs,
+        );
+
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::LowerToHigher
+        );
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      Some(PlannedPosition::At(IVec2::new(100, 50))),
+                physical_size: Some(UVec2::new(1600, 1200)),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "workaround-winit-4440")]
+    fn high_to_low_moves_first_then_applies_size() {
+        let mut target_position = target(
+            &saved_state(Some((100, 50)), 0),
+            &monitor(0, 1.0, 0),
+            2.0,
+            Platform::MacOs,
+        );
+
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::NeedInitialMove)
+        );
+        assert_eq!(
+            initial_move_geometry(&target_position),
+            PlannedGeometry {
+                position:      Some(PlannedPosition::At(IVec2::new(200, 100))),
+                physical_size: Some(UVec2::new(800, 600)),
+            }
+        );
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Wait
+        );
+
+        target_position.monitor_scale_strategy =
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize);
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      None,
+                physical_size: Some(UVec2::new(800, 600)),
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "workaround-winit-4440")]
+    fn windows_cross_dpi_compensates_size_only() {
+        let target_position = target(
+            &saved_state(Some((100, 50)), 0),
+            &monitor(0, 1.0, 0),
+            2.0,
+            Platform::Windows,
+        );
+
+        assert_eq!(
+            initial_move_geometry(&target_position),
+            PlannedGeometry {
+                position:      Some(PlannedPosition::At(IVec2::new(100, 50))),
+                physical_size: Some(UVec2::new(1600, 1200)),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_monitor_restores_centered_on_primary() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 1.0, 0)],
+        };
+        let saved_window_state = saved_state(Some((4000, 50)), 1);
+        let Some(resolved) = resolve_target_monitor_and_position(
+            &saved_window_state,
+            &monitors,
+            MonitorFallback::FallbackToPrimary,
+            None,
+        ) else {
+            panic!("expected a fallback monitor");
+        };
+        let target_position = compute_target_position(
+            &saved_window_state,
+            resolved.monitor_info,
+            resolved.logical_position,
+            UVec2::ZERO,
+            1.0,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+
+        assert_eq!(target_position.monitor_index, 0);
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      Some(PlannedPosition::At(IVec2::new(1520, 780))),
+                physical_size: Some(UVec2::new(800, 600)),
+            })
+        );
+    }
+
+    #[test]
+    fn wayland_without_position_centers_on_saved_monitor() {
+        let target_position = target(
+            &saved_state(None, 1),
+            &monitor(1, 1.5, 3840),
+            1.0,
+            Platform::Wayland,
+        );
+
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::ApplyUnchanged
+        );
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      Some(PlannedPosition::Centered(1)),
+                physical_size: Some(UVec2::new(1200, 900)),
+            })
+        );
+    }
+
+    #[test]
+    fn size_only_leaves_position_alone() {
+        let mut target_position = target(
+            &saved_state(None, 0),
+            &monitor(0, 2.0, 0),
+            2.0,
+            Platform::MacOs,
+        );
+        target_position.geometry_restore = GeometryRestore::SizeOnly;
+
+        assert_eq!(
+            windowed_restore_step(&target_position),
+            WindowedRestoreStep::Apply(PlannedGeometry {
+                position:      None,
+                physical_size: Some(UVec2::new(1600, 1200)),
+            })
+        );
+    }
+
+    #[test]
+    fn off_screen_target_moves_onto_closest_monitor() {
+        let monitors = Monitors {
+            list: vec![monitor(0, 1.0, 0), monitor(1, 2.0, 3840)],
+        };
+        let physical_size = UVec2::new(1600, 1200);
+
+        assert_eq!(
+            on_screen_position(IVec2::new(-1000, 500), physical_size, &monitors),
+            None,
+            "a window with a corner on a monitor stays put"
+        );
+        assert_eq!(
+            on_screen_position(IVec2::new(9000, -5000), physical_size, &monitors),
+            Some(IVec2::new(6080, 0))
+        );
+        assert_eq!(
+            on_screen_position(IVec2::new(-5000, 100), UVec2::new(5000, 3000), &monitors),
+            Some(IVec2::new(0, 0)),
+            "a window larger than the monitor aligns to its top-left"
+        );
+    }
+}
@@ -1,14 +1,31 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use bevy::prelude::*;
+use bevy::window::MonitorSelection;
+use bevy::window::WindowMode;
 use bevy_kana::ToI32;
 use bevy_kana::ToU32;
 
 use super::strategy::FullscreenRestoreState;
 use super::strategy::MonitorScaleStrategy;
+use super::strategy::WindowRestoreState;
 use crate::Platform;
+use crate::WindowKey;
+use crate::WindowRestoreStarted;
+use crate::constants::FULLY_OPAQUE;
+use crate::constants::SCALE_CHANGE_TIMEOUT_FRAMES;
+use crate::constants::SNAP_EDGE_THRESHOLD;
 use crate::monitors::MonitorInfo;
+use crate::opacity;
+use crate::persistence::SavedSpaceBehavior;
+use crate::persistence::SavedWindowAttributes;
 use crate::persistence::SavedWindowMode;
 use crate::persistence::WindowState;
 use crate::restore::settle_state::SettleState;
+use crate::restore_window_config::GeometryRestore;
+use crate::restore_window_config::ModeSequencing;
+use crate::restore_window_config::SnapPolicy;
 
 /// Holds the target window state during the restore process.
 ///
@@ -41,11 +58,16 @@ pub(crate) struct TargetPosition {
     pub(crate) monitor_scale_strategy:   MonitorScaleStrategy,
     /// Window mode to restore.
     pub(crate) saved_window_mode:        SavedWindowMode,
-    /// Target monitor index for fullscreen restore.
+    /// Target monitor index for fullscreen restore: the current index of the monitor the
+    /// saved state resolved to (matched by name first), never the raw saved index, which
+    /// may point at another screen after the monitor set changed.
     /// On non-Wayland platforms, this could be derived from position, but Wayland
     /// doesn't provide window position, so we store it explicitly.
     pub(crate) monitor_index:            usize,
-    /// Fullscreen restore state (DX12/DXGI workaround).
+    /// Restore fullscreen on `MonitorSelection::Current` rather than `monitor_index`.
+    pub(crate) follows_current_monitor:  bool,
+    /// Fullscreen restore state (DX12/DXGI workaround). Also sequences maximized targets
+    /// under `ModeSequencing::SeparateFrames`.
     pub(crate) fullscreen_restore_state: Option<FullscreenRestoreState>,
     /// Settling state. When set, `try_apply_restore` has completed and we're waiting
     /// for the compositor/winit to deliver stable, matching state.
@@ -60,9 +82,95 @@ pub(crate) struct TargetPosition {
     /// reports where `current_monitor()` briefly returns the wrong monitor during
     /// fullscreen transitions.
     pub(crate) settle_state:             Option<SettleState>,
+    /// Minimize the window once the restore settles
+    /// (saved minimized with [`MinimizedRestore::Minimize`](crate::MinimizedRestore::Minimize)).
+    pub(crate) minimize_after_restore:   bool,
+    /// Focus the window once the restore settles (see
+    /// [`FocusOnRestore`](crate::FocusOnRestore)).
+    pub(crate) focus_after_restore:      bool,
+    /// Decorations, resizability, and stacking level to apply.
+    pub(crate) window_attributes:        SavedWindowAttributes,
+    /// Saved opacity still to apply. `None` once applied, or when the window was saved
+    /// opaque since new windows already are.
+    pub(crate) opacity:                  Option<f32>,
+    /// Saved title, applied under `TitleRestore::Restore`.
+    pub(crate) title:                    Option<String>,
+    /// Saved icon path, reported in `WindowRestored` for the app to load.
+    pub(crate) icon_path:                Option<PathBuf>,
+    /// Saved app-defined settings, reported in `WindowRestored` for the app to apply.
+    pub(crate) extra:                    BTreeMap<String, String>,
+    /// Saved Windows virtual desktop still to move the window to. `None` once applied.
+    pub(crate) virtual_desktop:          Option<String>,
+    /// Saved macOS Spaces behavior still to set. `None` once applied.
+    pub(crate) space_behavior:           Option<SavedSpaceBehavior>,
+    /// Logical windowed size a maximized target is given before it maximizes, so
+    /// unmaximizing returns to the size it had before it was maximized.
+    pub(crate) logical_restore_size:     Option<UVec2>,
+    /// Which parts of the saved geometry are restored. Under `SizeOnly` a missing
+    /// `physical_position` means the window is left where it is rather than centered.
+    pub(crate) geometry_restore:         GeometryRestore,
+    /// Frames spent in `WaitingForScaleChange` without a scale change.
+    pub(crate) scale_change_wait_frames: u32,
 }
 
 impl TargetPosition {
+    /// Under [`ModeSequencing::SeparateFrames`], move fullscreen and maximized targets onto
+    /// their monitor before the frame that applies the mode.
+    pub(crate) fn apply_mode_sequencing(&mut self, mode_sequencing: ModeSequencing) {
+        if mode_sequencing == ModeSequencing::SeparateFrames
+            && (self.saved_window_mode.is_fullscreen() || self.saved_window_mode.is_maximized())
+        {
+            self.fullscreen_restore_state = Some(FullscreenRestoreState::MoveToMonitor);
+        }
+    }
+
+    /// Snap the restored position to the target monitor's edges or a grid per
+    /// `snap_policy`, then clamp it back inside the monitor.
+    ///
+    /// Only windowed targets with a known position snap. This runs on the target-scale
+    /// position before any scale compensation, so [`Self::ratio`] is unaffected.
+    pub(crate) fn apply_snap_policy(
+        &mut self,
+        snap_policy: SnapPolicy,
+        saved_window_state: &WindowState,
+        target_info: &MonitorInfo,
+        physical_decoration: UVec2,
+    ) {
+        if snap_policy == SnapPolicy::Disabled
+            || self.saved_window_mode != SavedWindowMode::Windowed
+        {
+            return;
+        }
+        let Some(physical_position) = self.physical_position else {
+            return;
+        };
+        let physical_outer_size =
+            physical_outer_size(saved_window_state, self.physical_size, physical_decoration);
+        let physical_invisible_border =
+            physical_invisible_border(saved_window_state, self.target_scale);
+        let physical_snapped = snap_position(
+            physical_position,
+            snap_policy,
+            physical_placement_bounds(target_info, physical_invisible_border),
+            physical_outer_size,
+            self.target_scale,
+        );
+        let physical_snapped = clamp_position_to_monitor(
+            physical_snapped.x,
+            physical_snapped.y,
+            target_info,
+            physical_outer_size.x,
+            physical_outer_size.y,
+            physical_invisible_border,
+        );
+        if physical_snapped != physical_position {
+            debug!(
+                "[apply_snap_policy] {snap_policy:?}: {physical_position} -> {physical_snapped}"
+            );
+            self.physical_position = Some(physical_snapped);
+        }
+    }
+
     /// Scale ratio between starting and target monitors.
     #[must_use]
     pub(super) const fn ratio(&self) -> f64 { self.starting_scale / self.target_scale }
@@ -76,8 +184,8 @@ impl TargetPosition {
         let ratio = self.ratio();
         self.physical_position.map(|position| {
             IVec2::new(
-                (f64::from(position.x) * ratio).to_i32(),
-                (f64::from(position.y) * ratio).to_i32(),
+                (f64::from(position.x) * ratio).round().to_i32(),
+                (f64::from(position.y) * ratio).round().to_i32(),
             )
         })
     }
@@ -89,10 +197,84 @@ impl TargetPosition {
     pub(super) fn compensated_size(&self) -> UVec2 {
         let ratio = self.ratio();
         UVec2::new(
-            (f64::from(self.physical_size.x) * ratio).to_u32(),
-            (f64::from(self.physical_size.y) * ratio).to_u32(),
+            (f64::from(self.physical_size.x) * ratio).round().to_u32(),
+            (f64::from(self.physical_size.y) * ratio).round().to_u32(),
         )
     }
+
+    /// Move a cross-DPI restore waiting for the scale change on to `ApplySize`.
+    ///
+    /// `HigherToLower` waits for `scale_changed`, but gives up after
+    /// [`SCALE_CHANGE_TIMEOUT_FRAMES`] frames so a scale change that never arrives (the
+    /// monitors ended up at the same scale, or the event was dropped) cannot leave the
+    /// window unsized. `CompensateSizeOnly` never waits, since macOS sends no scale change
+    /// for windows that are not yet visible.
+    pub(crate) fn advance_scale_change_wait(&mut self, scale_changed: bool) {
+        match self.monitor_scale_strategy {
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange) => {
+                if scale_changed {
+                    debug!(
+                        "[advance_scale_change_wait] ScaleChanged received, transitioning to WindowRestoreState::ApplySize"
+                    );
+                } else {
+                    self.scale_change_wait_frames += 1;
+                    if self.scale_change_wait_frames < SCALE_CHANGE_TIMEOUT_FRAMES {
+                        return;
+                    }
+                    warn!(
+                        "[advance_scale_change_wait] No ScaleChanged after {SCALE_CHANGE_TIMEOUT_FRAMES} frames, applying size anyway (starting_scale={} target_scale={})",
+                        self.starting_scale, self.target_scale
+                    );
+                }
+                self.monitor_scale_strategy =
+                    MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize);
+            },
+            MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::WaitingForScaleChange) => {
+                debug!(
+                    "[advance_scale_change_wait] CompensateSizeOnly: transitioning to ApplySize (scale_changed={scale_changed})"
+                );
+                self.monitor_scale_strategy =
+                    MonitorScaleStrategy::CompensateSizeOnly(WindowRestoreState::ApplySize);
+            },
+            _ => {},
+        }
+    }
+
+    /// Monitor that fullscreen modes are restored on.
+    #[must_use]
+    pub(crate) const fn monitor_selection(&self) -> MonitorSelection {
+        if self.follows_current_monitor {
+            MonitorSelection::Current
+        } else {
+            MonitorSelection::Index(self.monitor_index)
+        }
+    }
+
+    /// Bevy `WindowMode` the window is restored to.
+    #[must_use]
+    pub(crate) const fn window_mode(&self) -> WindowMode {
+        self.saved_window_mode
+            .to_window_mode(self.monitor_selection())
+    }
+
+    /// Build the `WindowRestoreStarted` event announcing this restore target.
+    #[must_use]
+    pub(crate) const fn restore_started(
+        &self,
+        entity: Entity,
+        window_key: WindowKey,
+    ) -> WindowRestoreStarted {
+        WindowRestoreStarted {
+            entity,
+            window_key,
+            physical_position: self.physical_position,
+            logical_position: self.logical_position,
+            physical_size: self.physical_size,
+            logical_size: self.logical_size,
+            window_mode: self.window_mode(),
+            monitor_index: self.monitor_index,
+        }
+    }
 }
 
 /// Durable record of a restore's launch context and chosen strategy.
@@ -117,6 +299,10 @@ pub(crate) struct RestoreDiagnostics {
 }
 
 /// Compute a `TargetPosition` from saved state and a resolved target monitor.
+///
+/// The saved logical size is clamped to `min_size`/`max_size` first. A clamped size
+/// always re-runs the position clamp, on every platform, so a window grown to
+/// `min_size` is pulled back onto the monitor.
 #[must_use]
 pub(crate) fn compute_target_position(
     saved_window_state: &WindowState,
@@ -124,59 +310,122 @@ pub(crate) fn compute_target_position(
     logical_fallback_position: Option<(i32, i32)>,
     physical_decoration: UVec2,
     starting_scale: f64,
+    min_size: Option<UVec2>,
+    max_size: Option<UVec2>,
+    scale_factor_epsilon: f64,
     platform: Platform,
 ) -> TargetPosition {
     let target_scale = target_info.scale;
 
+    let saved_logical_size = UVec2::new(
+        saved_window_state.logical_width,
+        saved_window_state.logical_height,
+    );
+    let logical_size = clamp_logical_size(saved_logical_size, min_size, max_size);
+    let size_clamped = logical_size != saved_logical_size;
+    if size_clamped {
+        debug!(
+            "[compute_target_position] Clamped logical size {saved_logical_size} -> {logical_size} (min={min_size:?} max={max_size:?})"
+        );
+    }
+
     // Convert logical → physical using the target monitor's scale factor.
-    // This is the single conversion point for size values.
-    let physical_width = (f64::from(saved_window_state.logical_width) * target_scale).to_u32();
-    let physical_height = (f64::from(saved_window_state.logical_height) * target_scale).to_u32();
+    // This is the single conversion point for size values. Round rather than truncate:
+    // fractional scales (1.25, 1.5) otherwise lose a pixel on every save/restore cycle.
+    let physical_width = (f64::from(logical_size.x) * target_scale).round().to_u32();
+    let physical_height = (f64::from(logical_size.y) * target_scale).round().to_u32();
 
-    let physical_outer_width = physical_width + physical_decoration.x;
-    let physical_outer_height = physical_height + physical_decoration.y;
+    let physical_outer_size = physical_outer_size(
+        saved_window_state,
+        UVec2::new(physical_width, physical_height),
+        physical_decoration,
+    );
+    let physical_outer_width = physical_outer_size.x;
+    let physical_outer_height = physical_outer_size.y;
+    let physical_invisible_border = physical_invisible_border(saved_window_state, target_scale);
     let physical_position = logical_fallback_position.map(|(x, y)| {
         // Convert logical position to physical using the target monitor's scale factor.
         let physical_x = (f64::from(x) * target_scale).round().to_i32();
         let physical_y = (f64::from(y) * target_scale).round().to_i32();
-        clamp_position_to_monitor(
-            physical_x,
-            physical_y,
-            target_info,
-            physical_outer_width,
-            physical_outer_height,
-            platform,
-        )
+        if platform.should_clamp_position() || size_clamped {
+            clamp_position_to_monitor(
+                physical_x,
+                physical_y,
+                target_info,
+                physical_outer_width,
+                physical_outer_height,
+                physical_invisible_border,
+            )
+        } else {
+            IVec2::new(physical_x, physical_y)
+        }
     });
 
     TargetPosition {
         physical_position,
         logical_position: logical_fallback_position.map(|(x, y)| IVec2::new(x, y)),
         physical_size: UVec2::new(physical_width, physical_height),
-        logical_size: UVec2::new(
-            saved_window_state.logical_width,
-            saved_window_state.logical_height,
-        ),
+        logical_size,
         target_scale,
         starting_scale,
-        monitor_scale_strategy: platform.scale_strategy(starting_scale, target_scale),
+        // Maximized windows are placed by monitor selection and sized by the OS, so the
+        // cross-DPI position/size compensation phases do not apply.
+        monitor_scale_strategy: if saved_window_state.saved_window_mode.is_maximized() {
+            MonitorScaleStrategy::ApplyUnchanged
+        } else {
+            platform.scale_strategy(starting_scale, target_scale, scale_factor_epsilon)
+        },
         saved_window_mode: saved_window_state.saved_window_mode.clone(),
         monitor_index: target_info.index,
+        follows_current_monitor: saved_window_state.follows_current_monitor,
         fullscreen_restore_state: saved_window_state
             .saved_window_mode
             .is_fullscreen()
             .then_some(platform.fullscreen_restore_state()),
         settle_state: None,
+        minimize_after_restore: false,
+        focus_after_restore: false,
+        window_attributes: saved_window_state.window_attributes,
+        opacity: (saved_window_state.opacity < FULLY_OPAQUE)
+            .then(|| opacity::restorable_opacity(saved_window_state.opacity)),
+        title: saved_window_state.title.clone(),
+        icon_path: saved_window_state.icon_path.clone(),
+        extra: saved_window_state.extra.clone(),
+        virtual_desktop: saved_window_state.virtual_desktop.clone(),
+        space_behavior: saved_window_state.space_behavior,
+        logical_restore_size: saved_window_state
+            .restore_bounds
+            .filter(|_| saved_window_state.saved_window_mode.is_maximized())
+            .map(|restore_bounds| {
+                clamp_logical_size(restore_bounds.logical_size(), min_size, max_size)
+            }),
+        geometry_restore: GeometryRestore::PositionAndSize,
+        scale_change_wait_frames: 0,
     }
 }
 
-/// Calculate restored window position, with optional clamping.
+/// Clamp a saved logical size into `min_size..=max_size`. `max_size` wins if they conflict.
+#[must_use]
+fn clamp_logical_size(
+    logical_size: UVec2,
+    min_size: Option<UVec2>,
+    max_size: Option<UVec2>,
+) -> UVec2 {
+    let logical_size = min_size.map_or(logical_size, |min_size| logical_size.max(min_size));
+    max_size.map_or(logical_size, |max_size| logical_size.min(max_size))
+}
+
+/// Clamp a restored window position so the window fits on the target monitor.
 ///
-/// On macOS, clamps to monitor bounds because macOS may resize/reposition windows
-/// that extend beyond the screen. macOS does not allow windows to span monitors.
+/// Clamps to the monitor's work area (full bounds when unknown). Always used on macOS,
+/// which may resize/reposition windows that extend beyond the screen or under the menu
+/// bar and does not allow windows to span monitors. On Windows and Linux, windows can
+/// legitimately span multiple monitors, so the exact saved position is preserved unless
+/// the saved size was clamped.
 ///
-/// On Windows and Linux, windows can legitimately span multiple monitors,
-/// so we preserve the exact saved position without clamping.
+/// On Windows the outer rect includes an invisible resize border (winit #4107) on the left,
+/// right, and bottom edges, which may hang off the work area; only the visible frame is
+/// kept inside it.
 #[must_use]
 fn clamp_position_to_monitor(
     physical_saved_x: i32,
@@ -184,34 +433,459 @@ fn clamp_position_to_monitor(
     target_info: &MonitorInfo,
     physical_outer_width: u32,
     physical_outer_height: u32,
-    platform: Platform,
+    physical_invisible_border: IVec2,
+) -> IVec2 {
+    let physical_bounds = physical_placement_bounds(target_info, physical_invisible_border);
+    let physical_monitor_right = physical_bounds.max.x;
+    let physical_monitor_bottom = physical_bounds.max.y;
+
+    let mut physical_x = physical_saved_x;
+    let mut physical_y = physical_saved_y;
+
+    if physical_x + physical_outer_width.to_i32() > physical_monitor_right {
+        physical_x = physical_monitor_right - physical_outer_width.to_i32();
+    }
+    if physical_y + physical_outer_height.to_i32() > physical_monitor_bottom {
+        physical_y = physical_monitor_bottom - physical_outer_height.to_i32();
+    }
+    physical_x = physical_x.max(physical_bounds.min.x);
+    physical_y = physical_y.max(physical_bounds.min.y);
+
+    if physical_x != physical_saved_x || physical_y != physical_saved_y {
+        debug!(
+            "[clamp_position_to_monitor] Clamped: ({physical_saved_x}, {physical_saved_y}) -> ({physical_x}, {physical_y}) for outer size {physical_outer_width}x{physical_outer_height}"
+        );
+    }
+
+    IVec2::new(physical_x, physical_y)
+}
+
+/// Outer size of a window restored at `physical_size`.
+///
+/// `physical_decoration` was measured on the window as created; a window restored without
+/// decorations has none, so its outer size is just the content size.
+#[must_use]
+fn physical_outer_size(
+    saved_window_state: &WindowState,
+    physical_size: UVec2,
+    physical_decoration: UVec2,
+) -> UVec2 {
+    if saved_window_state.window_attributes.decorations {
+        physical_size + physical_decoration
+    } else {
+        physical_size
+    }
+}
+
+/// Saved Windows invisible resize border, converted to the target monitor's scale.
+#[must_use]
+fn physical_invisible_border(saved_window_state: &WindowState, target_scale: f64) -> IVec2 {
+    saved_window_state
+        .logical_invisible_border
+        .map_or(IVec2::ZERO, |(x, y)| {
+            IVec2::new(
+                (f64::from(x) * target_scale).round().to_i32(),
+                (f64::from(y) * target_scale).round().to_i32(),
+            )
+        })
+}
+
+/// Bounds a window's outer rect is kept inside: the monitor's usable area, widened by the
+/// invisible border so the visible frame, not the border, lines up with the monitor edges.
+#[must_use]
+fn physical_placement_bounds(target_info: &MonitorInfo, physical_invisible_border: IVec2) -> IRect {
+    let physical_usable_bounds = target_info.physical_usable_bounds();
+    IRect::from_corners(
+        physical_usable_bounds.min - physical_invisible_border,
+        physical_usable_bounds.max + IVec2::splat(physical_invisible_border.x),
+    )
+}
+
+/// Snap a physical position per `snap_policy` within `physical_bounds`.
+#[must_use]
+fn snap_position(
+    physical_position: IVec2,
+    snap_policy: SnapPolicy,
+    physical_bounds: IRect,
+    physical_outer_size: UVec2,
+    target_scale: f64,
 ) -> IVec2 {
-    if platform.should_clamp_position() {
-        let physical_monitor_right =
-            target_info.physical_position.x + target_info.physical_size.x.to_i32();
-        let physical_monitor_bottom =
-            target_info.physical_position.y + target_info.physical_size.y.to_i32();
+    match snap_policy {
+        SnapPolicy::Disabled => physical_position,
+        SnapPolicy::Edges => {
+            let physical_threshold = (SNAP_EDGE_THRESHOLD * target_scale).round().to_i32();
+            let snap_axis = |position: i32, min: i32, max: i32, outer: u32| {
+                let far_position = max - outer.to_i32();
+                if (position - min).abs() <= physical_threshold {
+                    min
+                } else if (position - far_position).abs() <= physical_threshold {
+                    far_position
+                } else {
+                    position
+                }
+            };
+            IVec2::new(
+                snap_axis(
+                    physical_position.x,
+                    physical_bounds.min.x,
+                    physical_bounds.max.x,
+                    physical_outer_size.x,
+                ),
+                snap_axis(
+                    physical_position.y,
+                    physical_bounds.min.y,
+                    physical_bounds.max.y,
+                    physical_outer_size.y,
+                ),
+            )
+        },
+        SnapPolicy::Grid(logical_cell) => {
+            let snap_axis = |position: i32, origin: i32, logical_cell: u32| {
+                let physical_cell = f64::from(logical_cell) * target_scale;
+                if physical_cell < 1.0 {
+                    return position;
+                }
+                let cells = (f64::from(position - origin) / physical_cell).round();
+                origin + (cells * physical_cell).round().to_i32()
+            };
+            IVec2::new(
+                snap_axis(physical_position.x, physical_bounds.min.x, logical_cell.x),
+                snap_axis(physical_position.y, physical_bounds.min.y, logical_cell.y),
+            )
+        },
+    }
+}
 
-        let mut physical_x = physical_saved_x;
-        let mut physical_y = physical_saved_y;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DEFAULT_SCALE_FACTOR;
+    use crate::constants::MIN_RESTORED_OPACITY;
+    use crate::constants::SCALE_FACTOR_EPSILON;
+    use crate::persistence::RestoreBounds;
+    use crate::test_support;
 
-        if physical_x + physical_outer_width.to_i32() > physical_monitor_right {
-            physical_x = physical_monitor_right - physical_outer_width.to_i32();
+    fn monitor(index: usize, scale: f64) -> MonitorInfo {
+        MonitorInfo {
+            scale,
+            physical_size: UVec2::new(3840, 2160),
+            ..test_support::monitor(index, 0)
         }
-        if physical_y + physical_outer_height.to_i32() > physical_monitor_bottom {
-            physical_y = physical_monitor_bottom - physical_outer_height.to_i32();
+    }
+
+    fn saved_state(logical_size: UVec2, scale: f64) -> WindowState {
+        WindowState {
+            scale,
+            app_name: "test-app".to_string(),
+            ..WindowState::new(logical_size)
         }
-        physical_x = physical_x.max(target_info.physical_position.x);
-        physical_y = physical_y.max(target_info.physical_position.y);
+    }
 
-        if physical_x != physical_saved_x || physical_y != physical_saved_y {
-            debug!(
-                "[clamp_position_to_monitor] Clamped: ({physical_saved_x}, {physical_saved_y}) -> ({physical_x}, {physical_y}) for outer size {physical_outer_width}x{physical_outer_height}"
-            );
+    /// Logical size that saving a window of `physical_size` at `scale` records.
+    fn saved_logical_size(physical_size: UVec2, scale: f64) -> UVec2 {
+        (physical_size.as_dvec2() / scale).round().as_uvec2()
+    }
+
+    #[test]
+    fn fractional_scale_round_trip_stays_within_one_pixel() {
+        let fractional_info = monitor(0, 1.5);
+        let standard_info = monitor(1, DEFAULT_SCALE_FACTOR);
+        let physical_original_size = UVec2::new(1501, 1001);
+
+        let logical_size = saved_logical_size(physical_original_size, fractional_info.scale);
+        let on_standard = compute_target_position(
+            &saved_state(logical_size, fractional_info.scale),
+            &standard_info,
+            None,
+            UVec2::ZERO,
+            fractional_info.scale,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+
+        let logical_size = saved_logical_size(on_standard.physical_size, standard_info.scale);
+        let on_fractional = compute_target_position(
+            &saved_state(logical_size, standard_info.scale),
+            &fractional_info,
+            None,
+            UVec2::ZERO,
+            standard_info.scale,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+
+        let drift =
+            (on_fractional.physical_size.as_ivec2() - physical_original_size.as_ivec2()).abs();
+        assert!(
+            drift.max_element() <= 1,
+            "expected {physical_original_size} within 1px, got {}",
+            on_fractional.physical_size
+        );
+    }
+
+    #[test]
+    fn compensation_rounds_instead_of_truncating() {
+        let mut target_position = compute_target_position(
+            &saved_state(UVec2::new(1001, 667), DEFAULT_SCALE_FACTOR),
+            &monitor(0, DEFAULT_SCALE_FACTOR),
+            None,
+            UVec2::ZERO,
+            1.5,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+        target_position.physical_position = Some(IVec2::new(101, 33));
+
+        assert_eq!(target_position.compensated_size(), UVec2::new(1502, 1001));
+        assert_eq!(
+            target_position.compensated_position(),
+            Some(IVec2::new(152, 50))
+        );
+    }
+
+    #[test]
+    fn fullscreen_following_current_monitor_keeps_current_selection() {
+        let fullscreen_state = |follows_current_monitor| WindowState {
+            saved_window_mode: SavedWindowMode::BorderlessFullscreen,
+            follows_current_monitor,
+            ..saved_state(UVec2::new(400, 300), DEFAULT_SCALE_FACTOR)
+        };
+        let window_mode = |follows_current_monitor| {
+            compute_target_position(
+                &fullscreen_state(follows_current_monitor),
+                &monitor(1, DEFAULT_SCALE_FACTOR),
+                None,
+                UVec2::ZERO,
+                DEFAULT_SCALE_FACTOR,
+                None,
+                None,
+                SCALE_FACTOR_EPSILON,
+                Platform::Windows,
+            )
+            .window_mode()
+        };
+
+        assert_eq!(
+            window_mode(true),
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        );
+        assert_eq!(
+            window_mode(false),
+            WindowMode::BorderlessFullscreen(MonitorSelection::Index(1))
+        );
+    }
+
+    #[test]
+    fn higher_to_lower_applies_size_when_scale_change_never_arrives() {
+        let mut target_position = compute_target_position(
+            &saved_state(UVec2::new(800, 600), DEFAULT_SCALE_FACTOR),
+            &monitor(0, DEFAULT_SCALE_FACTOR),
+            None,
+            UVec2::ZERO,
+            2.0,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+        target_position.monitor_scale_strategy =
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange);
+
+        for _ in 1..SCALE_CHANGE_TIMEOUT_FRAMES {
+            target_position.advance_scale_change_wait(false);
         }
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange)
+        );
 
-        IVec2::new(physical_x, physical_y)
-    } else {
-        IVec2::new(physical_saved_x, physical_saved_y)
+        target_position.advance_scale_change_wait(false);
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize)
+        );
+    }
+
+    #[test]
+    fn higher_to_lower_applies_size_on_scale_change() {
+        let mut target_position = compute_target_position(
+            &saved_state(UVec2::new(800, 600), DEFAULT_SCALE_FACTOR),
+            &monitor(0, DEFAULT_SCALE_FACTOR),
+            None,
+            UVec2::ZERO,
+            2.0,
+            None,
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+        target_position.monitor_scale_strategy =
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::WaitingForScaleChange);
+
+        target_position.advance_scale_change_wait(true);
+        assert_eq!(
+            target_position.monitor_scale_strategy,
+            MonitorScaleStrategy::HigherToLower(WindowRestoreState::ApplySize)
+        );
+    }
+
+    #[test]
+    fn clamp_keeps_invisible_border_off_the_monitor() {
+        // Snapped to the left edge at 150%: the outer position sits 11px left of the monitor.
+        let saved_window_state = WindowState {
+            logical_position: Some((-7, 0)),
+            logical_invisible_border: Some((7, 0)),
+            ..saved_state(UVec2::new(400, 300), 1.5)
+        };
+        let target_position = compute_target_position(
+            &saved_window_state,
+            &monitor(0, 1.5),
+            saved_window_state.logical_position,
+            UVec2::ZERO,
+            1.5,
+            Some(UVec2::new(800, 600)),
+            None,
+            SCALE_FACTOR_EPSILON,
+            Platform::Windows,
+        );
+
+        assert_eq!(target_position.physical_position, Some(IVec2::new(-11, 0)));
+    }
+
+    #[test]
+    fn only_translucent_opacity_is_applied_and_never_below_minimum() {
+        let target_for = |opacity: f32| {
+            compute_target_position(
+                &WindowState {
+                    opacity,
+                    ..saved_state(UVec2::new(800, 600), DEFAULT_SCALE_FACTOR)
+                },
+                &monitor(0, DEFAULT_SCALE_FACTOR),
+                None,
+                UVec2::ZERO,
+                DEFAULT_SCALE_FACTOR,
+                None,
+                None,
+                SCALE_FACTOR_EPSILON,
+                Platform::Windows,
+            )
+            .opacity
+        };
+
+        assert_eq!(target_for(FULLY_OPAQUE), None);
+        assert_eq!(target_for(0.5), Some(0.5));
+        assert_eq!(target_for(0.0), Some(MIN_RESTORED_OPACITY));
+    }
+
+    #[test]
+    fn separate_frames_sequences_only_mode_changes() {
+        let sequenced_state = |saved_window_mode: SavedWindowMode| {
+            let mut target_position = compute_target_position(
+                &WindowState {
+                    saved_window_mode,
+                    ..saved_state(UVec2::new(800, 600), DEFAULT_SCALE_FACTOR)
+                },
+                &monitor(0, DEFAULT_SCALE_FACTOR),
+                None,
+                UVec2::ZERO,
+                DEFAULT_SCALE_FACTOR,
+                None,
+                None,
+                SCALE_FACTOR_EPSILON,
+                Platform::MacOs,
+            );
+            target_position.apply_mode_sequencing(ModeSequencing::SeparateFrames);
+            target_position.fullscreen_restore_state
+        };
+
+        assert_eq!(sequenced_state(SavedWindowMode::Windowed), None);
+        assert_eq!(
+            sequenced_state(SavedWindowMode::Maximized),
+            Some(FullscreenRestoreState::MoveToMonitor)
+        );
+        assert_eq!(
+            sequenced_state(SavedWindowMode::BorderlessFullscreen),
+            Some(FullscreenRestoreState::MoveToMonitor)
+        );
+    }
+
+    #[test]
+    fn snapping_moves_near_edges_flush_and_rounds_to_the_grid() {
+        let target_info = monitor(0, 2.0);
+        let mut saved_window_state = saved_state(UVec2::new(800, 600), 2.0);
+        saved_window_state.logical_position = Some((5, 1000));
+        let snapped = |snap_policy| {
+            let mut target_position = compute_target_position(
+                &saved_window_state,
+                &target_info,
+                saved_window_state.logical_position,
+                UVec2::ZERO,
+                2.0,
+                None,
+                None,
+                SCALE_FACTOR_EPSILON,
+                Platform::MacOs,
+            );
+            target_position.apply_snap_policy(
+                snap_policy,
+                &saved_window_state,
+                &target_info,
+                UVec2::ZERO,
+            );
+            target_position.physical_position
+        };
+
+        // Clamping pulls the bottom edge onto the monitor; snapping then pulls the left edge
+        // 10 physical pixels in onto the monitor edge.
+        assert_eq!(snapped(SnapPolicy::Disabled), Some(IVec2::new(10, 960)));
+        assert_eq!(snapped(SnapPolicy::Edges), Some(IVec2::new(0, 960)));
+        // A 100 logical cell is 200 physical at 2x; the bottom-aligned y rounds up past the
+        // monitor and is clamped back.
+        assert_eq!(
+            snapped(SnapPolicy::Grid(UVec2::new(100, 100))),
+            Some(IVec2::new(0, 960))
+        );
+        assert_eq!(
+            snapped(SnapPolicy::Grid(UVec2::new(0, 400))),
+            Some(IVec2::new(10, 800))
+        );
+    }
+
+    #[test]
+    fn maximized_target_keeps_its_clamped_restore_bounds() {
+        let restore_size = |saved_window_mode: SavedWindowMode| {
+            compute_target_position(
+                &WindowState {
+                    saved_window_mode,
+                    restore_bounds: Some(RestoreBounds {
+                        logical_position: Some((40, 30)),
+                        logical_width:    1200,
+                        logical_height:   200,
+                    }),
+                    ..saved_state(UVec2::new(3840, 2160), DEFAULT_SCALE_FACTOR)
+                },
+                &monitor(0, DEFAULT_SCALE_FACTOR),
+                None,
+                UVec2::ZERO,
+                DEFAULT_SCALE_FACTOR,
+                Some(UVec2::new(400, 300)),
+                None,
+                SCALE_FACTOR_EPSILON,
+                Platform::Windows,
+            )
+            .logical_restore_size
+        };
+
+        assert_eq!(
+            restore_size(SavedWindowMode::Maximized),
+            Some(UVec2::new(1200, 300))
+        );
+        assert_eq!(restore_size(SavedWindowMode::Windowed), None);
     }
 }
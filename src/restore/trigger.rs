@@ -0,0 +1,43 @@
+//! `RestoreTrigger::Manual`: saved geometry is loaded at startup, but nothing is applied
+//! until the app calls [`trigger_restore`](crate::trigger_restore).
+
+use bevy::prelude::*;
+
+use crate::restore_window_config::RestoreTrigger;
+
+/// Whether loaded restores may be applied to their windows.
+#[derive(Resource)]
+pub(crate) struct RestoreGate {
+    triggered: bool,
+}
+
+impl RestoreGate {
+    /// Open from the start under `RestoreTrigger::Startup`, closed under `Manual`.
+    pub(crate) fn new(restore_trigger: RestoreTrigger) -> Self {
+        Self {
+            triggered: restore_trigger == RestoreTrigger::Startup,
+        }
+    }
+
+    /// Let held restores apply. Returns `false` when they already could.
+    pub(crate) const fn trigger(&mut self) -> bool { !std::mem::replace(&mut self.triggered, true) }
+}
+
+/// Run condition: restores may be applied.
+pub(crate) fn restore_triggered(restore_gate: Res<RestoreGate>) -> bool { restore_gate.triggered }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_gate_opens_once() {
+        let mut restore_gate = RestoreGate::new(RestoreTrigger::Manual);
+        assert!(!restore_gate.triggered);
+        assert!(restore_gate.trigger());
+        assert!(restore_gate.triggered);
+        assert!(!restore_gate.trigger());
+
+        assert!(!RestoreGate::new(RestoreTrigger::Startup).trigger());
+    }
+}
@@ -3,22 +3,25 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy::window::WindowMode;
 use bevy::window::WindowPosition;
-use bevy::winit::WINIT_WINDOWS;
+use bevy::window::WindowResized;
 
+use super::RestorePhase;
 use super::target_position;
-use super::target_position::MonitorResolutionSource;
 use super::target_position::RestoreDiagnostics;
 use super::target_position::TargetPosition;
 use crate::Platform;
 use crate::WindowKey;
-use crate::constants::DEFAULT_SCALE_FACTOR;
-use crate::constants::PRIMARY_MONITOR_INDEX;
 use crate::monitors::CurrentMonitor;
+use crate::monitors::MonitorInfo;
 use crate::monitors::Monitors;
 use crate::persistence;
 #[cfg(all(target_os = "windows", feature = "workaround-winit-3124"))]
 use crate::persistence::SavedWindowMode;
+use crate::persistence::WindowState;
+use crate::restore_window_config::RestoreExecution;
 use crate::restore_window_config::RestoreWindowConfig;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
 
 /// Window decoration dimensions (title bar, borders).
 struct WindowDecoration {
@@ -26,14 +29,51 @@ struct WindowDecoration {
     physical_height: u32,
 }
 
+impl WindowDecoration {
+    const fn is_zero(&self) -> bool { self.physical_width == 0 && self.physical_height == 0 }
+}
+
+impl From<UVec2> for WindowDecoration {
+    fn from(physical_decoration: UVec2) -> Self {
+        Self {
+            physical_width:  physical_decoration.x,
+            physical_height: physical_decoration.y,
+        }
+    }
+}
+
+/// Whether the startup decoration measurement is final.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DecorationMeasurement {
+    /// Measured as nonzero at startup, or already re-measured once.
+    Final,
+    /// Measured as 0x0 at startup; re-measure on the first resize.
+    AwaitingResize,
+}
+
 /// Information from winit captured at startup.
 #[derive(Resource)]
 pub(crate) struct WinitInfo {
     starting_monitor_index: usize,
     window_decoration:      WindowDecoration,
+    decoration_measurement: DecorationMeasurement,
 }
 
 impl WinitInfo {
+    /// Startup measurement of an undecorated window on the first monitor, for tests that
+    /// run without winit.
+    #[cfg(test)]
+    pub(crate) const fn undecorated() -> Self {
+        Self {
+            starting_monitor_index: crate::constants::PRIMARY_MONITOR_INDEX,
+            window_decoration:      WindowDecoration {
+                physical_width:  0,
+                physical_height: 0,
+            },
+            decoration_measurement: DecorationMeasurement::Final,
+        }
+    }
+
     /// Get window decoration dimensions as a `UVec2`.
     #[must_use]
     pub(crate) const fn physical_decoration(&self) -> UVec2 {
@@ -59,7 +99,9 @@ pub(crate) struct X11FrameCompensated;
 /// # Panics
 ///
 /// Panics if no monitors are available (e.g., laptop lid closed at startup).
-/// Window management requires at least one monitor to function.
+/// Window management requires at least one monitor to function. A primary window spawned
+/// later is only measured once a monitor is connected, see
+/// `primary_window_awaiting_restore`.
 pub(crate) fn init_winit_info(
     mut commands: Commands,
     window_entity: Single<Entity, With<PrimaryWindow>>,
@@ -71,100 +113,182 @@ pub(crate) fn init_winit_info(
         "No monitors available - cannot initialize window manager without a display"
     );
 
-    WINIT_WINDOWS.with(|winit_windows| {
-        let winit_windows = winit_windows.borrow();
-        if let Some(winit_window) = winit_windows.get_window(*window_entity) {
-            let physical_outer_size = winit_window.outer_size();
-            let physical_inner_size = winit_window.inner_size();
-            let physical_decoration = WindowDecoration {
-                physical_width: physical_outer_size
-                    .width
-                    .saturating_sub(physical_inner_size.width),
-                physical_height: physical_outer_size
-                    .height
-                    .saturating_sub(physical_inner_size.height),
-            };
-
-            let physical_position = winit_window.outer_position().map_or(
-                IVec2::ZERO,
-                |position| IVec2::new(position.x, position.y),
-            );
+    let Some((physical_decoration, starting_monitor)) =
+        measure_startup(&LiveWinitWindows, *window_entity, &monitors)
+    else {
+        return;
+    };
+    let starting_monitor_index = starting_monitor.index;
 
-            debug!(
-                "[init_winit_info] outer_position={physical_position:?} platform={:?}",
-                Platform::detect()
-            );
+    commands.entity(*window_entity).insert(CurrentMonitor {
+        monitor_info:          starting_monitor,
+        effective_window_mode: WindowMode::Windowed,
+    });
+
+    // Some platforms report the window undecorated until it is first shown and
+    // resized, so a 0x0 decoration is re-measured by `refresh_window_decoration`.
+    let decoration_measurement = if physical_decoration.is_zero() {
+        DecorationMeasurement::AwaitingResize
+    } else {
+        DecorationMeasurement::Final
+    };
+    commands.insert_resource(WinitInfo {
+        starting_monitor_index,
+        window_decoration: physical_decoration,
+        decoration_measurement,
+    });
+}
+
+/// Measure the window's decoration and the monitor it starts on. `None` until the window
+/// has a winit window.
+///
+/// The starting monitor is the one winit reports the window on, or the monitor closest to
+/// the window's position when winit does not know it.
+fn measure_startup(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    monitors: &Monitors,
+) -> Option<(WindowDecoration, MonitorInfo)> {
+    let physical_decoration = WindowDecoration::from(winit_windows.physical_decoration(entity)?);
+    let physical_position = winit_windows.outer_position(entity).unwrap_or(IVec2::ZERO);
 
-            let starting_monitor = winit_window
-                .current_monitor()
-                .and_then(|current_monitor| {
-                    let physical_monitor_position = current_monitor.position();
-                    let monitor_info =
-                        monitors.at(physical_monitor_position.x, physical_monitor_position.y);
-                    debug!(
-                        "[init_winit_info] current_monitor() position=({}, {}) -> index={:?}",
-                        physical_monitor_position.x,
-                        physical_monitor_position.y,
-                        monitor_info.map(|monitor| monitor.index)
-                    );
-                    monitor_info.copied()
-                })
-                .unwrap_or_else(|| {
-                    debug!(
-                        "[init_winit_info] current_monitor() unavailable, falling back to closest_to({}, {})",
-                        physical_position.x,
-                        physical_position.y
-                    );
-                    *monitors.closest_to(physical_position.x, physical_position.y)
-                });
-            let starting_monitor_index = starting_monitor.index;
+    debug!(
+        "[measure_startup] outer_position={physical_position:?} platform={:?}",
+        Platform::detect()
+    );
 
+    let starting_monitor = winit_windows
+        .current_monitor_position(entity)
+        .and_then(|physical_monitor_position| {
+            let monitor_info = monitors.at(physical_monitor_position.x, physical_monitor_position.y);
+            debug!(
+                "[measure_startup] current_monitor() position={physical_monitor_position} -> index={:?}",
+                monitor_info.map(|monitor| monitor.index)
+            );
+            monitor_info.cloned()
+        })
+        .unwrap_or_else(|| {
             debug!(
-                "[init_winit_info] decoration={}x{} position=({}, {}) starting_monitor={starting_monitor_index}",
-                physical_decoration.physical_width,
-                physical_decoration.physical_height,
-                physical_position.x,
-                physical_position.y,
+                "[measure_startup] current_monitor() unavailable, falling back to closest_to({}, {})",
+                physical_position.x, physical_position.y
             );
+            monitors
+                .closest_to(physical_position.x, physical_position.y)
+                .clone()
+        });
 
-            commands.entity(*window_entity).insert(CurrentMonitor {
-                monitor_info:   starting_monitor,
-                effective_window_mode: WindowMode::Windowed,
-            });
+    debug!(
+        "[measure_startup] decoration={}x{} position=({}, {}) starting_monitor={}",
+        physical_decoration.physical_width,
+        physical_decoration.physical_height,
+        physical_position.x,
+        physical_position.y,
+        starting_monitor.index,
+    );
+    Some((physical_decoration, starting_monitor))
+}
 
-            commands.insert_resource(WinitInfo {
-                starting_monitor_index,
-                window_decoration: physical_decoration,
-            });
-        }
-    });
+/// Warn once, after startup, when the app has no primary window.
+///
+/// Restore measures decorations and the starting monitor on the primary window, so an app
+/// that sets `WindowPlugin { primary_window: None }` would otherwise see nothing restored
+/// without any hint why.
+pub(crate) fn warn_without_primary_window(primary_window: Query<(), With<PrimaryWindow>>) {
+    if primary_window.is_empty() {
+        warn!(
+            "[warn_without_primary_window] No window has `PrimaryWindow` after startup. \
+             Restore is driven by the primary window, so no window is restored, and \
+             `ManagedWindow`s open where the OS places them, until one is spawned. Keep \
+             `WindowPlugin::primary_window` set, or add `RestoreTarget` to the app's main window."
+        );
+    }
+}
+
+/// Run condition: a primary window exists that startup restore has not handled yet,
+/// because the app spawned it after `PreStartup`, e.g. one tagged
+/// [`RestoreTarget`](crate::RestoreTarget).
+///
+/// Waits while no monitor is connected (e.g. laptop lid closed), where `init_winit_info`
+/// cannot pick a starting monitor.
+pub(crate) fn primary_window_awaiting_restore(
+    winit_info: Option<Res<WinitInfo>>,
+    primary_window: Query<(), With<PrimaryWindow>>,
+    monitors: Res<Monitors>,
+) -> bool {
+    winit_info.is_none() && !primary_window.is_empty() && !monitors.is_empty()
+}
+
+/// Run condition: the decoration read as 0x0 at startup and has not been re-measured.
+pub(crate) fn decoration_awaiting_resize(winit_info: Option<Res<WinitInfo>>) -> bool {
+    winit_info.is_some_and(|winit_info| {
+        winit_info.decoration_measurement == DecorationMeasurement::AwaitingResize
+    })
+}
+
+/// Re-measure the primary window's decoration on its first resize after startup.
+///
+/// Only runs when startup measured 0x0, which would otherwise leave outer-size clamping
+/// without the title bar. Updates at most once, so later decoration changes (e.g. a
+/// restore turning decorations off) are not mistaken for the real startup decoration.
+pub(crate) fn refresh_window_decoration(
+    mut resized: MessageReader<WindowResized>,
+    window_entity: Single<Entity, With<PrimaryWindow>>,
+    mut winit_info: ResMut<WinitInfo>,
+    _: NonSendMarker,
+) {
+    if !resized
+        .read()
+        .any(|window_resized| window_resized.window == *window_entity)
+    {
+        return;
+    }
+
+    remeasure_decoration(&LiveWinitWindows, *window_entity, &mut winit_info);
+}
+
+/// Replace the startup decoration in `winit_info` with the one winit reports now.
+fn remeasure_decoration(
+    winit_windows: &impl WinitWindowAccess,
+    entity: Entity,
+    winit_info: &mut WinitInfo,
+) {
+    let Some(physical_decoration) = winit_windows.physical_decoration(entity) else {
+        return;
+    };
+    let physical_decoration = WindowDecoration::from(physical_decoration);
+
+    debug!(
+        "[refresh_window_decoration] decoration 0x0 -> {}x{}",
+        physical_decoration.physical_width, physical_decoration.physical_height
+    );
+    winit_info.window_decoration = physical_decoration;
+    winit_info.decoration_measurement = DecorationMeasurement::Final;
 }
 
 /// Load saved window state and insert `TargetPosition` on the primary window entity.
+///
+/// Without saved state and under `DefaultPlacement::OsDefault`, no `TargetPosition` is
+/// inserted and the window keeps its startup geometry; it is only made visible.
 pub(crate) fn load_target_position(
     mut commands: Commands,
-    window_entity: Single<Entity, With<PrimaryWindow>>,
+    primary_window: Single<(Entity, &Window), With<PrimaryWindow>>,
     monitors: Res<Monitors>,
     winit_info: Res<WinitInfo>,
     mut restore_window_config: ResMut<RestoreWindowConfig>,
     platform: Res<Platform>,
 ) {
-    if let Some(all_states) = persistence::load_all_states(&restore_window_config.path) {
+    if let Some(all_states) =
+        persistence::load_all_states_or_notify(&restore_window_config, &mut commands)
+    {
         restore_window_config.loaded_states = all_states;
     }
 
-    let Some(window_state) = restore_window_config
-        .loaded_states
-        .get(&WindowKey::Primary)
-        .cloned()
+    let (entity, window) = *primary_window;
+    let Some(window_state) =
+        primary_window_state(&restore_window_config, &monitors, window, *platform)
     else {
         debug!("[load_target_position] No saved bevy_window_manager state, showing window");
-        commands.queue(|world: &mut World| {
-            let mut query = world.query_filtered::<&mut Window, With<PrimaryWindow>>();
-            if let Some(mut window) = query.iter_mut(world).next() {
-                window.visible = true;
-            }
-        });
+        show_primary_window(&mut commands);
         return;
     };
 
@@ -179,33 +303,48 @@ pub(crate) fn load_target_position(
     );
 
     let starting_monitor_index = winit_info.starting_monitor_index;
-    let starting_scale = monitors
+    let starting_monitor = monitors
         .by_index(starting_monitor_index)
-        .map_or(DEFAULT_SCALE_FACTOR, |monitor| monitor.scale);
+        .unwrap_or_else(|| monitors.first());
+    let starting_scale = starting_monitor.scale;
 
-    let resolved_monitor = target_position::resolve_target_monitor_and_position(
-        window_state.monitor,
-        window_state.logical_position,
+    let geometry_restore = restore_window_config.effective_geometry_restore();
+    let Some((resolved_monitor, window_state)) = target_position::resolve_restore_target(
+        &window_state,
         &monitors,
-    );
-    if matches!(
-        resolved_monitor.monitor_resolution_source,
-        MonitorResolutionSource::FallbackToPrimary
-    ) {
-        warn!(
-            "[load_target_position] Target monitor {} not found, falling back to monitor {PRIMARY_MONITOR_INDEX}",
-            window_state.monitor,
-        );
-    }
+        &restore_window_config,
+        geometry_restore,
+        *platform,
+        starting_monitor,
+        window,
+        None,
+    ) else {
+        show_primary_window(&mut commands);
+        return;
+    };
 
-    let target_position = target_position::compute_target_position(
+    let mut target_position = target_position::compute_target_position(
         &window_state,
         resolved_monitor.monitor_info,
         resolved_monitor.logical_position,
         winit_info.physical_decoration(),
         starting_scale,
+        restore_window_config.min_size,
+        restore_window_config.max_size,
+        restore_window_config.scale_factor_epsilon,
         *platform,
     );
+    target_position.apply_snap_policy(
+        restore_window_config.snap_policy,
+        &window_state,
+        resolved_monitor.monitor_info,
+        winit_info.physical_decoration(),
+    );
+    target_position.minimize_after_restore =
+        restore_window_config.minimize_after_restore(&window_state);
+    target_position.focus_after_restore = restore_window_config.focus_after_restore(&window_state);
+    target_position.geometry_restore = geometry_restore;
+    target_position.apply_mode_sequencing(restore_window_config.mode_sequencing);
 
     debug!(
         "[load_target_position] Starting monitor={starting_monitor_index} scale={starting_scale}, Target monitor={} scale={}, monitor_scale_strategy={:?}, position={:?}",
@@ -223,15 +362,9 @@ pub(crate) fn load_target_position(
         debug!(
             "[load_target_position] Windows exclusive fullscreen: showing window for surface creation"
         );
-        commands.queue(|world: &mut World| {
-            let mut query = world.query_filtered::<&mut Window, With<PrimaryWindow>>();
-            if let Some(mut window) = query.iter_mut(world).next() {
-                window.visible = true;
-            }
-        });
+        show_primary_window(&mut commands);
     }
 
-    let entity = *window_entity;
     let is_fullscreen = window_state.saved_window_mode.is_fullscreen();
     let restore_diagnostics = RestoreDiagnostics {
         starting_monitor_index,
@@ -239,15 +372,59 @@ pub(crate) fn load_target_position(
         target_scale: target_position.target_scale,
         monitor_scale_strategy: target_position.monitor_scale_strategy,
     };
+    let restore_started = target_position.restore_started(entity, WindowKey::Primary);
+    let restore_phase = RestorePhase::of(&target_position);
     commands
         .entity(entity)
-        .insert((target_position, restore_diagnostics));
+        .insert((target_position, restore_diagnostics, restore_phase));
+    commands.trigger(restore_started);
 
     if is_fullscreen || !platform.needs_frame_compensation() {
         commands.entity(entity).insert(X11FrameCompensated);
     }
 }
 
+/// The primary window's saved state after the `on_load` hook, or without one, the state
+/// that places it per `DefaultPlacement`.
+fn primary_window_state(
+    restore_window_config: &RestoreWindowConfig,
+    monitors: &Monitors,
+    window: &Window,
+    platform: Platform,
+) -> Option<WindowState> {
+    if let Some(mut window_state) = restore_window_config
+        .loaded_states
+        .get(&WindowKey::Primary)
+        .cloned()
+    {
+        // Before the target monitor is resolved, so a hook's monitor index is checked
+        // against `Monitors` like a saved one.
+        restore_window_config.run_on_load(&mut window_state);
+        return Some(window_state);
+    }
+    let window_state = target_position::default_placement_state(
+        restore_window_config.default_placement,
+        monitors,
+        window,
+        platform,
+    )?;
+    debug!(
+        "[primary_window_state] No saved bevy_window_manager state, placing window per {:?}",
+        restore_window_config.default_placement
+    );
+    Some(window_state)
+}
+
+/// Make the primary window visible once queued commands apply.
+fn show_primary_window(commands: &mut Commands) {
+    commands.queue(|world: &mut World| {
+        let mut query = world.query_filtered::<&mut Window, With<PrimaryWindow>>();
+        if let Some(mut window) = query.iter_mut(world).next() {
+            window.visible = true;
+        }
+    });
+}
+
 /// Move the primary window to the target monitor for fullscreen restore on X11.
 ///
 /// Body is platform-neutral Bevy code; only the `add_systems` registration in
@@ -257,6 +434,7 @@ pub(crate) fn move_to_target_monitor(
     mut window: Single<&mut Window, With<PrimaryWindow>>,
     targets: Query<&TargetPosition, With<PrimaryWindow>>,
     platform: Res<Platform>,
+    restore_window_config: Res<RestoreWindowConfig>,
 ) {
     if !platform.is_x11() {
         return;
@@ -270,8 +448,105 @@ pub(crate) fn move_to_target_monitor(
         return;
     }
 
+    if restore_window_config.restore_execution == RestoreExecution::DryRun {
+        info!(
+            "[move_to_target_monitor] Dry run: X11 fullscreen would set position={:?}",
+            target_position.physical_position
+        );
+        return;
+    }
     if let Some(position) = target_position.physical_position {
         debug!("[move_to_target_monitor] X11 fullscreen: setting position={position:?}");
         window.position = WindowPosition::At(position);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::DecorationMeasurement;
+    use super::WinitInfo;
+    use super::measure_startup;
+    use super::remeasure_decoration;
+    use crate::monitors::Monitors;
+    use crate::test_support::FakeWinitWindow;
+    use crate::test_support::monitor;
+
+    fn monitors() -> Monitors {
+        Monitors {
+            list: vec![monitor(0, 0), monitor(1, 1920)],
+        }
+    }
+
+    fn window_on_second_monitor() -> FakeWinitWindow {
+        FakeWinitWindow {
+            physical_outer_position: Some(IVec2::new(2000, 100)),
+            physical_outer_size: UVec2::new(1280, 748),
+            physical_inner_size: UVec2::new(1280, 720),
+            physical_monitor_position: Some(IVec2::new(1920, 0)),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn startup_measures_decoration_and_winit_monitor() {
+        let measured = measure_startup(
+            &window_on_second_monitor(),
+            Entity::PLACEHOLDER,
+            &monitors(),
+        );
+
+        assert_eq!(
+            measured.map(|(physical_decoration, starting_monitor)| {
+                (
+                    physical_decoration.physical_width,
+                    physical_decoration.physical_height,
+                    starting_monitor.index,
+                )
+            }),
+            Some((0, 28, 1))
+        );
+    }
+
+    #[test]
+    fn startup_monitor_falls_back_to_closest_to_position() {
+        let unknown_monitor = FakeWinitWindow {
+            physical_monitor_position: None,
+            ..window_on_second_monitor()
+        };
+        let measured = measure_startup(&unknown_monitor, Entity::PLACEHOLDER, &monitors());
+        assert_eq!(
+            measured.map(|(_, starting_monitor)| starting_monitor.index),
+            Some(1)
+        );
+
+        let unknown_position = FakeWinitWindow {
+            physical_outer_position: None,
+            ..unknown_monitor
+        };
+        let measured = measure_startup(&unknown_position, Entity::PLACEHOLDER, &monitors());
+        assert_eq!(
+            measured.map(|(_, starting_monitor)| starting_monitor.index),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn decoration_measured_at_zero_is_remeasured() {
+        let mut winit_info = WinitInfo::undecorated();
+        winit_info.decoration_measurement = DecorationMeasurement::AwaitingResize;
+
+        remeasure_decoration(
+            &window_on_second_monitor(),
+            Entity::PLACEHOLDER,
+            &mut winit_info,
+        );
+
+        assert_eq!(winit_info.window_decoration.physical_height, 28);
+        assert_eq!(
+            winit_info.decoration_measurement,
+            DecorationMeasurement::Final
+        );
+    }
+}
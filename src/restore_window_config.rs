@@ -1,20 +1,464 @@
 //! Restore configuration.
 
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::ops::DerefMut;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use bevy::prelude::*;
 
 use super::WindowKey;
+use super::persistence::MemoryStateStore;
+use super::persistence::ProfileCache;
+use super::persistence::StateFileWriter;
+use super::persistence::StateFormat;
 use super::persistence::WindowState;
+use crate::constants::DEFAULT_PROFILE;
+use crate::constants::DEFAULT_STARTUP_SAVE_DELAY;
+use crate::constants::SCALE_FACTOR_EPSILON;
+#[cfg(target_os = "macos")]
+use crate::macos_stage_manager::StageManagerSetting;
+
+/// What to do when a window's saved monitor is no longer connected.
+///
+/// Common on laptops that are sometimes docked: the external display a window was
+/// saved on is missing at the next launch. Every fallback that restores keeps the
+/// saved size and window mode and centers the window on the chosen monitor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum MonitorFallback {
+    /// Restore onto the primary monitor, see [`Monitors::primary`](crate::Monitors::primary).
+    #[default]
+    FallbackToPrimary,
+    /// Restore onto the monitor closest to the saved position.
+    /// Uses the primary monitor when no position was saved (Wayland).
+    FallbackToClosest,
+    /// Skip the restore and leave the window where the OS placed it.
+    DoNothing,
+}
+
+/// Where the primary window opens when there is no saved state for it, e.g. on first run.
+///
+/// Under the default, [`OsDefault`](Self::OsDefault), a window without saved state is not
+/// restored at all, so it keeps the size and position it was created with. The centering
+/// variants only move the window and keep its startup size;
+/// [`ScaledDefault`](Self::ScaledDefault) also sizes it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum DefaultPlacement {
+    /// Leave the window where the OS places it.
+    #[default]
+    OsDefault,
+    /// Center the window on the primary monitor, see
+    /// [`Monitors::primary`](crate::Monitors::primary).
+    CenterPrimary,
+    /// Center the window on the monitor with this index, or on the primary monitor when
+    /// no monitor has it.
+    CenterMonitor(usize),
+    /// Center the window on the primary monitor at this logical size, so it covers the
+    /// same apparent area at any scale: its physical size is this logical size times the
+    /// monitor's scale, e.g. 800x600 opens at 1600x1200 physical pixels on a 2x display.
+    ScaledDefault(UVec2),
+}
+
+/// Which monitor a saved window is restored onto.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum RestorePreference {
+    /// The monitor the window was saved on, matched by name, then index.
+    #[default]
+    SavedMonitor,
+    /// The monitor under the mouse cursor, keeping the window's saved position relative
+    /// to its monitor. Useful when monitor indices shuffle between launches. Falls back to
+    /// [`Self::SavedMonitor`] when the cursor position is unavailable (Wayland).
+    CursorMonitor,
+}
+
+/// How much window geometry is remembered across monitors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum GeometryMemory {
+    /// Remember only the geometry on the monitor the window was last on.
+    #[default]
+    LastMonitor,
+    /// Also remember the geometry on each named monitor the window has been on, so a
+    /// window restored onto a different monitor gets the size and position it last had
+    /// there. Monitors that report no name are not remembered.
+    PerMonitor,
+}
+
+/// Which parts of a window's saved geometry are restored.
+///
+/// The window mode (fullscreen, maximized) is restored either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum GeometryRestore {
+    /// Restore both the saved position and size. On macOS under Stage Manager, which
+    /// places windows itself, only the size is restored, as with `SizeOnly`.
+    #[default]
+    PositionAndSize,
+    /// Restore the saved size but leave the window where the OS or window manager placed
+    /// it, on the monitor it was created on. Suits tiling window managers, which own
+    /// window placement.
+    SizeOnly,
+    /// Restore the saved position but keep the size the window was created with.
+    PositionOnly,
+}
+
+/// Which saved size a window is restored at when its target monitor's scale differs from
+/// the scale it was saved at.
+///
+/// Has no effect when both monitors share a scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum SizePolicy {
+    /// Restore the saved logical size, so content keeps its apparent size. A window saved
+    /// at 800x600 on a 1x monitor covers 1600x1200 physical pixels on a 2x monitor.
+    #[default]
+    PreserveLogical,
+    /// Restore the saved physical size, so the window covers the same number of pixels. A
+    /// window saved at 800x600 on a 1x monitor is restored at 400x300 logical on a 2x
+    /// monitor, and its content appears half as large.
+    PreservePhysical,
+}
+
+/// Whether a restored window is nudged onto a monitor edge or a grid.
+///
+/// Applies to windowed restores with a known position, after the position is clamped
+/// onto the target monitor. The snapped window always stays within the monitor's usable
+/// area.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum SnapPolicy {
+    /// Restore the saved position unchanged.
+    #[default]
+    Disabled,
+    /// Move each edge that lies within a few logical pixels of the matching monitor edge
+    /// flush against it.
+    Edges,
+    /// Round the position to the nearest multiple of this logical cell size, measured from
+    /// the monitor's top-left corner. A zero component leaves that axis unchanged.
+    Grid(UVec2),
+}
+
+/// Whether a window mode change shares a frame with the geometry change before it.
+///
+/// Fullscreen and maximized restores move the window onto its target monitor and change
+/// its mode. Windowed restores have no mode change and are unaffected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum ModeSequencing {
+    /// Apply the geometry and the mode in the same frame where the platform allows it.
+    /// X11 always separates them for fullscreen.
+    #[default]
+    SameFrame,
+    /// Move the window onto its target monitor, then apply the mode two frames later.
+    /// For window managers that mishandle a mode change arriving with a move or resize.
+    SeparateFrames,
+}
+
+/// What happens to a window when the monitor it is on is disconnected while the app runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum DisconnectedMonitorPolicy {
+    /// Move the window onto the primary monitor, centered and keeping its logical size.
+    /// Fullscreen windows stay fullscreen on the primary monitor.
+    #[default]
+    MoveToPrimary,
+    /// Leave the window alone, for apps that handle disconnects themselves.
+    DoNothing,
+}
+
+/// Whether a window that ends up entirely off every monitor while the app runs is moved
+/// back.
+///
+/// Restore already keeps windows on screen at startup; this covers later changes, such as
+/// a resolution change or a drag past the edge of the desktop. Only windowed windows are
+/// checked, and one is moved only after it has stayed off screen at the same position for
+/// a moment, so a drag in progress is not pulled back mid-gesture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum OffScreenRecovery {
+    /// Leave off-screen windows where they are.
+    #[default]
+    Disabled,
+    /// Center an off-screen window on the monitor closest to it.
+    Enabled,
+}
+
+/// Whether a windowed window that exactly fills its monitor counts as fullscreen.
+///
+/// Clicking the macOS green button fills the screen while `window.mode` stays `Windowed`,
+/// so a window spanning the monitor's full width from its left edge to its bottom edge is
+/// treated as `BorderlessFullscreen`, saved that way, and reported in
+/// [`CurrentMonitor::effective_window_mode`](crate::CurrentMonitor::effective_window_mode).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum GreenButtonDetection {
+    /// Treat a window that fills its monitor as `BorderlessFullscreen`.
+    #[default]
+    Enabled,
+    /// Trust `window.mode`, so a window sized to exactly fill the screen is saved as
+    /// `Windowed` with its exact size.
+    Disabled,
+}
+
+/// Whether window titles are saved and restored.
+///
+/// Off by default because most apps set the title themselves at startup. Enable it when
+/// the title reflects per-session state, such as the open document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum TitleRestore {
+    /// Leave titles out of the state file and keep the title the app sets.
+    #[default]
+    Keep,
+    /// Save each window's title and set it again on restore.
+    ///
+    /// The title bar height used to size restored windows is measured on the window as
+    /// created, with its startup title. Window managers draw the title on a single line
+    /// whatever its length, so restoring a different title does not change it.
+    Restore,
+}
+
+/// Whether a restore changes windows or only logs what it would change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum RestoreExecution {
+    #[default]
+    Apply,
+    /// Compute and `info!`-log the restore, but leave the windows where the OS put them.
+    DryRun,
+}
+
+/// When loaded window state is applied to the windows.
+///
+/// Under [`Manual`](Self::Manual), a launcher or loading screen can show in the window at
+/// its startup geometry, and the window jumps to its saved geometry only once the real
+/// content is ready.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum RestoreTrigger {
+    /// Restore as soon as each window exists.
+    #[default]
+    Startup,
+    /// Load each window's target at startup, but apply none until the app calls
+    /// [`trigger_restore`](crate::trigger_restore). The primary window is not hidden in
+    /// the meantime, and window changes are not saved until the held restores finish.
+    Manual,
+}
+
+/// How to restore a window that was minimized when its state was saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum MinimizedRestore {
+    /// Restore the window un-minimized at its last geometry before it was minimized.
+    #[default]
+    Unminimize,
+    /// Restore the window's geometry, then minimize it again.
+    Minimize,
+}
+
+/// Whether a restored window is given focus once its restore completes.
+///
+/// Some window managers bring restored windows up without focus. Focus is requested
+/// through Bevy's `Window::focused`, which calls winit's `focus_window`. Platforms that
+/// restrict focus stealing may ignore the request: Windows only lets the foreground app
+/// take focus, Wayland compositors generally refuse it, and macOS activates the app only
+/// when it is allowed to. A window restored minimized is not focused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum FocusOnRestore {
+    /// Leave focus to the OS.
+    #[default]
+    Disabled,
+    /// Focus every restored window.
+    Enabled,
+    /// Focus a restored window only if it was focused when its state was saved, so an app
+    /// that was in the background when it quit stays there. States saved by versions
+    /// without focus tracking count as unfocused.
+    WhenSavedFocused,
+}
+
+/// When window state changes are written to the state file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum SavePolicy {
+    /// Write the state file whenever a window's position, size, mode, or monitor changes.
+    #[default]
+    Continuous,
+    /// Track changes in memory and write the state file once when the app exits.
+    ///
+    /// Avoids disk writes during long sessions. State is lost if the process is killed
+    /// without a clean `AppExit`.
+    OnExit,
+}
+
+/// App closure run on each window's state before it is saved or after it is loaded.
+pub(crate) type WindowStateHook = Arc<dyn Fn(&mut WindowState) + Send + Sync>;
+
+/// Restore and save options set through
+/// [`WindowManagerPluginBuilder`](crate::WindowManagerPluginBuilder), which holds them
+/// until they move into the `RestoreWindowConfig`.
+#[derive(Clone)]
+pub(crate) struct RestoreOptions {
+    /// Additional locations every save is written to and loads fall back on, in order.
+    pub(crate) mirror_paths:           Vec<PathBuf>,
+    /// Which monitor windows are restored onto.
+    pub(crate) restore_preference:     RestorePreference,
+    /// Behavior when the saved monitor is missing.
+    pub(crate) monitor_fallback:       MonitorFallback,
+    /// Where the primary window opens without saved state.
+    pub(crate) default_placement:      DefaultPlacement,
+    /// Whether geometry is remembered per monitor.
+    pub(crate) geometry_memory:        GeometryMemory,
+    /// Which parts of the saved geometry are restored.
+    pub(crate) geometry_restore:       GeometryRestore,
+    /// Whether the saved logical or physical size is restored across scales.
+    pub(crate) size_policy:            SizePolicy,
+    /// Whether restored positions snap to monitor edges or a grid.
+    pub(crate) snap_policy:            SnapPolicy,
+    /// Whether the window mode is applied in a later frame than the geometry.
+    pub(crate) mode_sequencing:        ModeSequencing,
+    /// Behavior for windows saved while minimized.
+    pub(crate) minimized_restore:      MinimizedRestore,
+    /// Whether window titles are saved and restored.
+    pub(crate) title_restore:          TitleRestore,
+    /// Whether a window that fills its monitor counts as fullscreen.
+    pub(crate) green_button_detection: GreenButtonDetection,
+    /// Whether restored windows are focused.
+    pub(crate) focus_on_restore:       FocusOnRestore,
+    /// When window changes are written to the state file.
+    pub(crate) save_policy:            SavePolicy,
+    /// Time after startup during which window changes are not written.
+    pub(crate) startup_save_delay:     Duration,
+    /// Smallest logical size a window is restored at.
+    pub(crate) min_size:               Option<UVec2>,
+    /// Largest logical size a window is restored at.
+    pub(crate) max_size:               Option<UVec2>,
+    /// Largest difference between two scale factors that still counts as the same scale.
+    pub(crate) scale_factor_epsilon:   f64,
+    /// Whether restores are applied or only logged.
+    pub(crate) restore_execution:      RestoreExecution,
+    /// Layout profile that windows are saved under and restored from.
+    pub(crate) profile:                String,
+    /// Run on every window state before it is written.
+    pub(crate) on_save:                Option<WindowStateHook>,
+    /// Run on a window's saved state before it is restored.
+    pub(crate) on_load:                Option<WindowStateHook>,
+    /// Store that replaces the state file, when set.
+    pub(crate) memory_store:           Option<MemoryStateStore>,
+}
+
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        Self {
+            mirror_paths:           Vec::new(),
+            restore_preference:     RestorePreference::default(),
+            monitor_fallback:       MonitorFallback::default(),
+            default_placement:      DefaultPlacement::default(),
+            geometry_memory:        GeometryMemory::default(),
+            geometry_restore:       GeometryRestore::default(),
+            size_policy:            SizePolicy::default(),
+            snap_policy:            SnapPolicy::default(),
+            mode_sequencing:        ModeSequencing::default(),
+            minimized_restore:      MinimizedRestore::default(),
+            title_restore:          TitleRestore::default(),
+            green_button_detection: GreenButtonDetection::default(),
+            focus_on_restore:       FocusOnRestore::default(),
+            save_policy:            SavePolicy::default(),
+            startup_save_delay:     DEFAULT_STARTUP_SAVE_DELAY,
+            min_size:               None,
+            max_size:               None,
+            scale_factor_epsilon:   SCALE_FACTOR_EPSILON,
+            restore_execution:      RestoreExecution::default(),
+            profile:                DEFAULT_PROFILE.to_string(),
+            on_save:                None,
+            on_load:                None,
+            memory_store:           None,
+        }
+    }
+}
 
 /// Configuration for the `RestoreWindowPlugin`.
+///
+/// Derefs to the [`RestoreOptions`] it was built from.
 #[derive(Resource, Clone)]
 pub(crate) struct RestoreWindowConfig {
     /// Full path to the state file.
-    pub(crate) path:          PathBuf,
+    pub(crate) path:                  PathBuf,
+    /// Serialization format used when writing the state file.
+    pub(crate) state_format:          StateFormat,
+    /// Options set through the plugin builder.
+    pub(crate) restore_options:       RestoreOptions,
+    /// Background writer shared by every save.
+    pub(crate) state_file_writer:     StateFileWriter,
+    /// Saved profiles kept in memory, so saves do not read the state file.
+    pub(crate) profile_cache:         ProfileCache,
     /// Snapshot of window states as loaded from the file at startup.
     /// Populated during restore so downstream code can compare intended vs actual state.
     /// Entries persist as a read-only snapshot for the example's File column.
-    pub(crate) loaded_states: HashMap<WindowKey, WindowState>,
+    pub(crate) loaded_states:         HashMap<WindowKey, WindowState>,
+    /// Stage Manager setting shared by every window restored in the same frame.
+    #[cfg(target_os = "macos")]
+    pub(crate) stage_manager_setting: StageManagerSetting,
+}
+
+impl Deref for RestoreWindowConfig {
+    type Target = RestoreOptions;
+
+    fn deref(&self) -> &Self::Target { &self.restore_options }
+}
+
+impl DerefMut for RestoreWindowConfig {
+    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.restore_options }
+}
+
+impl RestoreWindowConfig {
+    /// A config for the state file at `path` with `restore_options`.
+    pub(crate) fn new(path: PathBuf, restore_options: RestoreOptions) -> Self {
+        Self {
+            state_format: StateFormat::from_path(&path),
+            path,
+            restore_options,
+            state_file_writer: StateFileWriter::default(),
+            profile_cache: ProfileCache::default(),
+            loaded_states: HashMap::new(),
+            #[cfg(target_os = "macos")]
+            stage_manager_setting: StageManagerSetting::default(),
+        }
+    }
+
+    /// Whether a window restored from `window_state` should be minimized once it settles.
+    pub(crate) fn minimize_after_restore(&self, window_state: &WindowState) -> bool {
+        window_state.minimized && self.minimized_restore == MinimizedRestore::Minimize
+    }
+
+    /// Whether a window restored from `window_state` should be focused once it settles.
+    pub(crate) fn focus_after_restore(&self, window_state: &WindowState) -> bool {
+        match self.focus_on_restore {
+            FocusOnRestore::Disabled => false,
+            FocusOnRestore::Enabled => true,
+            FocusOnRestore::WhenSavedFocused => window_state.focused,
+        }
+    }
+
+    /// Which parts of the saved geometry restores apply.
+    ///
+    /// Under macOS Stage Manager, which repositions windows itself, `PositionAndSize`
+    /// becomes `SizeOnly`. An explicit `PositionOnly` is left alone.
+    pub(crate) fn effective_geometry_restore(&self) -> GeometryRestore {
+        #[cfg(target_os = "macos")]
+        if self.geometry_restore == GeometryRestore::PositionAndSize
+            && self.stage_manager_setting.enabled()
+        {
+            debug!("[effective_geometry_restore] Stage Manager is on, not restoring position");
+            return GeometryRestore::SizeOnly;
+        }
+        self.geometry_restore
+    }
+
+    /// Run the `on_save` hook, if any, on `window_state`.
+    pub(crate) fn run_on_save(&self, window_state: &mut WindowState) {
+        if let Some(on_save) = &self.on_save {
+            on_save(window_state);
+        }
+    }
+
+    /// Run the `on_load` hook, if any, on `window_state`.
+    pub(crate) fn run_on_load(&self, window_state: &mut WindowState) {
+        if let Some(on_load) = &self.on_load {
+            on_load(window_state);
+        }
+    }
+
+    /// The title to save for `window`. `None` unless titles are restored.
+    pub(crate) fn saved_title(&self, window: &Window) -> Option<String> {
+        (self.title_restore == TitleRestore::Restore).then(|| window.title.clone())
+    }
 }
@@ -0,0 +1,49 @@
+//! One per-frame snapshot of `WindowScaleFactorChanged` messages.
+//!
+//! Scale changes arrive in bursts while a window is dragged across monitors. Systems that
+//! react to them read [`ScaleFactorChanges`] instead of draining the message stream each,
+//! so every consumer sees the same changes in a frame regardless of system order.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::window::WindowScaleFactorChanged;
+
+/// Latest scale factor reported for each window this frame. Empty in frames without a
+/// scale change.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct ScaleFactorChanges {
+    latest: HashMap<Entity, f64>,
+}
+
+impl ScaleFactorChanges {
+    /// Whether any window's scale factor changed this frame.
+    pub(crate) fn any(&self) -> bool { !self.latest.is_empty() }
+
+    /// The last scale factor reported for `entity` this frame, if it changed.
+    pub(crate) fn for_window(&self, entity: Entity) -> Option<f64> {
+        self.latest.get(&entity).copied()
+    }
+}
+
+/// Replace the snapshot with this frame's scale changes, keeping the last one per window.
+pub(crate) fn collect_scale_factor_changes(
+    mut scale_factor_messages: MessageReader<WindowScaleFactorChanged>,
+    mut scale_factor_changes: ResMut<ScaleFactorChanges>,
+) {
+    if scale_factor_messages.is_empty() && !scale_factor_changes.any() {
+        return;
+    }
+    scale_factor_changes.latest.clear();
+    for message in scale_factor_messages.read() {
+        scale_factor_changes
+            .latest
+            .insert(message.window, message.scale_factor);
+    }
+    if scale_factor_changes.any() {
+        debug!(
+            "[collect_scale_factor_changes] {:?}",
+            scale_factor_changes.latest
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Public system sets for ordering app systems around restore and save.
+
+use bevy::prelude::*;
+
+/// System sets the plugin's systems run in, so app systems can be ordered around them.
+///
+/// In `Update`, [`Restore`](Self::Restore) runs before [`Save`](Self::Save).
+///
+/// ```ignore
+/// app.add_systems(Update, arrange_tool_windows.after(WindowManagerSet::Restore))
+///     .add_systems(Update, snap_to_grid.before(WindowManagerSet::Save));
+/// ```
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowManagerSet {
+    /// Loading saved state and applying it to windows: the startup load in `PreStartup`
+    /// and the multi-frame restore in `Update`.
+    Restore,
+    /// Tracking each window's monitor and saving window state: change detection in
+    /// `Update` and the final write in `Last` when the app exits.
+    Save,
+}
@@ -0,0 +1,89 @@
+//! Helpers shared by tests that run the plugin without winit.
+
+use bevy::prelude::*;
+use bevy::window::ExitCondition;
+use bevy::window::Monitor;
+
+use crate::WindowManagerPluginBuilder;
+use crate::monitors::MonitorInfo;
+use crate::restore::WinitInfo;
+use crate::winit_access::WinitWindowAccess;
+
+/// An app running the plugin built by `builder`, with one 1920x1080 monitor at scale `1.0`.
+///
+/// Without winit, the monitor and the startup measurement winit would provide are stood in
+/// for, so the primary window is restored as soon as one is spawned. That restore never
+/// settles, since no winit window ever reports the target geometry.
+pub(crate) fn headless_app(builder: WindowManagerPluginBuilder) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            ..default()
+        },
+        builder.build(),
+    ));
+    app.world_mut().spawn(Monitor {
+        name:                    None,
+        physical_height:         1080,
+        physical_width:          1920,
+        physical_position:       IVec2::ZERO,
+        refresh_rate_millihertz: None,
+        scale_factor:            1.0,
+        video_modes:             Vec::new(),
+    });
+    app.insert_resource(WinitInfo::undecorated());
+    app
+}
+
+/// A 1920x1080 monitor at scale `1.0` at `index` in the monitor list, with its top-left
+/// corner at `(physical_x, 0)`. Set other fields with struct update syntax.
+pub(crate) fn monitor(index: usize, physical_x: i32) -> MonitorInfo {
+    MonitorInfo {
+        index,
+        scale: 1.0,
+        physical_position: IVec2::new(physical_x, 0),
+        physical_size: UVec2::new(1920, 1080),
+        name: None,
+        physical_work_area: None,
+        refresh_rate_millihertz: None,
+        video_modes: Vec::new(),
+        entity: Entity::PLACEHOLDER,
+    }
+}
+
+/// A winit window with fixed state, standing in for `WINIT_WINDOWS`.
+#[derive(Default)]
+pub(crate) struct FakeWinitWindow {
+    pub(crate) physical_outer_position:   Option<IVec2>,
+    pub(crate) physical_outer_size:       UVec2,
+    pub(crate) physical_inner_size:       UVec2,
+    pub(crate) physical_monitor_position: Option<IVec2>,
+    pub(crate) minimized:                 bool,
+    pub(crate) maximized:                 bool,
+    /// Monitors winit lists, see [`WinitWindowAccess::available_monitors`].
+    pub(crate) monitors:                  Vec<MonitorInfo>,
+}
+
+impl WinitWindowAccess for FakeWinitWindow {
+    fn outer_position(&self, _entity: Entity) -> Option<IVec2> { self.physical_outer_position }
+
+    fn outer_size(&self, _entity: Entity) -> Option<UVec2> { Some(self.physical_outer_size) }
+
+    fn inner_size(&self, _entity: Entity) -> Option<UVec2> { Some(self.physical_inner_size) }
+
+    fn current_monitor_position(&self, _entity: Entity) -> Option<IVec2> {
+        self.physical_monitor_position
+    }
+
+    fn is_minimized(&self, _entity: Entity) -> Option<bool> { Some(self.minimized) }
+
+    fn is_maximized(&self, _entity: Entity) -> Option<bool> { Some(self.maximized) }
+
+    fn available_monitors(&self) -> Option<Vec<MonitorInfo>> { Some(self.monitors.clone()) }
+
+    #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+    fn x11_window_id(&self, _entity: Entity) -> Option<u32> { None }
+}
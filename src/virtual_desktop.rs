@@ -0,0 +1,97 @@
+//! Windows virtual desktop queries (`windows-virtual-desktops` feature).
+//!
+//! winit has no notion of virtual desktops, so the desktop a window is on is read and set
+//! through the documented `IVirtualDesktopManager` COM interface. Desktops are identified
+//! by their GUID, saved as 32 hex digits. A saved desktop that was since removed cannot be
+//! moved to; the window then stays on the current desktop.
+//!
+//! Without the feature, or on other platforms, no desktop is saved and nothing is moved.
+//! All functions must run on the main thread; callers hold a `NonSendMarker`.
+
+use bevy::prelude::*;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::System::Com::CLSCTX_ALL;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::System::Com::CoCreateInstance;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::System::Com::CoInitializeEx;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::UI::Shell::IVirtualDesktopManager;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::Win32::UI::Shell::VirtualDesktopManager;
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use windows::core::GUID;
+
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+use crate::opacity::get_hwnd;
+
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+thread_local! {
+    /// Manager created on first use; `None` when COM could not create it.
+    static VIRTUAL_DESKTOP_MANAGER: Option<IVirtualDesktopManager> =
+        create_virtual_desktop_manager();
+}
+
+/// Create the virtual desktop manager, initializing COM on this thread if needed.
+///
+/// Runs once per thread. The COM initialization is never undone: the cached manager needs
+/// the apartment for as long as the thread runs.
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+fn create_virtual_desktop_manager() -> Option<IVirtualDesktopManager> {
+    // SAFETY: called on the main thread; an already initialized apartment (winit uses
+    // OLE for drag and drop) only makes this return `S_FALSE` or `RPC_E_CHANGED_MODE`.
+    let _ = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+    // SAFETY: `VirtualDesktopManager` is the documented class for this interface.
+    unsafe { CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL) }
+        .inspect_err(|error| {
+            debug!("[create_virtual_desktop_manager] IVirtualDesktopManager unavailable: {error}");
+        })
+        .ok()
+}
+
+/// The cached virtual desktop manager, creating it on first use.
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+fn virtual_desktop_manager() -> Option<IVirtualDesktopManager> {
+    VIRTUAL_DESKTOP_MANAGER.with(Clone::clone)
+}
+
+/// Id of the virtual desktop the window is on, when the platform reports it. Only read
+/// when the state file is written, not on every change check.
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+pub(crate) fn window_virtual_desktop(entity: Entity) -> Option<String> {
+    let hwnd = get_hwnd(entity)?;
+    let manager = virtual_desktop_manager()?;
+    // SAFETY: `hwnd` is a live window handle from winit.
+    let desktop_id = unsafe { manager.GetWindowDesktopId(hwnd) }.ok()?;
+    (desktop_id != GUID::zeroed()).then(|| format!("{:032x}", desktop_id.to_u128()))
+}
+
+/// Id of the virtual desktop the window is on, when the platform reports it.
+#[cfg(not(all(target_os = "windows", feature = "windows-virtual-desktops")))]
+pub(crate) const fn window_virtual_desktop(_entity: Entity) -> Option<String> { None }
+
+/// Move the window to the virtual desktop with id `desktop_id`. Returns whether it moved;
+/// `false` when the desktop no longer exists.
+#[cfg(all(target_os = "windows", feature = "windows-virtual-desktops"))]
+pub(crate) fn move_to_virtual_desktop(entity: Entity, desktop_id: &str) -> bool {
+    let Ok(desktop_id) = u128::from_str_radix(desktop_id, 16).map(GUID::from_u128) else {
+        warn!("[move_to_virtual_desktop] Invalid virtual desktop id \"{desktop_id}\"");
+        return false;
+    };
+    let (Some(hwnd), Some(manager)) = (get_hwnd(entity), virtual_desktop_manager()) else {
+        return false;
+    };
+    // SAFETY: `hwnd` is a live window handle owned by this process, which is the only
+    // kind `MoveWindowToDesktop` accepts.
+    unsafe { manager.MoveWindowToDesktop(hwnd, &desktop_id) }
+        .inspect_err(|error| {
+            warn!("[move_to_virtual_desktop] Could not move {entity:?} to desktop {desktop_id:?}: {error}");
+        })
+        .is_ok()
+}
+
+/// Move the window to the virtual desktop with id `desktop_id`. Returns whether it moved.
+#[cfg(not(all(target_os = "windows", feature = "windows-virtual-desktops")))]
+pub(crate) const fn move_to_virtual_desktop(_entity: Entity, _desktop_id: &str) -> bool { false }
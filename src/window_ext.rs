@@ -0,0 +1,407 @@
+//! Monitor-aware helpers on `Window`.
+//!
+//! These apply the same cross-DPI position compensation as startup restore, so apps can
+//! move windows between monitors at runtime without reimplementing the winit #4440
+//! workaround.
+
+use bevy::prelude::*;
+use bevy::window::MonitorSelection;
+use bevy::window::VideoModeSelection;
+use bevy::window::WindowMode;
+use bevy::window::WindowPosition;
+use bevy_kana::ToI32;
+use bevy_kana::ToU32;
+
+use crate::constants::SCALE_FACTOR_EPSILON;
+use crate::monitors::MonitorInfo;
+use crate::monitors::Monitors;
+use crate::persistence::SavedVideoMode;
+use crate::platform::Platform;
+use crate::restore::MonitorScaleStrategy;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
+
+/// Where a window lands on the monitor it is moved to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum MonitorAnchor {
+    /// Center the window on the monitor.
+    #[default]
+    Center,
+    /// Place the window at the monitor's top-left corner (work area when known).
+    TopLeft,
+    /// Keep the window's position relative to its current monitor, scaled to the new
+    /// monitor's size. Falls back to `Center` when the current position is unknown.
+    PreserveRelative,
+}
+
+/// Monitor-aware extension methods for [`Window`].
+pub trait WindowExt {
+    /// Move the window to the monitor at `index` in [`Monitors`].
+    ///
+    /// The logical size is kept; the OS resizes the window for the new monitor's scale.
+    /// When the target monitor has a different scale factor, the requested position is
+    /// compensated the same way startup restore compensates it (see winit #4440).
+    ///
+    /// On Wayland, where clients cannot position windows, the window is centered on the
+    /// monitor via `WindowPosition::Centered` regardless of `anchor`.
+    ///
+    /// Returns the physical position the window is moved to, or `None` if no monitor has
+    /// that index or the platform cannot position windows.
+    fn move_to_monitor(
+        &mut self,
+        monitors: &Monitors,
+        index: usize,
+        anchor: MonitorAnchor,
+    ) -> Option<IVec2>;
+
+    /// Center the window on the monitor at `index` in [`Monitors`].
+    ///
+    /// Centers within the work area when known. A window larger than the monitor is
+    /// placed at its top-left corner instead. Shorthand for
+    /// [`move_to_monitor`](Self::move_to_monitor) with [`MonitorAnchor::Center`].
+    fn center_on_monitor(&mut self, monitors: &Monitors, index: usize) -> Option<IVec2>;
+
+    /// Physical origin and size of the usable area of the window's current monitor,
+    /// excluding the menu bar, Dock, taskbar, or panels.
+    ///
+    /// Falls back to the full monitor bounds where the work area is unknown (see
+    /// [`MonitorInfo::physical_work_area`]). The monitor is found from the window's center,
+    /// like [`Monitors::monitor_for_window`]. Returns `None` when the window position is
+    /// unknown, as on Wayland, or no monitors exist.
+    fn work_area(&self, monitors: &Monitors) -> Option<(IVec2, UVec2)>;
+
+    /// Physical size of the title bar and borders around the window's content, as the
+    /// difference between winit's outer and inner sizes for `entity`, this window's entity.
+    ///
+    /// Zero when the window has no decorations. Some platforms report zero until the
+    /// window is first shown. Returns `None` when winit has no window for `entity`. Reads
+    /// winit's windows, so call it from a system holding a `NonSendMarker`.
+    fn decoration_size(&self, entity: Entity) -> Option<UVec2>;
+
+    /// Switch the window to exclusive fullscreen on the monitor at `index` in [`Monitors`].
+    ///
+    /// `video_mode` must be one of [`Monitors::video_modes`] for that monitor; `None` keeps
+    /// the monitor's current mode. Returns whether the mode was set. When no monitor has
+    /// that index, or the monitor does not offer `video_mode`, the window is left as is.
+    fn set_exclusive_fullscreen(
+        &mut self,
+        monitors: &Monitors,
+        index: usize,
+        video_mode: Option<&SavedVideoMode>,
+    ) -> bool;
+}
+
+impl WindowExt for Window {
+    fn move_to_monitor(
+        &mut self,
+        monitors: &Monitors,
+        index: usize,
+        anchor: MonitorAnchor,
+    ) -> Option<IVec2> {
+        let target_info = monitors.by_index(index)?;
+        let platform = Platform::detect();
+        if !platform.position_available() {
+            self.position = WindowPosition::Centered(MonitorSelection::Index(index));
+            return None;
+        }
+
+        let current_scale = f64::from(self.resolution.scale_factor());
+        let physical_target_size = UVec2::new(
+            (f64::from(self.width()) * target_info.scale)
+                .round()
+                .to_u32(),
+            (f64::from(self.height()) * target_info.scale)
+                .round()
+                .to_u32(),
+        );
+        let current = match self.position {
+            WindowPosition::At(physical_position) => Some((
+                physical_position,
+                monitors.monitor_for_window(
+                    physical_position,
+                    self.physical_width(),
+                    self.physical_height(),
+                ),
+            )),
+            _ => None,
+        };
+
+        let physical_position =
+            anchored_physical_position(anchor, current, target_info, physical_target_size);
+        let physical_requested_position =
+            match platform.scale_strategy(current_scale, target_info.scale, SCALE_FACTOR_EPSILON) {
+                // `set_outer_position` is interpreted at the current monitor's scale on
+                // macOS/X11, so pre-multiply by the scale ratio like `apply_initial_move`.
+                MonitorScaleStrategy::LowerToHigher | MonitorScaleStrategy::HigherToLower(_) => {
+                    let ratio = current_scale / target_info.scale;
+                    IVec2::new(
+                        (f64::from(physical_position.x) * ratio).round().to_i32(),
+                        (f64::from(physical_position.y) * ratio).round().to_i32(),
+                    )
+                },
+                MonitorScaleStrategy::ApplyUnchanged
+                | MonitorScaleStrategy::CompensateSizeOnly(_) => physical_position,
+            };
+
+        debug!(
+            "[move_to_monitor] monitor={index} anchor={anchor:?} position={physical_position:?} requested={physical_requested_position:?} scale={current_scale}->{}",
+            target_info.scale
+        );
+        self.position = WindowPosition::At(physical_requested_position);
+        Some(physical_position)
+    }
+
+    fn center_on_monitor(&mut self, monitors: &Monitors, index: usize) -> Option<IVec2> {
+        self.move_to_monitor(monitors, index, MonitorAnchor::Center)
+    }
+
+    fn work_area(&self, monitors: &Monitors) -> Option<(IVec2, UVec2)> {
+        let WindowPosition::At(physical_position) = self.position else {
+            return None;
+        };
+        if monitors.is_empty() {
+            return None;
+        }
+        let physical_usable_bounds = monitors
+            .monitor_for_window(
+                physical_position,
+                self.physical_width(),
+                self.physical_height(),
+            )
+            .physical_usable_bounds();
+        Some((
+            physical_usable_bounds.min,
+            physical_usable_bounds.size().as_uvec2(),
+        ))
+    }
+
+    fn decoration_size(&self, entity: Entity) -> Option<UVec2> {
+        window_decoration_size(&LiveWinitWindows, self, entity)
+    }
+
+    fn set_exclusive_fullscreen(
+        &mut self,
+        monitors: &Monitors,
+        index: usize,
+        video_mode: Option<&SavedVideoMode>,
+    ) -> bool {
+        if monitors.by_index(index).is_none() {
+            warn!("[set_exclusive_fullscreen] No monitor at index {index}");
+            return false;
+        }
+        let video_mode_selection = match video_mode {
+            Some(video_mode) if !monitors.video_modes(index).contains(video_mode) => {
+                warn!("[set_exclusive_fullscreen] Monitor {index} does not support {video_mode:?}");
+                return false;
+            },
+            Some(video_mode) => VideoModeSelection::Specific(video_mode.to_video_mode()),
+            None => VideoModeSelection::Current,
+        };
+
+        debug!("[set_exclusive_fullscreen] monitor={index} video_mode={video_mode_selection:?}");
+        self.mode = WindowMode::Fullscreen(MonitorSelection::Index(index), video_mode_selection);
+        true
+    }
+}
+
+/// [`WindowExt::decoration_size`] of `window`, read through `winit_windows`.
+fn window_decoration_size(
+    winit_windows: &impl WinitWindowAccess,
+    window: &Window,
+    entity: Entity,
+) -> Option<UVec2> {
+    let physical_decoration = winit_windows.physical_decoration(entity)?;
+    Some(if window.decorations {
+        physical_decoration
+    } else {
+        UVec2::ZERO
+    })
+}
+
+/// Compute the uncompensated physical position for `anchor` on `target_info`.
+///
+/// `current` is the window's physical position and the monitor it is on, if known.
+fn anchored_physical_position(
+    anchor: MonitorAnchor,
+    current: Option<(IVec2, &MonitorInfo)>,
+    target_info: &MonitorInfo,
+    physical_target_size: UVec2,
+) -> IVec2 {
+    let physical_bounds = target_info.physical_usable_bounds();
+    let physical_free_space = physical_bounds.size() - physical_target_size.as_ivec2();
+
+    match (anchor, current) {
+        (MonitorAnchor::TopLeft, _) => physical_bounds.min,
+        (MonitorAnchor::PreserveRelative, Some((physical_position, current_info))) => {
+            let physical_current_bounds = current_info.physical_usable_bounds();
+            let physical_offset = physical_position - physical_current_bounds.min;
+            let fraction = physical_offset.as_vec2() / physical_current_bounds.size().as_vec2();
+            physical_bounds.min
+                + (fraction * physical_bounds.size().as_vec2())
+                    .round()
+                    .as_ivec2()
+                    .clamp(IVec2::ZERO, physical_free_space.max(IVec2::ZERO))
+        },
+        (MonitorAnchor::Center | MonitorAnchor::PreserveRelative, _) => {
+            physical_bounds.min + physical_free_space.max(IVec2::ZERO) / 2
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::FakeWinitWindow;
+
+    fn monitor(index: usize, physical_x: i32, physical_size: UVec2) -> MonitorInfo {
+        MonitorInfo {
+            physical_size,
+            ..test_support::monitor(index, physical_x)
+        }
+    }
+
+    #[test]
+    fn center_places_window_in_middle_of_target() {
+        let target_info = monitor(1, 1920, UVec2::new(2560, 1440));
+
+        let physical_position = anchored_physical_position(
+            MonitorAnchor::Center,
+            None,
+            &target_info,
+            UVec2::new(800, 600),
+        );
+        assert_eq!(physical_position, IVec2::new(1920 + 880, 420));
+    }
+
+    #[test]
+    fn center_clamps_oversized_window_to_top_left() {
+        let target_info = monitor(1, 1920, UVec2::new(1280, 720));
+
+        let physical_position = anchored_physical_position(
+            MonitorAnchor::Center,
+            None,
+            &target_info,
+            UVec2::new(1600, 900),
+        );
+        assert_eq!(physical_position, IVec2::new(1920, 0));
+    }
+
+    #[test]
+    fn top_left_uses_work_area() {
+        let mut target_info = monitor(1, 1920, UVec2::new(2560, 1440));
+        target_info.physical_work_area = Some(IRect::new(1920, 25, 4480, 1440));
+
+        let physical_position = anchored_physical_position(
+            MonitorAnchor::TopLeft,
+            None,
+            &target_info,
+            UVec2::new(800, 600),
+        );
+        assert_eq!(physical_position, IVec2::new(1920, 25));
+    }
+
+    #[test]
+    fn preserve_relative_scales_offset_to_target() {
+        let current_info = monitor(0, 0, UVec2::new(1920, 1080));
+        let target_info = monitor(1, 1920, UVec2::new(3840, 2160));
+
+        let physical_position = anchored_physical_position(
+            MonitorAnchor::PreserveRelative,
+            Some((IVec2::new(480, 270), &current_info)),
+            &target_info,
+            UVec2::new(800, 600),
+        );
+        assert_eq!(physical_position, IVec2::new(1920 + 960, 540));
+    }
+
+    #[test]
+    fn preserve_relative_without_position_centers() {
+        let target_info = monitor(1, 1920, UVec2::new(1920, 1080));
+
+        let physical_position = anchored_physical_position(
+            MonitorAnchor::PreserveRelative,
+            None,
+            &target_info,
+            UVec2::new(800, 600),
+        );
+        assert_eq!(physical_position, IVec2::new(1920 + 560, 240));
+    }
+
+    #[test]
+    fn work_area_is_the_usable_area_of_the_current_monitor() {
+        let mut external = monitor(1, 1920, UVec2::new(2560, 1440));
+        external.physical_work_area = Some(IRect::new(1920, 25, 4480, 1440));
+        let monitors = Monitors {
+            list: vec![monitor(0, 0, UVec2::new(1920, 1080)), external],
+        };
+        let mut window = Window {
+            resolution: (800, 600).into(),
+            ..default()
+        };
+
+        assert_eq!(window.work_area(&monitors), None);
+
+        window.position = WindowPosition::At(IVec2::new(2000, 100));
+        assert_eq!(
+            window.work_area(&monitors),
+            Some((IVec2::new(1920, 25), UVec2::new(2560, 1415)))
+        );
+
+        window.position = WindowPosition::At(IVec2::new(100, 100));
+        assert_eq!(
+            window.work_area(&monitors),
+            Some((IVec2::ZERO, UVec2::new(1920, 1080)))
+        );
+    }
+
+    #[test]
+    fn exclusive_fullscreen_requires_a_mode_the_monitor_offers() {
+        let offered = SavedVideoMode {
+            physical_size:           UVec2::new(2560, 1440),
+            bit_depth:               32,
+            refresh_rate_millihertz: 144_000,
+        };
+        let mut external = monitor(1, 1920, UVec2::new(2560, 1440));
+        external.video_modes = vec![offered.clone()];
+        let monitors = Monitors {
+            list: vec![monitor(0, 0, UVec2::new(1920, 1080)), external],
+        };
+        let mut window = Window::default();
+
+        assert!(!window.set_exclusive_fullscreen(&monitors, 0, Some(&offered)));
+        assert!(!window.set_exclusive_fullscreen(&monitors, 2, None));
+        assert_eq!(window.mode, WindowMode::Windowed);
+
+        assert!(window.set_exclusive_fullscreen(&monitors, 1, Some(&offered)));
+        assert_eq!(
+            window.mode,
+            WindowMode::Fullscreen(
+                MonitorSelection::Index(1),
+                VideoModeSelection::Specific(offered.to_video_mode())
+            )
+        );
+        assert_eq!(monitors.video_modes(1), &[offered]);
+        assert!(monitors.video_modes(2).is_empty());
+    }
+
+    #[test]
+    fn decoration_size_is_zero_for_an_undecorated_window() {
+        let winit_window = FakeWinitWindow {
+            physical_outer_size: UVec2::new(1280, 748),
+            physical_inner_size: UVec2::new(1280, 720),
+            ..default()
+        };
+        let mut window = Window::default();
+        assert_eq!(
+            window_decoration_size(&winit_window, &window, Entity::PLACEHOLDER),
+            Some(UVec2::new(0, 28))
+        );
+
+        window.decorations = false;
+        assert_eq!(
+            window_decoration_size(&winit_window, &window, Entity::PLACEHOLDER),
+            Some(UVec2::ZERO)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Workaround for winit #4107: measure the invisible resize border Windows adds around
+//! each window.
+//!
+//! `GetWindowRect`, which winit's `outer_position()` reports, includes an invisible resize
+//! border on the left, right, and bottom edges (~7-11 physical pixels, scaled with DPI). A
+//! window snapped flush to a monitor edge therefore saves a slightly negative position. The
+//! visible frame is `DWMWA_EXTENDED_FRAME_BOUNDS`; the difference between the two rects is
+//! saved so restore can place the visible frame, not the invisible border, inside a
+//! monitor.
+//!
+//! Without the feature, on other platforms, or when DWM does not answer (composition
+//! off, window not yet shown), no border is reported and positions are used as-is.
+//!
+//! See: <https://github.com/rust-windowing/winit/issues/4107>
+
+use bevy::prelude::*;
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use bevy_kana::ToU32;
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use windows::Win32::Foundation::RECT;
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use windows::Win32::Graphics::Dwm::DWMWA_EXTENDED_FRAME_BOUNDS;
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+use crate::opacity::get_hwnd;
+
+/// Offset of the visible frame's top-left corner from the window's outer position, in
+/// physical pixels. Windows draws the left border's width on the right and bottom edges
+/// too.
+#[cfg(all(target_os = "windows", feature = "workaround-winit-4107"))]
+pub(crate) fn physical_invisible_border(entity: Entity) -> Option<IVec2> {
+    let hwnd = get_hwnd(entity)?;
+    let mut window_rect = RECT::default();
+    let mut frame_rect = RECT::default();
+    // SAFETY: `hwnd` is a live window handle from winit and both out pointers are valid
+    // for a `RECT`, whose size is passed alongside.
+    unsafe {
+        GetWindowRect(hwnd, &raw mut window_rect).ok()?;
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            (&raw mut frame_rect).cast(),
+            size_of::<RECT>().to_u32(),
+        )
+        .ok()?;
+    }
+    let physical_border = IVec2::new(
+        frame_rect.left - window_rect.left,
+        frame_rect.top - window_rect.top,
+    );
+    // A minimized or hidden window can report unrelated rects; a real border is never
+    // negative.
+    (physical_border.cmpge(IVec2::ZERO).all() && physical_border != IVec2::ZERO)
+        .then_some(physical_border)
+}
+
+/// Offset of the visible frame's top-left corner from the window's outer position, in
+/// physical pixels. Always `None` without the invisible border.
+#[cfg(not(all(target_os = "windows", feature = "workaround-winit-4107")))]
+pub(crate) const fn physical_invisible_border(_entity: Entity) -> Option<IVec2> { None }
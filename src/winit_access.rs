@@ -0,0 +1,158 @@
+//! Read access to the winit window behind a Bevy window entity.
+//!
+//! `WINIT_WINDOWS` only holds real winit windows, so logic that reads it directly can only
+//! run against a live window. Systems read through [`WinitWindowAccess`] instead:
+//! [`LiveWinitWindows`] wraps `WINIT_WINDOWS` at runtime, and tests pass a fake that reports
+//! fixed geometry.
+//!
+//! Fullscreen is switched through `Window::mode`, so the trait only reads window state.
+
+use bevy::prelude::*;
+use bevy::winit::WINIT_WINDOWS;
+#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+use bevy_kana::ToU32;
+#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+use raw_window_handle::HasWindowHandle;
+#[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+use raw_window_handle::RawWindowHandle;
+
+use crate::monitors::MonitorInfo;
+
+/// The winit window geometry the plugin reads, in physical pixels. Every method returns
+/// `None` when `entity` has no winit window.
+pub(crate) trait WinitWindowAccess {
+    /// Top-left corner of the window frame. Also `None` where clients cannot read window
+    /// positions (Wayland).
+    fn outer_position(&self, entity: Entity) -> Option<IVec2>;
+
+    /// Size of the window including decoration.
+    fn outer_size(&self, entity: Entity) -> Option<UVec2>;
+
+    /// Size of the content area.
+    fn inner_size(&self, entity: Entity) -> Option<UVec2>;
+
+    /// Top-left corner of the monitor winit reports the window on. Also `None` when winit
+    /// does not know the window's monitor.
+    fn current_monitor_position(&self, entity: Entity) -> Option<IVec2>;
+
+    /// Whether the window is minimized. Also `None` where the platform cannot tell.
+    fn is_minimized(&self, entity: Entity) -> Option<bool>;
+
+    /// Whether the window is maximized.
+    fn is_maximized(&self, entity: Entity) -> Option<bool>;
+
+    /// Every monitor winit reports, with only the scale, position, size, name, and refresh
+    /// rate filled in. `None` until a winit window exists, since winit lists monitors
+    /// through one.
+    fn available_monitors(&self) -> Option<Vec<MonitorInfo>>;
+
+    /// X11 window id of the window. Also `None` on Wayland.
+    #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+    fn x11_window_id(&self, entity: Entity) -> Option<u32>;
+
+    /// Size of the title bar and borders: the outer size minus the inner size.
+    fn physical_decoration(&self, entity: Entity) -> Option<UVec2> {
+        let physical_outer_size = self.outer_size(entity)?;
+        let physical_inner_size = self.inner_size(entity)?;
+        Some(physical_outer_size.saturating_sub(physical_inner_size))
+    }
+}
+
+/// [`WinitWindowAccess`] over Bevy's `WINIT_WINDOWS`. Only usable on the main thread;
+/// callers hold a `NonSendMarker`.
+pub(crate) struct LiveWinitWindows;
+
+impl WinitWindowAccess for LiveWinitWindows {
+    fn outer_position(&self, entity: Entity) -> Option<IVec2> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            let physical_position = winit_windows.get_window(entity)?.outer_position().ok()?;
+            Some(IVec2::new(physical_position.x, physical_position.y))
+        })
+    }
+
+    fn outer_size(&self, entity: Entity) -> Option<UVec2> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            let physical_size = winit_windows.get_window(entity)?.outer_size();
+            Some(UVec2::new(physical_size.width, physical_size.height))
+        })
+    }
+
+    fn inner_size(&self, entity: Entity) -> Option<UVec2> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            let physical_size = winit_windows.get_window(entity)?.inner_size();
+            Some(UVec2::new(physical_size.width, physical_size.height))
+        })
+    }
+
+    fn current_monitor_position(&self, entity: Entity) -> Option<IVec2> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            let current_monitor = winit_windows.get_window(entity)?.current_monitor()?;
+            let physical_position = current_monitor.position();
+            Some(IVec2::new(physical_position.x, physical_position.y))
+        })
+    }
+
+    fn is_minimized(&self, entity: Entity) -> Option<bool> {
+        WINIT_WINDOWS
+            .with(|winit_windows| winit_windows.borrow().get_window(entity)?.is_minimized())
+    }
+
+    fn is_maximized(&self, entity: Entity) -> Option<bool> {
+        WINIT_WINDOWS
+            .with(|winit_windows| Some(winit_windows.borrow().get_window(entity)?.is_maximized()))
+    }
+
+    fn available_monitors(&self) -> Option<Vec<MonitorInfo>> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            let winit_window = winit_windows.windows.values().next()?;
+            Some(
+                winit_window
+                    .available_monitors()
+                    .map(|handle| {
+                        let physical_position = handle.position();
+                        let physical_size = handle.size();
+                        MonitorInfo {
+                            index:                   0,
+                            scale:                   handle.scale_factor(),
+                            physical_position:       IVec2::new(
+                                physical_position.x,
+                                physical_position.y,
+                            ),
+                            physical_size:           UVec2::new(
+                                physical_size.width,
+                                physical_size.height,
+                            ),
+                            name:                    handle.name(),
+                            physical_work_area:      None,
+                            refresh_rate_millihertz: handle.refresh_rate_millihertz(),
+                            video_modes:             Vec::new(),
+                            entity:                  Entity::PLACEHOLDER,
+                        }
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    #[cfg(all(target_os = "linux", feature = "workaround-winit-4445"))]
+    fn x11_window_id(&self, entity: Entity) -> Option<u32> {
+        WINIT_WINDOWS.with(|winit_windows| {
+            let winit_windows = winit_windows.borrow();
+            match winit_windows
+                .get_window(entity)?
+                .window_handle()
+                .ok()?
+                .as_raw()
+            {
+                RawWindowHandle::Xlib(handle) => Some(handle.window.to_u32()),
+                RawWindowHandle::Xcb(handle) => Some(handle.window.get()),
+                _ => None,
+            }
+        })
+    }
+}
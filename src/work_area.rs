@@ -0,0 +1,207 @@
+//! Monitor work area queries.
+//!
+//! winit only reports full monitor bounds. The work area excludes the macOS menu bar and
+//! Dock, the Windows taskbar, and X11 panels, so it is queried from each platform
+//! directly. Monitors whose work area cannot be determined (Wayland, or a failed query)
+//! keep `physical_work_area = None` and callers fall back to full bounds.
+
+#[cfg(target_os = "windows")]
+use std::mem::size_of;
+
+use bevy::prelude::*;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use bevy_kana::ToI32;
+#[cfg(target_os = "linux")]
+use bevy_kana::ToUsize;
+#[cfg(target_os = "macos")]
+use objc2::MainThreadMarker;
+#[cfg(target_os = "macos")]
+use objc2_app_kit::NSScreen;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::POINT;
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONULL;
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::MONITORINFO;
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::MonitorFromPoint;
+#[cfg(target_os = "linux")]
+use x11rb::connection::Connection;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::AtomEnum;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::ConnectionExt;
+#[cfg(target_os = "linux")]
+use x11rb::xcb_ffi::XCBConnection;
+
+#[cfg(target_os = "linux")]
+use crate::constants::CURRENT_DESKTOP_ATOM_NAME;
+#[cfg(target_os = "linux")]
+use crate::constants::WORK_AREA_ATOM_NAME;
+#[cfg(target_os = "linux")]
+use crate::constants::WORK_AREA_VALUE_COUNT;
+use crate::monitors::MonitorInfo;
+use crate::platform::Platform;
+
+/// Fill in `physical_work_area` for every monitor the platform can report one for.
+///
+/// On X11 the work area comes from the root window's `_NET_WORKAREA`, which is a single
+/// rectangle for the whole desktop; each monitor gets its intersection with that rectangle.
+#[cfg(target_os = "linux")]
+pub(crate) fn apply_work_areas(monitors: &mut [MonitorInfo], platform: Platform) {
+    // Wayland clients cannot see global coordinates, so there is no work area to query.
+    if !platform.is_x11() {
+        return;
+    }
+    let Some(physical_desktop_work_area) = query_x11_work_area() else {
+        debug!("[apply_work_areas] _NET_WORKAREA not available, using full monitor bounds");
+        return;
+    };
+    for monitor in monitors {
+        let physical_work_area = physical_desktop_work_area.intersect(monitor.physical_bounds());
+        monitor.physical_work_area = (!physical_work_area.is_empty()).then_some(physical_work_area);
+    }
+}
+
+/// Fill in `physical_work_area` for every monitor the platform can report one for.
+///
+/// On macOS the work area is `NSScreen.visibleFrame`. Screens are matched to monitors by
+/// converting the screen frame into winit's top-left-origin physical coordinates.
+#[cfg(target_os = "macos")]
+pub(crate) fn apply_work_areas(monitors: &mut [MonitorInfo], _platform: Platform) {
+    // `NSScreen` is main-thread only; callers hold a `NonSendMarker`.
+    let Some(main_thread_marker) = MainThreadMarker::new() else {
+        debug!("[apply_work_areas] Not on the main thread, using full monitor bounds");
+        return;
+    };
+    let screens = NSScreen::screens(main_thread_marker);
+    // AppKit's global coordinates are bottom-left origin relative to the first screen.
+    let Some(main_frame_height) = screens
+        .iter()
+        .next()
+        .map(|screen| screen.frame().size.height)
+    else {
+        return;
+    };
+
+    for screen in &screens {
+        let frame = screen.frame();
+        let visible_frame = screen.visibleFrame();
+        let scale = screen.backingScaleFactor();
+
+        let physical_position = IVec2::new(
+            (frame.origin.x * scale).round().to_i32(),
+            ((main_frame_height - frame.origin.y - frame.size.height) * scale)
+                .round()
+                .to_i32(),
+        );
+        let Some(monitor) = monitors
+            .iter_mut()
+            .find(|monitor| monitor.physical_position == physical_position)
+        else {
+            continue;
+        };
+
+        let physical_inset_left = ((visible_frame.origin.x - frame.origin.x) * scale)
+            .round()
+            .to_i32();
+        let physical_inset_top = ((frame.origin.y + frame.size.height
+            - visible_frame.origin.y
+            - visible_frame.size.height)
+            * scale)
+            .round()
+            .to_i32();
+        let physical_min = physical_position + IVec2::new(physical_inset_left, physical_inset_top);
+        let physical_size = IVec2::new(
+            (visible_frame.size.width * scale).round().to_i32(),
+            (visible_frame.size.height * scale).round().to_i32(),
+        );
+        monitor.physical_work_area = Some(IRect::from_corners(
+            physical_min,
+            physical_min + physical_size,
+        ));
+    }
+}
+
+/// Fill in `physical_work_area` for every monitor the platform can report one for.
+///
+/// On Windows the work area is `MONITORINFO.rcWork`, which excludes the taskbar and
+/// docked app bars.
+#[cfg(target_os = "windows")]
+pub(crate) fn apply_work_areas(monitors: &mut [MonitorInfo], _platform: Platform) {
+    for monitor in monitors {
+        monitor.physical_work_area = query_windows_work_area(monitor);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn query_windows_work_area(monitor: &MonitorInfo) -> Option<IRect> {
+    let point = POINT {
+        x: monitor.physical_position.x,
+        y: monitor.physical_position.y,
+    };
+    // SAFETY: `MonitorFromPoint` has no preconditions; it returns a null handle when no
+    // monitor contains the point.
+    let hmonitor = unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONULL) };
+    if hmonitor.is_invalid() {
+        return None;
+    }
+
+    let mut monitor_info = MONITORINFO {
+        cbSize: u32::try_from(size_of::<MONITORINFO>()).ok()?,
+        ..Default::default()
+    };
+    // SAFETY: `hmonitor` is a valid handle and `cbSize` is initialized as required.
+    if !unsafe { GetMonitorInfoW(hmonitor, &raw mut monitor_info) }.as_bool() {
+        return None;
+    }
+
+    let work = monitor_info.rcWork;
+    Some(IRect::new(work.left, work.top, work.right, work.bottom))
+}
+
+#[cfg(target_os = "linux")]
+fn query_x11_work_area() -> Option<IRect> {
+    let (conn, screen_num) = XCBConnection::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+
+    let current_desktop = query_root_cardinals(&conn, root, CURRENT_DESKTOP_ATOM_NAME, 1)
+        .and_then(|values| values.first().copied())
+        .unwrap_or(0);
+    let values = query_root_cardinals(
+        &conn,
+        root,
+        WORK_AREA_ATOM_NAME,
+        (current_desktop + 1) * WORK_AREA_VALUE_COUNT,
+    )?;
+
+    // `_NET_WORKAREA` holds x, y, width, height for each desktop in turn.
+    let start = (current_desktop * WORK_AREA_VALUE_COUNT).to_usize();
+    let &[x, y, width, height] = values.get(start..start + WORK_AREA_VALUE_COUNT.to_usize())?
+    else {
+        return None;
+    };
+    let physical_min = IVec2::new(x.to_i32(), y.to_i32());
+    Some(IRect::from_corners(
+        physical_min,
+        physical_min + IVec2::new(width.to_i32(), height.to_i32()),
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn query_root_cardinals(
+    conn: &XCBConnection,
+    root: u32,
+    atom_name: &[u8],
+    length: u32,
+) -> Option<Vec<u32>> {
+    let atom = conn.intern_atom(true, atom_name).ok()?.reply().ok()?.atom;
+    let property = conn
+        .get_property(false, root, atom, AtomEnum::CARDINAL, 0, length)
+        .ok()?
+        .reply()
+        .ok()?;
+    Some(property.value32()?.collect())
+}
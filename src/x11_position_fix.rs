@@ -8,25 +8,86 @@
 //!
 //! See: <https://github.com/rust-windowing/winit/issues/4445>
 
+use std::collections::HashMap;
+
 use bevy::ecs::system::NonSendMarker;
 use bevy::prelude::*;
 use bevy::window::WindowPosition;
-use bevy::winit::WINIT_WINDOWS;
 use bevy_kana::ToI32;
-use bevy_kana::ToU32;
-use bevy_kana::ToUsize;
-use raw_window_handle::HasWindowHandle;
-use raw_window_handle::RawWindowHandle;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::Atom;
 use x11rb::protocol::xproto::AtomEnum;
 use x11rb::protocol::xproto::ConnectionExt;
 use x11rb::xcb_ffi::XCBConnection;
 
 use crate::constants::FRAME_EXTENT_COUNT;
-use crate::constants::FRAME_EXTENT_TOP_INDEX;
 use crate::constants::FRAME_EXTENTS_ATOM_NAME;
 use crate::restore::MonitorScaleStrategy;
 use crate::restore::TargetPosition;
 use crate::restore::X11FrameCompensated;
+use crate::winit_access::LiveWinitWindows;
+use crate::winit_access::WinitWindowAccess;
+
+/// `_NET_FRAME_EXTENTS` of one window: the size of the frame the window manager draws
+/// around it on each side, in physical pixels.
+#[derive(Clone, Copy, Debug)]
+#[expect(
+    clippy::struct_field_names,
+    reason = "`physical_` marks the pixel space, as everywhere else in the crate"
+)]
+pub(crate) struct X11FrameExtents {
+    physical_left:   i32,
+    physical_right:  i32,
+    physical_top:    i32,
+    physical_bottom: i32,
+}
+
+/// Frame extents already queried, per window, with whether the window was decorated when
+/// they were queried.
+///
+/// Lets a later restore of the same window, e.g. an undo or a profile switch, compensate
+/// without querying the X server again. An entry is queried again once the window's
+/// decorations change, since the window manager then draws a different frame.
+#[derive(Resource, Default)]
+pub(crate) struct X11FrameExtentsCache {
+    by_entity: HashMap<Entity, (X11FrameExtents, bool)>,
+}
+
+impl X11FrameExtentsCache {
+    /// Frame extents of `entity`, from the cache or queried from the window manager.
+    /// `None` until the window manager sets `_NET_FRAME_EXTENTS`.
+    fn frame_extents(
+        &mut self,
+        winit_windows: &impl WinitWindowAccess,
+        entity: Entity,
+        window: &Window,
+        x11_connection: &mut X11Connection,
+    ) -> Option<X11FrameExtents> {
+        if let Some(&(frame_extents, decorations)) = self.by_entity.get(&entity)
+            && decorations == window.decorations
+        {
+            return Some(frame_extents);
+        }
+        let frame_extents = x11_connection.frame_extents(winit_windows, entity)?;
+        debug!(
+            "[W6] Frame extents of {entity:?}: left={} right={} top={} bottom={}",
+            frame_extents.physical_left,
+            frame_extents.physical_right,
+            frame_extents.physical_top,
+            frame_extents.physical_bottom
+        );
+        self.by_entity
+            .insert(entity, (frame_extents, window.decorations));
+        Some(frame_extents)
+    }
+
+    /// Title bar height of `entity` as last queried.
+    fn physical_top(&self, entity: Entity) -> Option<i32> {
+        self.by_entity
+            .get(&entity)
+            .map(|(frame_extents, _)| frame_extents.physical_top)
+    }
+}
 
 /// Subtract the X11 title bar height from `TargetPosition.physical_position`.
 ///
@@ -35,18 +96,31 @@ use crate::restore::X11FrameCompensated;
 /// silently and retries next frame.
 pub(crate) fn compensate_target_position(
     mut commands: Commands,
-    mut windows: Query<(Entity, &mut TargetPosition), Without<X11FrameCompensated>>,
+    mut windows: Query<(Entity, &mut TargetPosition, &Window), Without<X11FrameCompensated>>,
+    all_windows: Query<(), With<Window>>,
+    mut frame_extents_cache: ResMut<X11FrameExtentsCache>,
+    mut x11_connection: ResMut<X11Connection>,
     _: NonSendMarker,
 ) {
-    for (entity, mut target) in &mut windows {
+    frame_extents_cache
+        .by_entity
+        .retain(|entity, _| all_windows.contains(*entity));
+
+    for (entity, mut target, window) in &mut windows {
         let Some(physical_position) = target.physical_position else {
             commands.entity(entity).insert(X11FrameCompensated);
             continue;
         };
 
-        let Some(physical_frame_top) = query_frame_top_for_entity(entity) else {
+        let Some(frame_extents) = frame_extents_cache.frame_extents(
+            &LiveWinitWindows,
+            entity,
+            window,
+            &mut x11_connection,
+        ) else {
             continue;
         };
+        let physical_frame_top = frame_extents.physical_top;
 
         let physical_compensated = IVec2::new(
             physical_position.x,
@@ -56,20 +130,10 @@ pub(crate) fn compensate_target_position(
             "[W6] Compensating position: {physical_position:?} -> {physical_compensated:?} (physical_frame_top={physical_frame_top})"
         );
         target.physical_position = Some(physical_compensated);
-        commands
-            .entity(entity)
-            .insert((X11FrameCompensated, X11FrameTop(physical_frame_top)));
+        commands.entity(entity).insert(X11FrameCompensated);
     }
 }
 
-/// The `_NET_FRAME_EXTENTS` top (physical pixels) queried during W6 compensation.
-///
-/// Recorded so [`reapply_compensated_position`] knows the expected mapped-window
-/// readback (`compensated_position + frame_top`) without re-opening an X11 connection
-/// every frame. Present only on windows whose position was compensated.
-#[derive(Component)]
-pub(crate) struct X11FrameTop(i32);
-
 /// Re-issue the W6-compensated position once the window is mapped.
 ///
 /// During the initial restore the X11 WM can set a freshly-mapped window's `y`
@@ -79,14 +143,16 @@ pub(crate) struct X11FrameTop(i32);
 /// `set_outer_position` readback equals the requested position plus a stable
 /// `frame_top`. This system watches the settling
 /// window and, while the readback hasn't reached `compensated + frame_top`, re-issues the
-/// compensated position so the mapped window converges on the saved position.
+/// compensated position so the mapped window converges on the saved position. The frame
+/// top comes from [`X11FrameExtentsCache`], so the X server is not queried per frame.
 ///
 /// Same-scale (`ApplyUnchanged`) windowed restores only — cross-DPI strategies drive
 /// position through their own multi-phase move and tolerate the W6 offset.
 pub(crate) fn reapply_compensated_position(
-    mut windows: Query<(&TargetPosition, &X11FrameTop, &mut Window)>,
+    mut windows: Query<(Entity, &TargetPosition, &mut Window), With<X11FrameCompensated>>,
+    frame_extents_cache: Res<X11FrameExtentsCache>,
 ) {
-    for (target_position, physical_frame_top, mut window) in &mut windows {
+    for (entity, target_position, mut window) in &mut windows {
         if target_position.settle_state.is_none() {
             continue;
         }
@@ -99,12 +165,15 @@ pub(crate) fn reapply_compensated_position(
         let Some(physical_compensated) = target_position.physical_position else {
             continue;
         };
+        let Some(physical_frame_top) = frame_extents_cache.physical_top(entity) else {
+            continue;
+        };
         let WindowPosition::At(physical_actual) = window.position else {
             continue;
         };
         let physical_expected = IVec2::new(
             physical_compensated.x,
-            physical_compensated.y + physical_frame_top.0,
+            physical_compensated.y + physical_frame_top,
         );
         if physical_actual != physical_expected {
             debug!(
@@ -116,23 +185,66 @@ pub(crate) fn reapply_compensated_position(
     }
 }
 
-fn query_frame_top_for_entity(entity: Entity) -> Option<i32> {
-    WINIT_WINDOWS.with(|winit_windows| {
-        let winit_windows = winit_windows.borrow();
-        winit_windows.get_window(entity).and_then(|winit_window| {
-            let window_id = get_x11_window_id(&**winit_window)?;
-            query_frame_top(window_id)
-        })
-    })
+/// X11 connection for the frame extent queries, with the `_NET_FRAME_EXTENTS` atom.
+///
+/// The query retries every frame until the window manager sets the property, so the
+/// connection is opened on first use and kept across frames. It is dropped when a request
+/// fails on the connection itself and reopened by the next query, so a lost connection to
+/// the X server recovers.
+#[derive(Resource, Default)]
+pub(crate) struct X11Connection {
+    connection: Option<(XCBConnection, Atom)>,
 }
 
-fn query_frame_top(window_id: u32) -> Option<i32> {
-    let (conn, _) = XCBConnection::connect(None).ok()?;
+impl X11Connection {
+    /// `_NET_FRAME_EXTENTS` of the X11 window behind `entity`. `None` until the window
+    /// manager sets it, or when the X server cannot be reached.
+    fn frame_extents(
+        &mut self,
+        winit_windows: &impl WinitWindowAccess,
+        entity: Entity,
+    ) -> Option<X11FrameExtents> {
+        let window_id = winit_windows.x11_window_id(entity)?;
+        if self.connection.is_none() {
+            self.connection = connect();
+        }
+        let (conn, atom) = self.connection.as_ref()?;
+        match query_frame_extents(conn, *atom, window_id) {
+            Ok(frame_extents) => frame_extents,
+            Err(ReplyError::ConnectionError(error)) => {
+                warn!("[W6] X11 connection failed, reconnecting on the next query: {error}");
+                self.connection = None;
+                None
+            },
+            Err(ReplyError::X11Error(error)) => {
+                debug!("[W6] _NET_FRAME_EXTENTS query for {entity:?} failed: {error:?}");
+                None
+            },
+        }
+    }
+}
 
-    let atom_cookie = conn.intern_atom(false, FRAME_EXTENTS_ATOM_NAME).ok()?;
-    let atom = atom_cookie.reply().ok()?.atom;
+/// Connect to the X server and intern the `_NET_FRAME_EXTENTS` atom.
+fn connect() -> Option<(XCBConnection, Atom)> {
+    let (conn, _) = XCBConnection::connect(None)
+        .inspect_err(|error| warn!("[W6] Could not connect to the X server: {error}"))
+        .ok()?;
+    let atom = conn
+        .intern_atom(false, FRAME_EXTENTS_ATOM_NAME)
+        .ok()?
+        .reply()
+        .ok()?
+        .atom;
+    Some((conn, atom))
+}
 
-    let property_cookie = conn
+/// Read `_NET_FRAME_EXTENTS` of `window_id`. `Ok(None)` while the property is not set.
+fn query_frame_extents(
+    conn: &XCBConnection,
+    atom: Atom,
+    window_id: u32,
+) -> Result<Option<X11FrameExtents>, ReplyError> {
+    let property = conn
         .get_property(
             false,
             window_id,
@@ -140,23 +252,20 @@ fn query_frame_top(window_id: u32) -> Option<i32> {
             AtomEnum::CARDINAL,
             0,
             FRAME_EXTENT_COUNT,
-        )
-        .ok()?;
-    let property = property_cookie.reply().ok()?;
+        )?
+        .reply()?;
 
-    let values: Vec<u32> = property.value32()?.collect();
-    if values.len() >= FRAME_EXTENT_COUNT.to_usize() {
-        Some(values[FRAME_EXTENT_TOP_INDEX].to_i32())
-    } else {
-        None
-    }
-}
-
-fn get_x11_window_id<W: HasWindowHandle>(window: &W) -> Option<u32> {
-    let handle = window.window_handle().ok()?;
-    match handle.as_raw() {
-        RawWindowHandle::Xlib(h) => Some(h.window.to_u32()),
-        RawWindowHandle::Xcb(h) => Some(h.window.get()),
-        _ => None,
-    }
+    let Some(values) = property.value32() else {
+        return Ok(None);
+    };
+    let values: Vec<u32> = values.collect();
+    let [left, right, top, bottom, ..] = values[..] else {
+        return Ok(None);
+    };
+    Ok(Some(X11FrameExtents {
+        physical_left:   left.to_i32(),
+        physical_right:  right.to_i32(),
+        physical_top:    top.to_i32(),
+        physical_bottom: bottom.to_i32(),
+    }))
 }